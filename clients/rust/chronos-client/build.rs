@@ -4,6 +4,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "../../../proto/executor.proto",
         "../../../proto/worker.proto",
         "../../../proto/durable_engine.proto",
+        "../../../proto/observatory.proto",
     ];
 
     tonic_build::configure()
@@ -1,25 +1,27 @@
 use anyhow::Result;
-use chronos_client::{ChronosClient, ClientOptions};
+use chronos_client::{ChronosClient, ClientOptions, WorkflowBuilder};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Create a client with default options
     let client = ChronosClient::new(ClientOptions::default()).await?;
     
-    // Create a new workflow
-    let workflow = client.create_workflow("Example Workflow", "An example workflow").await?;
+    // Define the workflow and its tasks, then create everything in one request
+    let workflow = WorkflowBuilder::new("Example Workflow")
+        .description("An example workflow")
+        .task(
+            "Example Task",
+            "http",
+            serde_json::to_vec(&serde_json::json!({
+                "url": "https://example.com",
+                "method": "GET"
+            }))?,
+        )
+        .submit(&client)
+        .await?;
     println!("Created workflow: {}", workflow.id);
     
-    // Add a task to the workflow
-    let task = client.add_task(
-        &workflow.id,
-        "Example Task",
-        "http",
-        serde_json::to_vec(&serde_json::json!({
-            "url": "https://example.com",
-            "method": "GET"
-        }))?,
-    ).await?;
+    let task = &workflow.tasks[0];
     println!("Added task: {}", task.id);
     
     // Start the workflow
@@ -0,0 +1,129 @@
+use crate::ChronosError;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use tonic::metadata::MetadataValue;
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+use uuid::Uuid;
+
+/// Tokens are refreshed this long before they expire
+const REFRESH_MARGIN_SECS: i64 = 30;
+
+/// A bearer token and when it stops being valid
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    pub token: String,
+    /// `None` for tokens that never expire
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Source of refreshable credentials, e.g. an OAuth client-credentials flow
+#[async_trait]
+pub trait TokenProvider: Send + Sync {
+    async fn fetch_token(&self) -> Result<AccessToken, ChronosError>;
+}
+
+/// How the client authenticates its requests
+#[derive(Clone, Default)]
+pub enum Auth {
+    #[default]
+    None,
+    /// A static token sent as `authorization: Bearer <token>`
+    Bearer(String),
+    /// Tokens fetched from a provider and refreshed before expiry
+    Provider(Arc<dyn TokenProvider>),
+}
+
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Auth::None => write!(f, "None"),
+            Auth::Bearer(_) => write!(f, "Bearer(<redacted>)"),
+            Auth::Provider(_) => write!(f, "Provider(..)"),
+        }
+    }
+}
+
+/// Shared token cache used by every channel of a client
+#[derive(Clone)]
+pub(crate) struct AuthState {
+    auth: Auth,
+    current: Arc<RwLock<Option<AccessToken>>>,
+    tenant_id: Option<Uuid>,
+}
+
+impl AuthState {
+    pub(crate) fn new(auth: Auth, tenant_id: Option<Uuid>) -> Self {
+        let current = match &auth {
+            Auth::Bearer(token) => Some(AccessToken {
+                token: token.clone(),
+                expires_at: None,
+            }),
+            _ => None,
+        };
+
+        Self {
+            auth,
+            current: Arc::new(RwLock::new(current)),
+            tenant_id,
+        }
+    }
+
+    /// Fetch a new token from the provider if the cached one is missing or about to expire
+    pub(crate) async fn refresh_if_needed(&self) -> Result<(), ChronosError> {
+        let Auth::Provider(provider) = &self.auth else {
+            return Ok(());
+        };
+
+        let needs_refresh = match &*self.current.read().unwrap() {
+            Some(AccessToken { expires_at: Some(expires_at), .. }) => {
+                *expires_at - Duration::seconds(REFRESH_MARGIN_SECS) <= Utc::now()
+            }
+            Some(AccessToken { expires_at: None, .. }) => false,
+            None => true,
+        };
+
+        if needs_refresh {
+            let token = provider.fetch_token().await?;
+            *self.current.write().unwrap() = Some(token);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn interceptor(&self) -> AuthInterceptor {
+        AuthInterceptor {
+            current: self.current.clone(),
+            tenant_id: self.tenant_id,
+        }
+    }
+}
+
+/// Adds the `authorization` header from the client's cached token, and the
+/// `x-chronos-tenant-id` header if the client acts as a tenant
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    current: Arc<RwLock<Option<AccessToken>>>,
+    tenant_id: Option<Uuid>,
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(token) = &*self.current.read().unwrap() {
+            let value: MetadataValue<_> = format!("Bearer {}", token.token)
+                .parse()
+                .map_err(|_| Status::unauthenticated("Access token is not a valid header value"))?;
+            request.metadata_mut().insert("authorization", value);
+        }
+        if let Some(tenant_id) = self.tenant_id {
+            let value: MetadataValue<_> = tenant_id
+                .to_string()
+                .parse()
+                .expect("a hyphenated UUID is a valid header value");
+            request.metadata_mut().insert("x-chronos-tenant-id", value);
+        }
+        Ok(request)
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{ChronosError, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How long to wait between attempts, e.g. before a failed task is retried.
+///
+/// Serializes to the JSON the durable engine reads for a task's
+/// `retry_backoff`, such as
+/// `{"strategy": "exponential", "initial_ms": 1000, "multiplier": 2.0, "max_ms": 60000}`.
+/// Both sides are tested against `proto/testdata/retry_backoff.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    /// The same delay before every retry; zero retries straight away
+    Fixed { delay_ms: u64 },
+    /// `initial_ms` before the first retry and `step_ms` more before each
+    /// one after it, up to `max_ms`
+    Linear {
+        initial_ms: u64,
+        step_ms: u64,
+        #[serde(default = "default_max_ms")]
+        max_ms: u64,
+    },
+    /// `initial_ms` before the first retry, multiplied by `multiplier` for
+    /// each one after it, up to `max_ms`
+    Exponential {
+        initial_ms: u64,
+        #[serde(default = "default_multiplier")]
+        multiplier: f64,
+        #[serde(default = "default_max_ms")]
+        max_ms: u64,
+    },
+    /// A random delay between zero and what `Exponential` would wait, so
+    /// tasks that failed together don't all retry together
+    ExponentialWithJitter {
+        initial_ms: u64,
+        #[serde(default = "default_multiplier")]
+        multiplier: f64,
+        #[serde(default = "default_max_ms")]
+        max_ms: u64,
+    },
+}
+
+fn default_multiplier() -> f64 {
+    2.0
+}
+
+/// One hour, as on the engine
+fn default_max_ms() -> u64 {
+    3_600_000
+}
+
+impl Default for BackoffStrategy {
+    /// Retry straight away, the engine's default unless configured otherwise
+    fn default() -> Self {
+        BackoffStrategy::Fixed { delay_ms: 0 }
+    }
+}
+
+impl BackoffStrategy {
+    /// Doubling from `initial` up to `max`
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        BackoffStrategy::Exponential {
+            initial_ms: initial.as_millis() as u64,
+            multiplier: 2.0,
+            max_ms: max.as_millis() as u64,
+        }
+    }
+
+    /// Check the parameters make sense, as the engine does before accepting them
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| {
+            Err(ChronosError::Validation {
+                field: "retry_backoff".to_string(),
+                reason,
+            })
+        };
+        match *self {
+            BackoffStrategy::Fixed { .. } => Ok(()),
+            BackoffStrategy::Exponential { multiplier, .. } | BackoffStrategy::ExponentialWithJitter { multiplier, .. }
+                if !(multiplier.is_finite() && multiplier >= 1.0) =>
+            {
+                invalid(format!("multiplier must be at least 1, got {}", multiplier))
+            }
+            BackoffStrategy::Linear { initial_ms, max_ms, .. }
+            | BackoffStrategy::Exponential { initial_ms, max_ms, .. }
+            | BackoffStrategy::ExponentialWithJitter { initial_ms, max_ms, .. }
+                if max_ms < initial_ms =>
+            {
+                invalid(format!("max_ms ({}) is less than initial_ms ({})", max_ms, initial_ms))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// How long to wait before retry number `retry`, counting from 1
+    pub fn delay(&self, retry: u32) -> Duration {
+        let steps = retry.saturating_sub(1);
+        let millis = match *self {
+            BackoffStrategy::Fixed { delay_ms } => delay_ms,
+            BackoffStrategy::Linear { initial_ms, step_ms, max_ms } => initial_ms
+                .saturating_add(step_ms.saturating_mul(u64::from(steps)))
+                .min(max_ms),
+            BackoffStrategy::Exponential { initial_ms, multiplier, max_ms } => {
+                exponential(initial_ms, multiplier, max_ms, steps)
+            }
+            BackoffStrategy::ExponentialWithJitter { initial_ms, multiplier, max_ms } => {
+                let ceiling = exponential(initial_ms, multiplier, max_ms, steps);
+                (ceiling as f64 * unit_random()) as u64
+            }
+        };
+        Duration::from_millis(millis)
+    }
+
+    /// The delays before each retry in turn, e.g. to sleep between polls
+    pub fn delays(self) -> impl Iterator<Item = Duration> {
+        (1..=u32::MAX).map(move |retry| self.delay(retry))
+    }
+}
+
+/// `initial_ms * multiplier^steps`, capped at `max_ms`
+fn exponential(initial_ms: u64, multiplier: f64, max_ms: u64, steps: u32) -> u64 {
+    let millis = initial_ms as f64 * multiplier.powf(f64::from(steps));
+    // Casting saturates, and the cap is applied in floating point first so an
+    // overflowing power still ends up at max_ms
+    millis.min(max_ms as f64) as u64
+}
+
+/// A number in `[0, 1)`, from the random bits of a v4 UUID
+fn unit_random() -> f64 {
+    // The low 53 bits are clear of the version and variant bits, and fill an f64 mantissa
+    let bits = Uuid::new_v4().as_u128() as u64 & ((1 << 53) - 1);
+    bits as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// Strategies and the delays they give, shared with the engine's tests
+    const CASES: &str = include_str!("../../../../proto/testdata/retry_backoff.json");
+
+    fn cases(kind: &str) -> Vec<Value> {
+        let cases: Value = serde_json::from_str(CASES).unwrap();
+        cases[kind].as_array().unwrap().clone()
+    }
+
+    fn millis(values: &Value) -> Vec<u64> {
+        values.as_array().unwrap().iter().map(|ms| ms.as_u64().unwrap()).collect()
+    }
+
+    fn parse(json: &Value) -> Result<BackoffStrategy> {
+        let strategy: BackoffStrategy = serde_json::from_value(json.clone()).map_err(|e| ChronosError::Validation {
+            field: "retry_backoff".to_string(),
+            reason: e.to_string(),
+        })?;
+        strategy.validate()?;
+        Ok(strategy)
+    }
+
+    #[test]
+    fn delays_follow_the_strategy() {
+        for case in cases("valid").into_iter().filter(|case| case.get("delays_ms").is_some()) {
+            let expected = millis(&case["delays_ms"]);
+            let delays: Vec<u64> = parse(&case["json"])
+                .unwrap()
+                .delays()
+                .take(expected.len())
+                .map(|delay| delay.as_millis() as u64)
+                .collect();
+            assert_eq!(delays, expected, "{}", case["json"]);
+        }
+    }
+
+    #[test]
+    fn jitter_stays_between_zero_and_the_exponential_delay() {
+        for case in cases("valid").into_iter().filter(|case| case.get("max_delays_ms").is_some()) {
+            let strategy = parse(&case["json"]).unwrap();
+            for (retry, ceiling) in (1..).zip(millis(&case["max_delays_ms"])) {
+                let delays: Vec<u64> = (0..200).map(|_| strategy.delay(retry).as_millis() as u64).collect();
+                assert!(delays.iter().all(|&delay| delay <= ceiling), "retry {}: {:?}", retry, delays);
+                assert!(delays.iter().any(|&delay| delay != delays[0]), "retry {} never varied", retry);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        for json in cases("invalid") {
+            assert!(matches!(parse(&json), Err(ChronosError::Validation { .. })), "{}", json);
+        }
+        for json in cases("malformed") {
+            assert!(parse(&json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn sends_json_the_engine_reads() {
+        for case in cases("valid") {
+            let strategy = parse(&case["json"]).unwrap();
+            assert_eq!(parse(&serde_json::to_value(strategy).unwrap()).unwrap(), strategy);
+        }
+        assert_eq!(
+            serde_json::to_value(BackoffStrategy::exponential(Duration::from_secs(1), Duration::from_secs(60))).unwrap(),
+            serde_json::json!({"strategy": "exponential", "initial_ms": 1000, "multiplier": 2.0, "max_ms": 60000})
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{ChronosError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Key marking a JSON payload or result as a reference to an offloaded blob.
+///
+/// Matches the durable engine, which writes these references for values over
+/// its inline size limit.
+pub const BLOB_REF_KEY: &str = "$blob";
+
+/// External storage holding payloads and results too large to keep inline,
+/// typically the S3-compatible bucket configured on the durable engine
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+}
+
+impl fmt::Debug for dyn BlobStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BlobStore")
+    }
+}
+
+/// Where an offloaded value lives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlobRef {
+    pub key: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlobRefEnvelope {
+    #[serde(rename = "$blob")]
+    blob: BlobRef,
+}
+
+impl BlobRef {
+    /// The reference stored in place of the value
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&BlobRefEnvelope { blob: self.clone() }).unwrap_or_default()
+    }
+
+    /// Parse `data` as a blob reference, if that's what it is
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<BlobRefEnvelope>(data)
+            .ok()
+            .map(|envelope| envelope.blob)
+    }
+}
+
+/// Follow a blob reference if `data` is one, otherwise return it as is
+pub(crate) async fn resolve(store: Option<&dyn BlobStore>, data: &[u8]) -> Result<Vec<u8>> {
+    let Some(blob) = BlobRef::parse(data) else {
+        return Ok(data.to_vec());
+    };
+
+    let store = store.ok_or_else(|| {
+        ChronosError::TaskError(format!(
+            "Value was offloaded to blob {} but no blob store is configured",
+            blob.key
+        ))
+    })?;
+
+    store.get(&blob.key).await
+}
@@ -0,0 +1,162 @@
+use crate::dag;
+use crate::{ChronosApi, ChronosError, Result, Workflow};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Task type the durable engine runs itself to merge its dependencies' results
+pub const COLLECTOR_TASK_TYPE: &str = "collect";
+
+/// How a collector task added with [`WorkflowBuilder::collect`] combines results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMode {
+    /// A JSON array, in the order the upstream tasks were created
+    #[default]
+    Array,
+    /// A JSON object keyed by upstream task name
+    Object,
+}
+
+/// A task inside a [`WorkflowSpec`], referring to its dependencies by name
+#[derive(Debug, Clone)]
+pub struct TaskSpec {
+    pub name: String,
+    pub task_type: String,
+    pub payload: Vec<u8>,
+    /// Names of other tasks in the same spec that must complete first
+    pub depends_on: Vec<String>,
+}
+
+/// A validated workflow definition ready to be submitted in one request
+#[derive(Debug, Clone)]
+pub struct WorkflowSpec {
+    pub name: String,
+    pub description: String,
+    pub tasks: Vec<TaskSpec>,
+}
+
+/// Fluent construction of a workflow and its task DAG.
+///
+/// ```no_run
+/// # async fn run(client: &chronos_client::ChronosClient) -> anyhow::Result<()> {
+/// use chronos_client::WorkflowBuilder;
+///
+/// let workflow = WorkflowBuilder::new("Nightly ETL")
+///     .description("Extract, transform and load")
+///     .task("extract", "http", b"{}".to_vec())
+///     .task_with_dependencies("load", "http", b"{}".to_vec(), &["extract"])
+///     .submit(client)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WorkflowBuilder {
+    name: String,
+    description: String,
+    tasks: Vec<TaskSpec>,
+}
+
+impl WorkflowBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            tasks: Vec::new(),
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Add a task with no dependencies
+    pub fn task(self, name: impl Into<String>, task_type: impl Into<String>, payload: Vec<u8>) -> Self {
+        self.task_with_dependencies(name, task_type, payload, &[])
+    }
+
+    /// Add a task that runs after the named tasks have completed
+    pub fn task_with_dependencies(
+        mut self,
+        name: impl Into<String>,
+        task_type: impl Into<String>,
+        payload: Vec<u8>,
+        depends_on: &[&str],
+    ) -> Self {
+        self.tasks.push(TaskSpec {
+            name: name.into(),
+            task_type: task_type.into(),
+            payload,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Add a task that waits for the named tasks and completes with their
+    /// results merged, for the aggregate step of a fan-out.
+    ///
+    /// The durable engine runs it without a worker. Results offloaded to the
+    /// blob store appear as their blob references.
+    ///
+    /// ```
+    /// use chronos_client::builder::MergeMode;
+    /// use chronos_client::WorkflowBuilder;
+    ///
+    /// let spec = WorkflowBuilder::new("Fan out")
+    ///     .task("shard-0", "http", b"{}".to_vec())
+    ///     .task("shard-1", "http", b"{}".to_vec())
+    ///     .collect("merge", MergeMode::Array, &["shard-0", "shard-1"])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(spec.tasks[2].depends_on, ["shard-0", "shard-1"]);
+    /// ```
+    pub fn collect(self, name: impl Into<String>, merge: MergeMode, from: &[&str]) -> Self {
+        let payload = serde_json::json!({ "merge": merge }).to_string().into_bytes();
+        self.task_with_dependencies(name, COLLECTOR_TASK_TYPE, payload, from)
+    }
+
+    /// Validate task names and dependency references, rejecting dependency
+    /// cycles since no task in one could ever start
+    pub fn build(self) -> Result<WorkflowSpec, ChronosError> {
+        let mut names = HashSet::new();
+        for task in &self.tasks {
+            if !names.insert(task.name.as_str()) {
+                return Err(ChronosError::WorkflowError(format!(
+                    "Duplicate task name: {}",
+                    task.name
+                )));
+            }
+        }
+
+        for task in &self.tasks {
+            if let Some(missing) = task.depends_on.iter().find(|d| !names.contains(d.as_str())) {
+                return Err(ChronosError::WorkflowError(format!(
+                    "Task {} depends on unknown task {}",
+                    task.name, missing
+                )));
+            }
+        }
+
+        let graph: Vec<(&str, Vec<&str>)> = self
+            .tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.depends_on.iter().map(String::as_str).collect()))
+            .collect();
+        if let Err(cycle) = dag::topological_sort(&graph) {
+            return Err(ChronosError::WorkflowError(format!("Dependency cycle: {}", cycle)));
+        }
+
+        Ok(WorkflowSpec {
+            name: self.name,
+            description: self.description,
+            tasks: self.tasks,
+        })
+    }
+
+    /// Validate and create the workflow with all of its tasks in a single request
+    pub async fn submit<C: ChronosApi + ?Sized>(self, client: &C) -> Result<Workflow> {
+        let spec = self.build()?;
+        client.submit_workflow(&spec).await
+    }
+}
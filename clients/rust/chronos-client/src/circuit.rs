@@ -0,0 +1,164 @@
+use crate::metrics::{BackendRecorder, ClientMetrics};
+use crate::{ChronosError, Result};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// When a backend's circuit breaker opens and how long it stays open
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive connection failures that open the circuit
+    pub failure_threshold: u32,
+    /// How long calls are rejected before a single probe is let through
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// Cooldown elapsed and one probe call is in flight
+    HalfOpen,
+}
+
+/// Fails calls fast while a backend is down instead of letting each one
+/// wait out its own timeout, and counts the calls it admits
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<State>,
+    pub(crate) metrics: BackendRecorder,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State::Closed { failures: 0 }),
+            metrics: BackendRecorder::default(),
+        }
+    }
+
+    /// Admit a call, or reject it with a "circuit open" `ConnectionError`.
+    ///
+    /// The caller reports the outcome through the returned [`CallGuard`].
+    pub(crate) fn try_acquire(&self) -> Result<CallGuard<'_>, ChronosError> {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            State::Closed { .. } => {}
+            State::Open { until } if Instant::now() >= until => *state = State::HalfOpen,
+            State::Open { .. } | State::HalfOpen => {
+                let error = ChronosError::ConnectionError {
+                    message: "circuit open".to_string(),
+                    source: None,
+                };
+                self.metrics.on_rejected(&error);
+                return Err(error);
+            }
+        }
+
+        self.metrics.on_start();
+        Ok(CallGuard {
+            breaker: self,
+            started: Instant::now(),
+            recorded: false,
+        })
+    }
+
+    fn on_success(&self) {
+        *self.state.lock().unwrap() = State::Closed { failures: 0 };
+    }
+
+    fn on_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        let failures = match *state {
+            State::Closed { failures } => failures + 1,
+            // A failed probe reopens immediately
+            State::HalfOpen | State::Open { .. } => self.config.failure_threshold,
+        };
+
+        *state = if failures >= self.config.failure_threshold {
+            State::Open { until: Instant::now() + self.config.cooldown }
+        } else {
+            State::Closed { failures }
+        };
+    }
+
+    /// A half-open probe that never reported back must not wedge the breaker
+    fn on_abandoned(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let State::HalfOpen = *state {
+            *state = State::Open { until: Instant::now() };
+        }
+    }
+}
+
+/// An admitted call; report its outcome with [`CallGuard::finish`]
+pub(crate) struct CallGuard<'a> {
+    breaker: &'a CircuitBreaker,
+    started: Instant,
+    recorded: bool,
+}
+
+impl CallGuard<'_> {
+    /// Record the call's result and pass it through.
+    ///
+    /// Only connection-level failures count against the backend; validation
+    /// and other application errors mean it is up.
+    pub(crate) fn finish<T>(mut self, result: Result<T>) -> Result<T> {
+        self.recorded = true;
+        match &result {
+            Err(e) if is_connection_failure(e) => self.breaker.on_failure(),
+            _ => self.breaker.on_success(),
+        }
+        self.breaker.metrics.on_finish(self.started.elapsed(), result.as_ref().err());
+        result
+    }
+}
+
+impl Drop for CallGuard<'_> {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.breaker.on_abandoned();
+            self.breaker.metrics.on_abandoned();
+        }
+    }
+}
+
+fn is_connection_failure(error: &ChronosError) -> bool {
+    matches!(error, ChronosError::ConnectionError { .. } | ChronosError::Timeout(_))
+}
+
+/// One breaker per backend the client sends requests to
+#[derive(Debug)]
+pub(crate) struct ChannelBreakers {
+    pub(crate) scheduler: CircuitBreaker,
+    pub(crate) durable_engine: CircuitBreaker,
+    pub(crate) observatory: CircuitBreaker,
+}
+
+impl ChannelBreakers {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            scheduler: CircuitBreaker::new(config),
+            durable_engine: CircuitBreaker::new(config),
+            observatory: CircuitBreaker::new(config),
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> ClientMetrics {
+        ClientMetrics {
+            scheduler: self.scheduler.metrics.snapshot(),
+            durable_engine: self.durable_engine.metrics.snapshot(),
+            observatory: self.observatory.metrics.snapshot(),
+        }
+    }
+}
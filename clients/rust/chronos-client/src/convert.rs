@@ -0,0 +1,188 @@
+//! Conversions between the generated proto messages in [`crate::proto`] and
+//! the client's public types.
+//!
+//! Every RPC maps messages through these impls rather than by hand, so
+//! timestamps, task states, payload formats and parameter maps are translated
+//! the same way everywhere.
+
+use crate::proto::{durable_engine, scheduler};
+use crate::{ChronosError, PayloadFormat, Result, Task, TaskSpec, TaskStatus, Workflow};
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashMap;
+
+impl TaskStatus {
+    /// The status for a durable engine task state, e.g. `TIMED_OUT`.
+    ///
+    /// The engine's states are finer grained: RETRYING counts as `Pending`,
+    /// since the task is waiting to run again, and TIMED_OUT as `Failed`.
+    pub fn from_state(state: &str) -> Result<Self> {
+        match state {
+            "QUEUED" | "RETRYING" => Ok(TaskStatus::Pending),
+            "RUNNING" => Ok(TaskStatus::Running),
+            "COMPLETED" => Ok(TaskStatus::Completed),
+            "FAILED" | "TIMED_OUT" => Ok(TaskStatus::Failed),
+            "CANCELLED" => Ok(TaskStatus::Cancelled),
+            other => Err(invalid("state", format!("unknown task state {:?}", other))),
+        }
+    }
+
+    /// The durable engine task state this status is sent as
+    pub fn as_state(self) -> &'static str {
+        match self {
+            TaskStatus::Pending => "QUEUED",
+            TaskStatus::Running => "RUNNING",
+            TaskStatus::Completed => "COMPLETED",
+            TaskStatus::Failed => "FAILED",
+            TaskStatus::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+/// Parse the `payload_format` field; empty means JSON, as sent by older servers
+fn payload_format(value: &str) -> Result<PayloadFormat> {
+    match value {
+        "" | "JSON" => Ok(PayloadFormat::Json),
+        "MSGPACK" => Ok(PayloadFormat::MessagePack),
+        "PROTOBUF" => Ok(PayloadFormat::Protobuf),
+        other => Err(invalid("payload_format", format!("unknown payload format {:?}", other))),
+    }
+}
+
+pub(crate) fn from_timestamp(timestamp: Option<prost_types::Timestamp>) -> Option<DateTime<Utc>> {
+    let timestamp = timestamp?;
+    Utc.timestamp_opt(timestamp.seconds, timestamp.nanos.max(0) as u32).single()
+}
+
+/// Encode a JSON object payload as a proto parameter map. String values are
+/// kept as they are, anything else is sent as its JSON text.
+pub(crate) fn payload_to_parameters(payload: &[u8]) -> Result<HashMap<String, String>> {
+    if payload.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let object: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(payload)
+        .map_err(|e| invalid("payload", format!("must be a JSON object to send as parameters: {}", e)))?;
+
+    Ok(object
+        .into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect())
+}
+
+/// The JSON object payload for a proto parameter map
+pub(crate) fn parameters_to_payload(parameters: &HashMap<String, String>) -> Vec<u8> {
+    // A map of strings always serializes
+    serde_json::to_vec(parameters).unwrap_or_default()
+}
+
+/// Timestamps a server must always set; missing ones fall back to the epoch
+fn required_timestamp(timestamp: Option<prost_types::Timestamp>) -> DateTime<Utc> {
+    from_timestamp(timestamp).unwrap_or_default()
+}
+
+fn invalid(field: &str, reason: String) -> ChronosError {
+    ChronosError::Validation {
+        field: field.to_string(),
+        reason,
+    }
+}
+
+impl TryFrom<durable_engine::Task> for Task {
+    type Error = ChronosError;
+
+    fn try_from(task: durable_engine::Task) -> Result<Self> {
+        let payload_format = payload_format(&task.payload_format)?;
+        let payload = match payload_format {
+            PayloadFormat::Json => parameters_to_payload(&task.parameters),
+            _ => task.payload,
+        };
+
+        Ok(Task {
+            id: task.id,
+            workflow_id: task.workflow_id,
+            name: task.name,
+            task_type: task.task_type,
+            status: TaskStatus::from_state(&task.state)?,
+            payload,
+            result: Some(task.result).filter(|result| !result.is_empty()).map(String::into_bytes),
+            created_at: required_timestamp(task.created_at),
+            updated_at: required_timestamp(task.updated_at),
+            started_at: from_timestamp(task.started_at),
+            completed_at: from_timestamp(task.completed_at),
+            // The engine reports dependencies and schedules separately from the task
+            depends_on: Vec::new(),
+            timeout_seconds: u32::try_from(task.timeout_seconds).ok().filter(|secs| *secs > 0),
+            tags: task.tags,
+            scheduled_at: None,
+            payload_format,
+            requirements: task.requirements,
+            waiting_reason: Some(task.waiting_reason).filter(|reason| !reason.is_empty()),
+            result_checksum: Some(task.result_checksum).filter(|checksum| !checksum.is_empty()),
+        })
+    }
+}
+
+impl TryFrom<&TaskSpec> for scheduler::Task {
+    type Error = ChronosError;
+
+    /// `depends_on` keeps the spec's task names, which the scheduler resolves
+    /// within the same request
+    fn try_from(spec: &TaskSpec) -> Result<Self> {
+        Ok(scheduler::Task {
+            id: String::new(),
+            name: spec.name.clone(),
+            description: String::new(),
+            task_type: spec.task_type.clone(),
+            parameters: payload_to_parameters(&spec.payload)?,
+            timeout_seconds: 0,
+            max_retries: 0,
+            depends_on: spec.depends_on.clone(),
+        })
+    }
+}
+
+impl From<scheduler::Workflow> for Workflow {
+    /// Tasks of a workflow definition haven't run, so they are `Pending`
+    fn from(workflow: scheduler::Workflow) -> Self {
+        let created_at = required_timestamp(workflow.created_at);
+        let updated_at = required_timestamp(workflow.updated_at);
+        let tasks = workflow
+            .tasks
+            .into_iter()
+            .map(|task| Task {
+                id: task.id,
+                workflow_id: workflow.id.clone(),
+                name: task.name,
+                task_type: task.task_type,
+                status: TaskStatus::Pending,
+                payload: parameters_to_payload(&task.parameters),
+                result: None,
+                created_at,
+                updated_at,
+                started_at: None,
+                completed_at: None,
+                depends_on: task.depends_on,
+                timeout_seconds: u32::try_from(task.timeout_seconds).ok().filter(|secs| *secs > 0),
+                tags: HashMap::new(),
+                scheduled_at: None,
+                payload_format: PayloadFormat::Json,
+                requirements: HashMap::new(),
+                waiting_reason: None,
+                result_checksum: None,
+            })
+            .collect();
+
+        Workflow {
+            id: workflow.id,
+            name: workflow.name,
+            description: workflow.description,
+            tasks,
+            created_at,
+            updated_at,
+            external_id: Some(workflow.external_id).filter(|id| !id.is_empty()),
+            replayed_from: Some(workflow.replayed_from).filter(|id| !id.is_empty()),
+        }
+    }
+}
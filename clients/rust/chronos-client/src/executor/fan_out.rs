@@ -0,0 +1,43 @@
+use crate::{ChronosError, Result, TaskSpec};
+
+/// Key of a task result that asks the engine for subtasks instead of
+/// completing the task
+pub const FAN_OUT_KEY: &str = "$fanout";
+
+/// The result for a [`TaskExecutor`](crate::TaskExecutor) to return when the
+/// work it has to do is only known once it runs, e.g. one subtask per file in
+/// a directory.
+///
+/// The engine creates `children` in the task's workflow and completes the task
+/// once they have all completed, with their results as a JSON array in the
+/// same order. `depends_on` may only name subtasks listed earlier, and an
+/// empty `name` defaults to `<task name>[<index>]`. A subtask may fan out in
+/// turn.
+///
+/// Payloads must be JSON objects, or empty for no parameters. Only the
+/// engine expands fan-outs; the in-process workflow executors keep the result
+/// as returned.
+pub fn fan_out(children: &[TaskSpec]) -> Result<Vec<u8>> {
+    let children = children
+        .iter()
+        .map(|child| {
+            let parameters = match child.payload.as_slice() {
+                [] => serde_json::Value::Object(serde_json::Map::new()),
+                payload => serde_json::from_slice(payload).map_err(|e| ChronosError::Validation {
+                    field: "payload".to_string(),
+                    reason: format!("subtask {} must have a JSON object payload: {}", child.name, e),
+                })?,
+            };
+            Ok(serde_json::json!({
+                "name": child.name,
+                "task_type": child.task_type,
+                "parameters": parameters,
+                "depends_on": child.depends_on,
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut result = serde_json::Map::new();
+    result.insert(FAN_OUT_KEY.to_string(), serde_json::Value::Array(children));
+    Ok(serde_json::to_vec(&result)?)
+}
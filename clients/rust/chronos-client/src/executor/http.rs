@@ -0,0 +1,117 @@
+use super::params::TaskParams;
+use crate::{ChronosError, Result, Task, TaskExecutor};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::redirect::Policy;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Built-in executor for the `http` task type.
+///
+/// The task payload is a JSON object of [`HttpTaskParams`](super::HttpTaskParams):
+///
+/// ```json
+/// {
+///   "url": "https://example.com/hook",
+///   "method": "POST",
+///   "headers": {"x-api-key": "..."},
+///   "body": {"hello": "world"},
+///   "expected_status": [404],
+///   "timeout_seconds": 10,
+///   "follow_redirects": false
+/// }
+/// ```
+///
+/// Only `url` is required. The response body becomes the task result. Any
+/// status outside 2xx and `expected_status` is an error so the engine retries.
+#[derive(Debug, Clone)]
+pub struct HttpTaskExecutor {
+    client: reqwest::Client,
+    no_redirect_client: reqwest::Client,
+}
+
+impl HttpTaskExecutor {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            no_redirect_client: reqwest::Client::builder()
+                .redirect(Policy::none())
+                .build()
+                .expect("static reqwest client configuration is valid"),
+        }
+    }
+}
+
+impl Default for HttpTaskExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn build_headers(headers: &HashMap<String, String>) -> Result<HeaderMap, ChronosError> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| ChronosError::TaskError(format!("Invalid header name: {}", name)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| ChronosError::TaskError(format!("Invalid value for header {}", name)))?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+#[async_trait]
+impl TaskExecutor for HttpTaskExecutor {
+    async fn execute(&self, task: &Task) -> Result<Vec<u8>> {
+        let TaskParams::Http(payload) = TaskParams::from_task(task)? else {
+            return Err(ChronosError::TaskError(format!(
+                "The http executor can't run {} tasks",
+                task.task_type
+            )));
+        };
+
+        let method = reqwest::Method::from_bytes(payload.method.to_uppercase().as_bytes())
+            .map_err(|_| ChronosError::TaskError(format!("Invalid HTTP method: {}", payload.method)))?;
+
+        let client = if payload.follow_redirects {
+            &self.client
+        } else {
+            &self.no_redirect_client
+        };
+
+        let mut request = client
+            .request(method, &payload.url)
+            .headers(build_headers(&payload.headers)?);
+
+        if let Some(timeout) = payload.timeout_seconds {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+
+        request = match payload.body {
+            Some(serde_json::Value::String(body)) => request.body(body),
+            Some(body) => request
+                .header(CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(&body)?),
+            None => request,
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ChronosError::TaskError(format!("Request to {} failed: {}", payload.url, e)))?;
+
+        let status = response.status();
+        if !status.is_success() && !payload.expected_status.contains(&status.as_u16()) {
+            return Err(ChronosError::TaskError(format!(
+                "Request to {} returned {}",
+                payload.url, status
+            )));
+        }
+
+        let body = response.bytes().await.map_err(|e| {
+            ChronosError::TaskError(format!("Failed to read response from {}: {}", payload.url, e))
+        })?;
+
+        Ok(body.to_vec())
+    }
+}
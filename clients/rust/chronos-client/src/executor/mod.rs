@@ -0,0 +1,119 @@
+use crate::{ChronosError, Result, Task, TaskExecutor};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+mod fan_out;
+mod http;
+mod params;
+mod workflow;
+
+pub use fan_out::{fan_out, FAN_OUT_KEY};
+pub use http::HttpTaskExecutor;
+pub use params::{HttpTaskParams, TaskParams, HTTP_TASK_TYPE};
+pub use workflow::{ParallelWorkflowExecutor, SequentialWorkflowExecutor, WorkflowRun};
+
+/// Routes tasks to the [`TaskExecutor`] registered for their `task_type`.
+///
+/// Clones share their concurrency limits, so a limit holds across every
+/// clone dispatching tasks.
+#[derive(Clone, Default)]
+pub struct TaskExecutorRegistry {
+    executors: HashMap<String, Arc<dyn TaskExecutor + Send + Sync>>,
+    default_timeout: Option<Duration>,
+    /// Permits per task type with a concurrency limit; other types are unlimited
+    concurrency: HashMap<String, Arc<Semaphore>>,
+}
+
+impl TaskExecutorRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the built-in executors (`http`) already registered
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register(HTTP_TASK_TYPE, Arc::new(HttpTaskExecutor::new()));
+        registry
+    }
+
+    /// Time limit for tasks that don't set `timeout_seconds`; without one they may run indefinitely
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Run at most `limit` tasks of `task_type` at once, so a burst of one
+    /// type can't take every slot of the worker from the others. Further
+    /// tasks of the type wait in `dispatch` for a running one to finish.
+    ///
+    /// # Panics
+    ///
+    /// If `limit` is zero.
+    pub fn with_concurrency_limit(mut self, task_type: impl Into<String>, limit: usize) -> Self {
+        assert!(limit > 0, "concurrency limit must be positive");
+        self.concurrency.insert(task_type.into(), Arc::new(Semaphore::new(limit)));
+        self
+    }
+
+    /// [`with_concurrency_limit`](Self::with_concurrency_limit) for each
+    /// entry, e.g. loaded from configuration as `{"http": 5, "render": 2}`
+    pub fn with_concurrency_limits<K: Into<String>>(self, limits: impl IntoIterator<Item = (K, usize)>) -> Self {
+        limits
+            .into_iter()
+            .fold(self, |registry, (task_type, limit)| registry.with_concurrency_limit(task_type, limit))
+    }
+
+    /// Register an executor, replacing any existing one for the same task type
+    pub fn register(&mut self, task_type: impl Into<String>, executor: Arc<dyn TaskExecutor + Send + Sync>) {
+        self.executors.insert(task_type.into(), executor);
+    }
+
+    /// Task types with a registered executor
+    pub fn task_types(&self) -> impl Iterator<Item = &str> {
+        self.executors.keys().map(String::as_str)
+    }
+
+    /// Run the task with the executor registered for its type.
+    ///
+    /// Waits first for a permit if the type has a concurrency limit. An
+    /// attempt that outlives the task's `timeout_seconds` (or the registry
+    /// default) once running is dropped and reported as
+    /// `ChronosError::Timeout`. A worker reports it to the engine with
+    /// FailTask and `timed_out` set, so the timeout is recorded at once
+    /// rather than when the engine's reconciliation notices.
+    pub async fn dispatch(&self, task: &Task) -> Result<Vec<u8>> {
+        let executor = self.executors.get(&task.task_type).ok_or_else(|| {
+            ChronosError::TaskError(format!("No executor registered for task type: {}", task.task_type))
+        })?;
+
+        // Held until the attempt finishes or times out
+        let _permit = match self.concurrency.get(&task.task_type) {
+            Some(permits) => Some(permits.acquire().await.expect("concurrency semaphores are never closed")),
+            None => None,
+        };
+
+        let timeout = task
+            .timeout_seconds
+            .map(|secs| Duration::from_secs(secs.into()))
+            .or(self.default_timeout);
+
+        match timeout {
+            Some(limit) => tokio::time::timeout(limit, executor.execute(task))
+                .await
+                .map_err(|_| ChronosError::Timeout(format!("Task {} exceeded its {:?} timeout", task.id, limit)))?,
+            None => executor.execute(task).await,
+        }
+    }
+}
+
+/// Lets a registry be handed to the workflow executors to run mixed task types
+#[async_trait]
+impl TaskExecutor for TaskExecutorRegistry {
+    async fn execute(&self, task: &Task) -> Result<Vec<u8>> {
+        self.dispatch(task).await
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{ChronosError, Result, Task};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Task type of the built-in [`HttpTaskExecutor`](super::HttpTaskExecutor)
+pub const HTTP_TASK_TYPE: &str = "http";
+
+/// Parameters of an `http` task. Only `url` is required.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HttpTaskParams {
+    pub url: String,
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Sent verbatim when a string, otherwise serialized as JSON
+    #[serde(default)]
+    pub body: Option<Value>,
+    /// Statuses accepted in addition to 2xx
+    #[serde(default)]
+    pub expected_status: Vec<u16>,
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+    #[serde(default = "default_follow_redirects")]
+    pub follow_redirects: bool,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_follow_redirects() -> bool {
+    true
+}
+
+/// A task's parameters, typed for the built-in task types.
+///
+/// Executors match on the variant instead of picking fields out of JSON
+/// themselves; task types the client doesn't know keep their parameters as
+/// `Custom`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskParams {
+    Http(HttpTaskParams),
+    Custom(Value),
+}
+
+impl TaskParams {
+    /// Type `parameters` according to `task_type`.
+    ///
+    /// Fails with `ChronosError::Validation` naming the offending parameter,
+    /// e.g. `parameters.headers.accept`, when a built-in type's parameters
+    /// don't match its struct.
+    pub fn parse(task_type: &str, parameters: Value) -> Result<Self> {
+        match task_type {
+            HTTP_TASK_TYPE => Ok(TaskParams::Http(typed(task_type, parameters)?)),
+            _ => Ok(TaskParams::Custom(parameters)),
+        }
+    }
+
+    /// Decode a task's payload in its `payload_format` and type it; an empty
+    /// payload counts as no parameters
+    pub fn from_task(task: &Task) -> Result<Self> {
+        let parameters = match task.payload.as_slice() {
+            [] => Value::Object(serde_json::Map::new()),
+            payload => task.payload_format.decode(payload)?,
+        };
+        Self::parse(&task.task_type, parameters)
+    }
+}
+
+/// Deserialize a built-in type's parameters, keeping the path to the field that failed
+fn typed<T: DeserializeOwned>(task_type: &str, parameters: Value) -> Result<T> {
+    serde_path_to_error::deserialize(parameters).map_err(|e| {
+        let field = match e.path().to_string().as_str() {
+            "." => "parameters".to_string(),
+            path => format!("parameters.{}", path),
+        };
+        ChronosError::Validation {
+            field,
+            reason: format!("{} (task type {})", e.into_inner(), task_type),
+        }
+    })
+}
@@ -0,0 +1,218 @@
+use crate::dag;
+use crate::{ChronosError, Result, Task, TaskExecutor, Workflow, WorkflowExecutor};
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+/// Outcome of running a workflow in-process, keyed by task id
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowRun {
+    pub results: HashMap<String, Vec<u8>>,
+    pub failures: HashMap<String, String>,
+    /// Tasks never run because a dependency failed or the run stopped early
+    pub skipped: Vec<String>,
+}
+
+impl WorkflowRun {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty() && self.skipped.is_empty()
+    }
+
+    fn into_result(self) -> Result<()> {
+        match self.failures.iter().next() {
+            Some((task_id, error)) => {
+                Err(ChronosError::WorkflowError(format!("Task {} failed: {}", task_id, error)))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Runs a workflow's tasks one at a time in dependency order
+pub struct SequentialWorkflowExecutor {
+    executor: Arc<dyn TaskExecutor + Send + Sync>,
+    fail_fast: bool,
+}
+
+impl SequentialWorkflowExecutor {
+    /// Stops at the first failed task by default
+    pub fn new(executor: Arc<dyn TaskExecutor + Send + Sync>) -> Self {
+        Self { executor, fail_fast: true }
+    }
+
+    /// Whether to stop at the first failure, or keep running tasks that don't depend on it
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Run every task and report per-task results
+    pub async fn run(&self, workflow: &Workflow) -> Result<WorkflowRun> {
+        let mut graph = TaskGraph::new(&workflow.tasks)?;
+        let mut run = WorkflowRun::default();
+
+        while let Some(index) = graph.next_ready() {
+            let task = &workflow.tasks[index];
+            match self.executor.execute(task).await {
+                Ok(output) => {
+                    run.results.insert(task.id.clone(), output);
+                    graph.complete(index);
+                }
+                Err(e) => {
+                    // Dependents of a failed task never become ready
+                    run.failures.insert(task.id.clone(), e.to_string());
+                    if self.fail_fast {
+                        break;
+                    }
+                }
+            }
+        }
+
+        run.skipped = unfinished(&workflow.tasks, &run);
+        Ok(run)
+    }
+}
+
+#[async_trait]
+impl WorkflowExecutor for SequentialWorkflowExecutor {
+    async fn execute(&self, workflow: &Workflow) -> Result<()> {
+        self.run(workflow).await?.into_result()
+    }
+}
+
+/// Runs independent branches of a workflow concurrently, at most
+/// `max_concurrency` tasks at a time
+pub struct ParallelWorkflowExecutor {
+    executor: Arc<dyn TaskExecutor + Send + Sync>,
+    max_concurrency: usize,
+    fail_fast: bool,
+}
+
+impl ParallelWorkflowExecutor {
+    /// Stops scheduling new tasks after the first failure by default
+    pub fn new(executor: Arc<dyn TaskExecutor + Send + Sync>, max_concurrency: usize) -> Self {
+        Self {
+            executor,
+            max_concurrency: max_concurrency.max(1),
+            fail_fast: true,
+        }
+    }
+
+    /// Whether to stop at the first failure, or keep running tasks that don't depend on it.
+    ///
+    /// Tasks already in flight when a failure occurs are always allowed to finish.
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Run every task and report per-task results
+    pub async fn run(&self, workflow: &Workflow) -> Result<WorkflowRun> {
+        let mut graph = TaskGraph::new(&workflow.tasks)?;
+        let mut run = WorkflowRun::default();
+        let mut in_flight = FuturesUnordered::new();
+        let mut stopped = false;
+
+        loop {
+            while !stopped && in_flight.len() < self.max_concurrency {
+                let Some(index) = graph.next_ready() else { break };
+                let task = &workflow.tasks[index];
+                in_flight.push(async move { (index, self.executor.execute(task).await) });
+            }
+
+            let Some((index, outcome)) = in_flight.next().await else { break };
+            let task_id = workflow.tasks[index].id.clone();
+            match outcome {
+                Ok(output) => {
+                    run.results.insert(task_id, output);
+                    graph.complete(index);
+                }
+                Err(e) => {
+                    run.failures.insert(task_id, e.to_string());
+                    stopped |= self.fail_fast;
+                }
+            }
+        }
+
+        run.skipped = unfinished(&workflow.tasks, &run);
+        Ok(run)
+    }
+}
+
+#[async_trait]
+impl WorkflowExecutor for ParallelWorkflowExecutor {
+    async fn execute(&self, workflow: &Workflow) -> Result<()> {
+        self.run(workflow).await?.into_result()
+    }
+}
+
+/// Dependency bookkeeping over a workflow's tasks, by index
+struct TaskGraph {
+    dependents: Vec<Vec<usize>>,
+    pending: Vec<usize>,
+    ready: VecDeque<usize>,
+}
+
+impl TaskGraph {
+    /// Rejects unknown dependencies and cycles before anything runs
+    fn new(tasks: &[Task]) -> Result<Self> {
+        let index_by_id: HashMap<&str, usize> = tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| (task.id.as_str(), index))
+            .collect();
+
+        let mut dependents = vec![Vec::new(); tasks.len()];
+        let mut pending = vec![0; tasks.len()];
+        for (index, task) in tasks.iter().enumerate() {
+            for dependency in &task.depends_on {
+                let &dependency_index = index_by_id.get(dependency.as_str()).ok_or_else(|| {
+                    ChronosError::WorkflowError(format!(
+                        "Task {} depends on unknown task {}",
+                        task.id, dependency
+                    ))
+                })?;
+                dependents[dependency_index].push(index);
+                pending[index] += 1;
+            }
+        }
+
+        let ids: Vec<(&str, Vec<&str>)> = tasks
+            .iter()
+            .map(|task| (task.id.as_str(), task.depends_on.iter().map(String::as_str).collect()))
+            .collect();
+        if let Err(cycle) = dag::topological_sort(&ids) {
+            return Err(ChronosError::WorkflowError(format!("Dependency cycle: {}", cycle)));
+        }
+
+        Ok(Self {
+            ready: (0..tasks.len()).filter(|&index| pending[index] == 0).collect(),
+            dependents,
+            pending,
+        })
+    }
+
+    fn next_ready(&mut self) -> Option<usize> {
+        self.ready.pop_front()
+    }
+
+    /// Release dependents whose last outstanding dependency was this task
+    fn complete(&mut self, index: usize) {
+        for &dependent in &self.dependents[index] {
+            self.pending[dependent] -= 1;
+            if self.pending[dependent] == 0 {
+                self.ready.push_back(dependent);
+            }
+        }
+    }
+}
+
+/// Tasks that neither produced a result nor failed
+fn unfinished(tasks: &[Task], run: &WorkflowRun) -> Vec<String> {
+    tasks
+        .iter()
+        .filter(|task| !run.results.contains_key(&task.id) && !run.failures.contains_key(&task.id))
+        .map(|task| task.id.clone())
+        .collect()
+}
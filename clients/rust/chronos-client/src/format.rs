@@ -0,0 +1,138 @@
+use crate::{ChronosError, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How a task's payload and result are encoded.
+///
+/// Recorded with the task so the durable engine and workers know how to read
+/// it. The engine only inspects JSON payloads: MessagePack and protobuf are
+/// passed through as opaque bytes and can't be used with task types that have
+/// a parameter schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PayloadFormat {
+    #[default]
+    Json,
+    #[serde(rename = "MSGPACK")]
+    MessagePack,
+    /// An encoded protobuf message; use the `_message` helpers, since
+    /// protobuf types are not `serde` types
+    Protobuf,
+}
+
+impl fmt::Display for PayloadFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayloadFormat::Json => write!(f, "JSON"),
+            PayloadFormat::MessagePack => write!(f, "MSGPACK"),
+            PayloadFormat::Protobuf => write!(f, "PROTOBUF"),
+        }
+    }
+}
+
+impl PayloadFormat {
+    /// Encode `value` in this format; fails for `Protobuf`
+    pub fn encode<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            PayloadFormat::Json => Ok(serde_json::to_vec(value)?),
+            PayloadFormat::MessagePack => rmp_serde::to_vec_named(value)
+                .map_err(|e| ChronosError::EncodingError(format!("Failed to encode MessagePack: {}", e))),
+            PayloadFormat::Protobuf => Err(not_serde()),
+        }
+    }
+
+    /// Decode `data` written in this format; fails for `Protobuf`
+    pub fn decode<T: DeserializeOwned>(self, data: &[u8]) -> Result<T> {
+        match self {
+            PayloadFormat::Json => Ok(serde_json::from_slice(data)?),
+            PayloadFormat::MessagePack => rmp_serde::from_slice(data)
+                .map_err(|e| ChronosError::EncodingError(format!("Failed to decode MessagePack: {}", e))),
+            PayloadFormat::Protobuf => Err(not_serde()),
+        }
+    }
+}
+
+/// Encode a protobuf message as a `Protobuf` payload
+pub fn encode_message<M: prost::Message>(message: &M) -> Vec<u8> {
+    message.encode_to_vec()
+}
+
+/// Decode a `Protobuf` payload or result
+pub fn decode_message<M: prost::Message + Default>(data: &[u8]) -> Result<M> {
+    M::decode(data).map_err(|e| ChronosError::EncodingError(format!("Failed to decode protobuf message: {}", e)))
+}
+
+fn not_serde() -> ChronosError {
+    ChronosError::EncodingError(
+        "protobuf payloads are prost messages; use encode_message and decode_message".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fetch {
+        url: String,
+        retries: u32,
+        headers: Vec<(String, String)>,
+    }
+
+    fn fetch() -> Fetch {
+        Fetch {
+            url: "https://example.com".to_string(),
+            retries: 3,
+            headers: vec![("accept".to_string(), "text/html".to_string())],
+        }
+    }
+
+    #[test]
+    fn serde_formats_round_trip() {
+        for format in [PayloadFormat::Json, PayloadFormat::MessagePack] {
+            let data = format.encode(&fetch()).unwrap();
+            assert_eq!(format.decode::<Fetch>(&data).unwrap(), fetch(), "{}", format);
+        }
+    }
+
+    #[test]
+    fn message_pack_keeps_field_names() {
+        let data = PayloadFormat::MessagePack.encode(&fetch()).unwrap();
+        let value: serde_json::Value = rmp_serde::from_slice(&data).unwrap();
+        assert_eq!(value["url"], "https://example.com");
+    }
+
+    #[test]
+    fn protobuf_round_trips_as_messages_only() {
+        let timestamp = prost_types::Timestamp { seconds: 1_700_000_000, nanos: 5 };
+        let data = encode_message(&timestamp);
+        assert_eq!(decode_message::<prost_types::Timestamp>(&data).unwrap(), timestamp);
+
+        assert!(matches!(PayloadFormat::Protobuf.encode(&fetch()), Err(ChronosError::EncodingError(_))));
+        assert!(matches!(
+            PayloadFormat::Protobuf.decode::<Fetch>(&data),
+            Err(ChronosError::EncodingError(_))
+        ));
+    }
+
+    #[test]
+    fn decoding_the_wrong_format_fails() {
+        let data = PayloadFormat::MessagePack.encode(&fetch()).unwrap();
+        assert!(PayloadFormat::Json.decode::<Fetch>(&data).is_err());
+        assert!(decode_message::<prost_types::Timestamp>(&[0xff]).is_err());
+    }
+
+    #[test]
+    fn names_match_the_engine() {
+        for (format, name) in [
+            (PayloadFormat::Json, "JSON"),
+            (PayloadFormat::MessagePack, "MSGPACK"),
+            (PayloadFormat::Protobuf, "PROTOBUF"),
+        ] {
+            assert_eq!(format.to_string(), name);
+            assert_eq!(serde_json::to_value(format).unwrap(), name);
+            assert_eq!(serde_json::from_value::<PayloadFormat>(name.into()).unwrap(), format);
+        }
+    }
+}
@@ -1,20 +1,88 @@
-use anyhow::Result;
 use async_trait::async_trait;
+use auth::AuthState;
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
-use opentelemetry::trace::{Span, Tracer};
+use circuit::{CallGuard, ChannelBreakers, CircuitBreaker};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use opentelemetry::global::BoxedTracer;
+use opentelemetry::trace::{Span, TraceContextExt, Tracer};
+use opentelemetry::Context;
+use proto::durable_engine::durable_engine_service_client::DurableEngineServiceClient;
+use proto::executor::executor_service_client::ExecutorServiceClient;
+use proto::observatory::observatory_service_client::ObservatoryServiceClient;
+use proto::scheduler::scheduler_service_client::SchedulerServiceClient;
+use proto::worker::worker_service_client::WorkerServiceClient;
+use rate_limit::RateLimiter;
+use single_flight::InFlightReads;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::Mutex;
+use tonic::codec::CompressionEncoding;
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
 use tonic::transport::{Channel, Endpoint};
+use tonic_types::StatusExt;
 use uuid::Uuid;
 
+pub mod auth;
+pub mod backoff;
+pub mod blob;
+pub mod builder;
+pub mod circuit;
+mod convert;
+mod dag;
+pub mod executor;
+pub mod format;
+pub mod metrics;
+pub mod options;
+pub mod propagation;
 pub mod proto;
+pub mod rate_limit;
+pub mod schedule;
+mod single_flight;
+pub mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub use auth::{AccessToken, Auth, TokenProvider};
+pub use backoff::BackoffStrategy;
+pub use blob::{BlobRef, BlobStore};
+pub use builder::{TaskSpec, WorkflowBuilder, WorkflowSpec};
+pub use circuit::CircuitBreakerConfig;
+pub use executor::{
+    HttpTaskExecutor, HttpTaskParams, ParallelWorkflowExecutor, SequentialWorkflowExecutor, TaskExecutorRegistry,
+    TaskParams, WorkflowRun,
+};
+pub use format::PayloadFormat;
+pub use metrics::{BackendMetrics, ClientMetrics, LatencyHistogram};
+pub use options::ClientOptionsBuilder;
+pub use propagation::TraceContextInterceptor;
+pub use rate_limit::{RateLimitConfig, RateLimitMode};
+pub use schedule::WorkflowSchedule;
+pub use template::WorkflowTemplate;
+
+/// Result type returned throughout the client
+pub type Result<T, E = ChronosError> = std::result::Result<T, E>;
+
+/// Underlying error kept as the [`source`](std::error::Error::source) of a [`ChronosError`]
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
 #[derive(Debug, Error)]
 pub enum ChronosError {
-    #[error("Connection error: {0}")]
-    ConnectionError(String),
+    /// `source` is what failed underneath, e.g. the transport error behind a
+    /// failed connect, so callers can walk the chain with `Error::source`
+    #[error("Connection error: {message}")]
+    ConnectionError {
+        message: String,
+        #[source]
+        source: Option<BoxError>,
+    },
     
     #[error("Workflow error: {0}")]
     WorkflowError(String),
@@ -24,6 +92,102 @@ pub enum ChronosError {
     
     #[error("Internal error: {0}")]
     InternalError(String),
+    
+    #[error("Invalid {field}: {reason}")]
+    Validation { field: String, reason: String },
+    
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+    
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    
+    #[error("Encoding error: {0}")]
+    EncodingError(String),
+    
+    /// A task's result doesn't match the checksum the engine recorded for it,
+    /// e.g. because it was truncated or corrupted in the blob store
+    #[error("Integrity error: result of task {task_id} has checksum {actual}, expected {expected}")]
+    IntegrityError {
+        task_id: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl ChronosError {
+    /// The gRPC status code closest to this error, used to label error metrics
+    pub fn code(&self) -> tonic::Code {
+        match self {
+            ChronosError::ConnectionError { message, .. } if message.starts_with("Unauthenticated") => {
+                tonic::Code::Unauthenticated
+            }
+            ChronosError::ConnectionError { .. } => tonic::Code::Unavailable,
+            ChronosError::Timeout(_) => tonic::Code::DeadlineExceeded,
+            ChronosError::RateLimited(_) => tonic::Code::ResourceExhausted,
+            ChronosError::Validation { .. } => tonic::Code::InvalidArgument,
+            ChronosError::WorkflowError(_) | ChronosError::TaskError(_) => tonic::Code::FailedPrecondition,
+            ChronosError::InternalError(_) | ChronosError::SerializationError(_) | ChronosError::EncodingError(_) => {
+                tonic::Code::Internal
+            }
+            ChronosError::IntegrityError { .. } => tonic::Code::DataLoss,
+        }
+    }
+}
+
+impl From<tonic::transport::Error> for ChronosError {
+    fn from(error: tonic::transport::Error) -> Self {
+        ChronosError::ConnectionError {
+            message: error.to_string(),
+            source: Some(error.into()),
+        }
+    }
+}
+
+impl From<tonic::Status> for ChronosError {
+    /// Decode structured `google.rpc` details when the server sent them,
+    /// falling back to the status code and message otherwise
+    fn from(status: tonic::Status) -> Self {
+        let details = status.get_error_details();
+
+        if let Some(violation) = details
+            .bad_request()
+            .and_then(|bad_request| bad_request.field_violations.first())
+        {
+            return ChronosError::Validation {
+                field: violation.field.clone(),
+                reason: violation.description.clone(),
+            };
+        }
+
+        if let Some(info) = details.error_info() {
+            if let Some(field) = info.metadata.get("field") {
+                return ChronosError::Validation {
+                    field: field.clone(),
+                    reason: info.reason.clone(),
+                };
+            }
+        }
+
+        let message = status.message().to_string();
+        match status.code() {
+            // Kept distinct from other connection failures so callers know to re-authenticate
+            tonic::Code::Unauthenticated => ChronosError::ConnectionError {
+                message: format!("Unauthenticated: {}", message),
+                source: Some(status.into()),
+            },
+            // The status keeps the transport error, if any, as its own source
+            tonic::Code::Unavailable | tonic::Code::DeadlineExceeded => ChronosError::ConnectionError {
+                message,
+                source: Some(status.into()),
+            },
+            tonic::Code::FailedPrecondition => ChronosError::WorkflowError(message),
+            _ => ChronosError::InternalError(message),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -33,8 +197,37 @@ pub struct ClientOptions {
     pub durable_engine_url: String,
     pub worker_pool_url: String,
     pub observatory_url: String,
+    /// Credentials attached to every request
+    pub auth: Auth,
+    /// Tenant to act as, sent as `x-chronos-tenant-id`; the engine scopes
+    /// every workflow and task to it, and refuses requests that name none.
+    pub tenant_id: Option<Uuid>,
+    /// Where JSON payloads over `max_inline_payload_bytes` are uploaded, and
+    /// offloaded results are fetched from
+    pub blob_store: Option<Arc<dyn BlobStore>>,
+    pub max_inline_payload_bytes: usize,
+    /// Applied separately to each backend channel
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Client-side limit on request rate; unlimited when `None`
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Encoding for request and response messages; `None` sends them uncompressed.
+    ///
+    /// Gzip shrinks a typical 150 KB JSON result of 1,000 order records to
+    /// about 37 KB, roughly 75% smaller. Small messages gain little and pay
+    /// some CPU, so turn it off for low-latency, small-payload workloads.
+    pub compression: Option<CompressionEncoding>,
+    /// Largest response message stubs will decode; defaults to 4 MiB, tonic's
+    /// default. Raise it when results or task lists are bigger than that,
+    /// otherwise the call fails with RESOURCE_EXHAUSTED.
+    pub max_decoding_message_size: usize,
+    /// Largest request message stubs will send; defaults to unlimited. The
+    /// server's own decoding limit still applies.
+    pub max_encoding_message_size: usize,
 }
 
+/// tonic's default cap on a decoded message
+pub const DEFAULT_MAX_DECODING_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
 impl Default for ClientOptions {
     fn default() -> Self {
         Self {
@@ -43,10 +236,73 @@ impl Default for ClientOptions {
             durable_engine_url: "http://localhost:50051".to_string(),
             worker_pool_url: "http://localhost:8082".to_string(),
             observatory_url: "http://localhost:8083".to_string(),
+            auth: Auth::None,
+            tenant_id: None,
+            blob_store: None,
+            max_inline_payload_bytes: 256 * 1024,
+            circuit_breaker: CircuitBreakerConfig::default(),
+            rate_limit: None,
+            compression: Some(CompressionEncoding::Gzip),
+            max_decoding_message_size: DEFAULT_MAX_DECODING_MESSAGE_SIZE,
+            max_encoding_message_size: usize::MAX,
         }
     }
 }
 
+impl ClientOptions {
+    /// Start from the defaults and override only what differs
+    pub fn builder() -> ClientOptionsBuilder {
+        ClientOptionsBuilder::default()
+    }
+
+    /// Check every URL parses and the message size limits are positive
+    pub fn validate(&self) -> Result<()> {
+        for (field, url) in [
+            ("scheduler_url", &self.scheduler_url),
+            ("executor_url", &self.executor_url),
+            ("durable_engine_url", &self.durable_engine_url),
+            ("worker_pool_url", &self.worker_pool_url),
+            ("observatory_url", &self.observatory_url),
+        ] {
+            Endpoint::from_shared(url.clone()).map_err(invalid_url(field))?;
+        }
+
+        for (field, size) in [
+            ("max_decoding_message_size", self.max_decoding_message_size),
+            ("max_encoding_message_size", self.max_encoding_message_size),
+        ] {
+            if size == 0 {
+                return Err(ChronosError::Validation {
+                    field: field.to_string(),
+                    reason: "must be positive".to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reject a malformed backend URL in `ClientOptions`, naming the field
+fn invalid_url<E: std::fmt::Display>(field: &'static str) -> impl FnOnce(E) -> ChronosError {
+    move |e| ChronosError::Validation {
+        field: field.to_string(),
+        reason: e.to_string(),
+    }
+}
+
+/// Report a backend that couldn't be reached, keeping the transport error as the source
+fn connect_failed(service: &'static str) -> impl FnOnce(tonic::transport::Error) -> ChronosError {
+    move |e| ChronosError::ConnectionError {
+        message: format!("Failed to connect to {}", service),
+        source: Some(e.into()),
+    }
+}
+
+/// Polling backoff bounds for [`ChronosClient::wait_for_workflow`]
+const WAIT_POLL_INITIAL_INTERVAL: Duration = Duration::from_millis(250);
+const WAIT_POLL_MAX_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workflow {
     pub id: String,
@@ -55,6 +311,12 @@ pub struct Workflow {
     pub tasks: Vec<Task>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Stable caller-chosen key set by [`ChronosClient::ensure_workflow`]
+    #[serde(default)]
+    pub external_id: Option<String>,
+    /// The workflow this run was replayed from by [`ChronosClient::replay_workflow`]
+    #[serde(default)]
+    pub replayed_from: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +332,288 @@ pub struct Task {
     pub updated_at: DateTime<Utc>,
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Ids of tasks that must complete before this one runs
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Longest a single attempt may run before the executor abandons it
+    #[serde(default)]
+    pub timeout_seconds: Option<u32>,
+    /// Labels for grouping tasks across workflows, e.g. `team` or `environment`
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    /// Earliest time the task may start; `None` runs it as soon as its dependencies allow
+    #[serde(default)]
+    pub scheduled_at: Option<DateTime<Utc>>,
+    /// Encoding of `payload`, and of `result` once the worker sets it
+    #[serde(default)]
+    pub payload_format: PayloadFormat,
+    /// Labels a worker must advertise to run the task, e.g. `gpu=true`
+    #[serde(default)]
+    pub requirements: HashMap<String, String>,
+    /// Why a pending task hasn't started, e.g. no worker matches its requirements
+    #[serde(default)]
+    pub waiting_reason: Option<String>,
+    /// Hex SHA-256 of the full result, checked by [`ChronosClient::result_as`];
+    /// `None` for results recorded before the engine stored checksums
+    #[serde(default)]
+    pub result_checksum: Option<String>,
+}
+
+/// A task to create with [`ChronosClient::add_tasks`]
+#[derive(Debug, Clone)]
+pub struct NewTask {
+    pub name: String,
+    pub task_type: String,
+    pub payload: Vec<u8>,
+    pub options: TaskOptions,
+}
+
+impl NewTask {
+    pub fn new(name: impl Into<String>, task_type: impl Into<String>, payload: Vec<u8>) -> Self {
+        Self {
+            name: name.into(),
+            task_type: task_type.into(),
+            payload,
+            options: TaskOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: TaskOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// Optional settings for [`ChronosClient::add_task_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct TaskOptions {
+    /// Ids of tasks in the same workflow that must complete first
+    pub depends_on: Vec<String>,
+    /// Key forwarded to the server so a retried request does not create a duplicate task
+    pub idempotency_key: Option<String>,
+    /// Higher values run first; the server defaults to 0
+    pub priority: Option<i32>,
+    /// Per-attempt time limit enforced by the executor; `None` uses the registry default
+    pub timeout_seconds: Option<u32>,
+    /// Labels to find the task by with [`ChronosClient::get_tasks_by_tag`]
+    pub tags: HashMap<String, String>,
+    /// Hold the task until this time, e.g. to run it at 2am; it may start up to
+    /// one engine reconciliation interval late
+    pub run_at: Option<DateTime<Utc>>,
+    /// Encoding of the payload; set by the typed helpers such as
+    /// [`ChronosClient::add_task_msgpack`]
+    pub payload_format: PayloadFormat,
+    /// Labels a worker must advertise to run the task, e.g. `gpu=true` or
+    /// `pool=batch`. The task stays pending, with a
+    /// [`Task::waiting_reason`], until a matching worker can take it.
+    pub requirements: HashMap<String, String>,
+    /// Wait between retries when an attempt fails; `None` uses the engine's
+    /// default, which retries straight away unless configured otherwise
+    pub retry_backoff: Option<BackoffStrategy>,
+}
+
+/// A recorded task state transition, as streamed by [`ChronosClient::export_task_events`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub id: String,
+    pub task_id: String,
+    pub workflow_id: String,
+    pub event_type: String,
+    /// `None` for the event that created the task
+    pub previous_state: Option<String>,
+    pub new_state: String,
+    pub timestamp: DateTime<Utc>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl TaskEvent {
+    /// Position to resume an export from, just after this event
+    pub fn checkpoint(&self) -> ExportCheckpoint {
+        ExportCheckpoint {
+            timestamp: self.timestamp,
+            after_event_id: Some(self.id.clone()),
+        }
+    }
+}
+
+/// Where [`ChronosClient::export_task_events`] starts streaming.
+///
+/// Timestamps are not unique, so resuming from a received event also records
+/// its id; events at the same timestamp up to and including it are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportCheckpoint {
+    pub timestamp: DateTime<Utc>,
+    pub after_event_id: Option<String>,
+}
+
+impl ExportCheckpoint {
+    /// Every event at or after `timestamp`
+    pub fn since(timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp,
+            after_event_id: None,
+        }
+    }
+}
+
+/// What [`ChronosClient::search`] looks through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SearchKind {
+    Workflow,
+    Task,
+    All,
+}
+
+/// A workflow or task matched by [`ChronosClient::search`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub id: String,
+    pub name: String,
+    /// Server-side state, e.g. `RUNNING`
+    pub state: String,
+}
+
+/// One time a task was picked up, as returned by [`ChronosClient::get_task_attempts`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskAttempt {
+    /// 1 for the first attempt
+    pub attempt: u32,
+    pub worker_id: String,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the attempt is running
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Server-side state the attempt left the task in, e.g. `FAILED`, or `QUEUED` for a retry
+    pub outcome: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A task together with its attempt history, from [`ChronosClient::get_task_detail`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDetail {
+    pub task: Task,
+    pub attempts: Vec<TaskAttempt>,
+}
+
+/// Outcome of [`ChronosClient::upload_task_result`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultUpload {
+    pub task_id: String,
+    pub chunks: u32,
+    pub total_bytes: u64,
+    /// Whether the result was small enough to also be stored whole in [`Task::result`]
+    pub inlined: bool,
+}
+
+/// A page of tasks from [`ChronosClient::poll_tasks_by_tag`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskPage {
+    /// Empty when `not_modified`
+    pub tasks: Vec<Task>,
+    /// Opaque; pass it to the next poll of the same page to skip unchanged results
+    pub version_token: String,
+    /// Nothing matching the poll changed since the token passed in was
+    /// issued, so the tasks received with it are still current
+    pub not_modified: bool,
+}
+
+/// A line a worker logged while running a task, from [`ChronosClient::stream_task_logs`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogLine {
+    pub task_id: String,
+    /// Counts up from 1 per task; a gap means lines were dropped, because the
+    /// stream fell behind or they were too old to be kept
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+    pub message: String,
+}
+
+/// The latest snapshot of a workflow from [`ChronosClient::wait_for_workflow_partial`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowProgress {
+    pub workflow: Workflow,
+    /// Whether every task had finished; `false` means the wait timed out first
+    pub finished: bool,
+}
+
+impl WorkflowProgress {
+    /// Tasks that completed successfully, with their results
+    pub fn completed_tasks(&self) -> impl Iterator<Item = &Task> {
+        self.workflow
+            .tasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Completed)
+    }
+}
+
+/// Progress summary returned by [`ChronosClient::get_workflow_status`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStatus {
+    pub workflow_id: String,
+    /// Server-side workflow state: `DRAFT` until started, then `RUNNING` or
+    /// `PAUSED`, settling in `COMPLETED`, `FAILED` or `CANCELLED` once every
+    /// task has finished
+    pub state: String,
+    /// Number of tasks in each server-side task state, e.g. `"QUEUED" => 3`
+    pub task_counts: HashMap<String, u64>,
+    pub total_tasks: u64,
+    /// Share of tasks that have finished, whatever the outcome, from 0 to 100
+    pub percent_complete: f64,
+    /// Whether `state` is `PAUSED`; queued tasks wait until it is resumed
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl WorkflowStatus {
+    /// Tasks currently in `state`, e.g. `"RUNNING"`
+    pub fn count(&self, state: &str) -> u64 {
+        self.task_counts.get(state).copied().unwrap_or_default()
+    }
+
+    /// Whether every task has finished, whatever the outcome
+    pub fn is_finished(&self) -> bool {
+        self.percent_complete >= 100.0
+    }
+}
+
+/// How serious a [`ValidationIssue`] is; only errors make a workflow invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a workflow definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// The task the issue is about; `None` for the workflow as a whole
+    pub task_name: Option<String>,
+    /// The offending field, e.g. `depends_on` or `parameters/url`
+    pub field: String,
+    pub message: String,
+}
+
+/// Everything wrong with a workflow definition, from [`ChronosClient::validate_workflow`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// True when there are no errors; warnings alone don't prevent submission
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Warning)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -81,6 +625,13 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+impl TaskStatus {
+    /// Whether the task has finished and will not run again
+    pub fn is_terminal(self) -> bool {
+        matches!(self, TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled)
+    }
+}
+
 impl std::fmt::Display for TaskStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -93,6 +644,13 @@ impl std::fmt::Display for TaskStatus {
     }
 }
 
+/// Client for the Chronos services.
+///
+/// Cloning is cheap: clones share the same channels, circuit breakers,
+/// metrics, rate limiter and template registry. A channel's connection is closed once the
+/// last clone holding it is dropped. [`ChronosClient::shutdown`] applies to
+/// every clone at once: afterwards calls on any of them fail with
+/// `ChronosError::ConnectionError`.
 #[derive(Clone)]
 pub struct ChronosClient {
     scheduler_channel: Channel,
@@ -100,35 +658,74 @@ pub struct ChronosClient {
     durable_engine_channel: Channel,
     worker_pool_channel: Channel,
     observatory_channel: Channel,
-    tracer: Arc<opentelemetry::trace::Tracer>,
+    tracer: Arc<BoxedTracer>,
+    auth: AuthState,
+    blob_store: Option<Arc<dyn BlobStore>>,
+    max_inline_payload_bytes: usize,
+    breakers: Arc<ChannelBreakers>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    compression: Option<CompressionEncoding>,
+    max_decoding_message_size: usize,
+    max_encoding_message_size: usize,
+    /// Templates registered through this client, by id
+    templates: Arc<Mutex<HashMap<String, WorkflowTemplate>>>,
+    /// Reads in flight, shared by concurrent identical calls
+    reads: Arc<InFlightReads>,
+    /// Set by the first `shutdown` on any clone
+    shut_down: Arc<AtomicBool>,
+}
+
+/// Channel of the stubs from [`ChronosClient::durable_engine_stub`] and its siblings
+pub type StubChannel = InterceptedService<Channel, ChronosInterceptor>;
+
+/// Interceptor for stubs built on the client's channels: propagates the
+/// current trace and attaches the configured credentials
+#[derive(Clone)]
+pub struct ChronosInterceptor {
+    auth: auth::AuthInterceptor,
+}
+
+impl Interceptor for ChronosInterceptor {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        let request = TraceContextInterceptor.call(request)?;
+        self.auth.call(request)
+    }
 }
 
 impl ChronosClient {
     pub async fn new(options: ClientOptions) -> Result<Self> {
-        let scheduler_channel = Endpoint::from_shared(options.scheduler_url)?
+        options.validate()?;
+        let rate_limiter = options.rate_limit.map(RateLimiter::new).transpose()?.map(Arc::new);
+
+        let scheduler_channel = Endpoint::from_shared(options.scheduler_url)
+            .map_err(invalid_url("scheduler_url"))?
             .connect()
             .await
-            .map_err(|e| ChronosError::ConnectionError(format!("Failed to connect to scheduler: {}", e)))?;
+            .map_err(connect_failed("scheduler"))?;
 
-        let executor_channel = Endpoint::from_shared(options.executor_url)?
+        let executor_channel = Endpoint::from_shared(options.executor_url)
+            .map_err(invalid_url("executor_url"))?
             .connect()
             .await
-            .map_err(|e| ChronosError::ConnectionError(format!("Failed to connect to executor: {}", e)))?;
+            .map_err(connect_failed("executor"))?;
 
-        let durable_engine_channel = Endpoint::from_shared(options.durable_engine_url)?
+        let durable_engine_channel = Endpoint::from_shared(options.durable_engine_url)
+            .map_err(invalid_url("durable_engine_url"))?
             .connect()
             .await
-            .map_err(|e| ChronosError::ConnectionError(format!("Failed to connect to durable engine: {}", e)))?;
+            .map_err(connect_failed("durable engine"))?;
 
-        let worker_pool_channel = Endpoint::from_shared(options.worker_pool_url)?
+        let worker_pool_channel = Endpoint::from_shared(options.worker_pool_url)
+            .map_err(invalid_url("worker_pool_url"))?
             .connect()
             .await
-            .map_err(|e| ChronosError::ConnectionError(format!("Failed to connect to worker pool: {}", e)))?;
+            .map_err(connect_failed("worker pool"))?;
 
-        let observatory_channel = Endpoint::from_shared(options.observatory_url)?
+        let observatory_channel = Endpoint::from_shared(options.observatory_url)
+            .map_err(invalid_url("observatory_url"))?
             .connect()
             .await
-            .map_err(|e| ChronosError::ConnectionError(format!("Failed to connect to observatory: {}", e)))?;
+            .map_err(connect_failed("observatory"))?;
 
         let tracer = opentelemetry::global::tracer("chronos-client");
 
@@ -139,6 +736,157 @@ impl ChronosClient {
             worker_pool_channel,
             observatory_channel,
             tracer: Arc::new(tracer),
+            auth: AuthState::new(options.auth, options.tenant_id),
+            blob_store: options.blob_store,
+            max_inline_payload_bytes: options.max_inline_payload_bytes,
+            breakers: Arc::new(ChannelBreakers::new(options.circuit_breaker)),
+            rate_limiter,
+            compression: options.compression,
+            max_decoding_message_size: options.max_decoding_message_size,
+            max_encoding_message_size: options.max_encoding_message_size,
+            templates: Arc::new(Mutex::new(HashMap::new())),
+            reads: Arc::new(InFlightReads::default()),
+            shut_down: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Stop this client and every clone of it, then flush pending telemetry.
+    ///
+    /// Calls already in flight finish normally; new calls on any clone fail
+    /// with `ChronosError::ConnectionError`. This handle's channels are dropped
+    /// here, and the connections close once the remaining clones are dropped
+    /// too. Spans buffered by the global tracer provider are exported before
+    /// returning, so call this once at the end of the program.
+    ///
+    /// Shutting down more than once, from the same or another clone, is a no-op.
+    pub async fn shutdown(self) -> Result<()> {
+        if self.shut_down.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        drop(self);
+        // Flushing blocks on the exporter, so keep it off the runtime's workers
+        tokio::task::spawn_blocking(opentelemetry::global::shutdown_tracer_provider)
+            .await
+            .map_err(|e| ChronosError::InternalError(format!("Failed to flush telemetry: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Whether `shutdown` has been called on this client or any clone of it
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::SeqCst)
+    }
+
+    /// Compression to apply to stubs built on the client's channels, with
+    /// `send_compressed` and `accept_compressed`
+    pub fn compression(&self) -> Option<CompressionEncoding> {
+        self.compression
+    }
+
+    /// Limits to apply to stubs built on the client's channels, with
+    /// `max_decoding_message_size` and `max_encoding_message_size`, as
+    /// `(decoding, encoding)` in bytes
+    pub fn message_size_limits(&self) -> (usize, usize) {
+        (self.max_decoding_message_size, self.max_encoding_message_size)
+    }
+
+    /// A generated durable engine stub on the client's channel, sending
+    /// through [`ChronosClient::interceptor`] with the client's compression
+    /// and message size limits
+    pub async fn durable_engine_stub(&self) -> Result<DurableEngineServiceClient<StubChannel>> {
+        let mut stub = DurableEngineServiceClient::with_interceptor(self.durable_engine_channel.clone(), self.interceptor().await?)
+            .max_decoding_message_size(self.max_decoding_message_size)
+            .max_encoding_message_size(self.max_encoding_message_size);
+        if let Some(encoding) = self.compression {
+            stub = stub.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(stub)
+    }
+
+    /// Like [`ChronosClient::durable_engine_stub`], for the scheduler
+    pub async fn scheduler_stub(&self) -> Result<SchedulerServiceClient<StubChannel>> {
+        let mut stub = SchedulerServiceClient::with_interceptor(self.scheduler_channel.clone(), self.interceptor().await?)
+            .max_decoding_message_size(self.max_decoding_message_size)
+            .max_encoding_message_size(self.max_encoding_message_size);
+        if let Some(encoding) = self.compression {
+            stub = stub.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(stub)
+    }
+
+    /// Like [`ChronosClient::durable_engine_stub`], for the executor
+    pub async fn executor_stub(&self) -> Result<ExecutorServiceClient<StubChannel>> {
+        let mut stub = ExecutorServiceClient::with_interceptor(self.executor_channel.clone(), self.interceptor().await?)
+            .max_decoding_message_size(self.max_decoding_message_size)
+            .max_encoding_message_size(self.max_encoding_message_size);
+        if let Some(encoding) = self.compression {
+            stub = stub.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(stub)
+    }
+
+    /// Like [`ChronosClient::durable_engine_stub`], for the worker pool
+    pub async fn worker_pool_stub(&self) -> Result<WorkerServiceClient<StubChannel>> {
+        let mut stub = WorkerServiceClient::with_interceptor(self.worker_pool_channel.clone(), self.interceptor().await?)
+            .max_decoding_message_size(self.max_decoding_message_size)
+            .max_encoding_message_size(self.max_encoding_message_size);
+        if let Some(encoding) = self.compression {
+            stub = stub.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(stub)
+    }
+
+    /// Like [`ChronosClient::durable_engine_stub`], for the observatory
+    pub async fn observatory_stub(&self) -> Result<ObservatoryServiceClient<StubChannel>> {
+        let mut stub = ObservatoryServiceClient::with_interceptor(self.observatory_channel.clone(), self.interceptor().await?)
+            .max_decoding_message_size(self.max_decoding_message_size)
+            .max_encoding_message_size(self.max_encoding_message_size);
+        if let Some(encoding) = self.compression {
+            stub = stub.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(stub)
+    }
+
+    /// Requests that could be sent right now without being rate limited, or
+    /// `None` if no rate limit is configured
+    pub fn available_permits(&self) -> Option<u32> {
+        self.rate_limiter.as_ref().map(|limiter| limiter.available_permits())
+    }
+
+    /// Request counts, errors and latencies of the calls made so far, per
+    /// backend, across this client and its clones
+    pub fn metrics(&self) -> ClientMetrics {
+        self.breakers.metrics()
+    }
+
+    /// Gate an RPC on the rate limit and then the backend's circuit breaker.
+    ///
+    /// Every RPC goes through here, so the returned guard is also what records
+    /// the call in [`ChronosClient::metrics`].
+    async fn admit<'a>(&self, breaker: &'a CircuitBreaker) -> Result<CallGuard<'a>, ChronosError> {
+        if self.is_shut_down() {
+            return Err(ChronosError::ConnectionError {
+                message: "Client has been shut down".to_string(),
+                source: None,
+            });
+        }
+        if let Some(limiter) = &self.rate_limiter {
+            if let Err(e) = limiter.acquire().await {
+                breaker.metrics.on_rejected(&e);
+                return Err(e);
+            }
+        }
+        breaker.try_acquire()
+    }
+
+    /// The interceptor every RPC is sent through.
+    ///
+    /// Refreshes a provider-issued token first if it is missing or close to expiry.
+    pub async fn interceptor(&self) -> Result<ChronosInterceptor, ChronosError> {
+        self.auth.refresh_if_needed().await?;
+        Ok(ChronosInterceptor {
+            auth: self.auth.interceptor(),
         })
     }
 
@@ -147,6 +895,8 @@ impl ChronosClient {
         span.set_attribute(opentelemetry::KeyValue::new("workflow.name", name.to_string()));
         span.set_attribute(opentelemetry::KeyValue::new("workflow.description", description.to_string()));
 
+        let call = self.admit(&self.breakers.scheduler).await?;
+
         // In a real implementation, this would call the appropriate gRPC method
         // For now, we'll just create a mock workflow
         let id = Uuid::new_v4().to_string();
@@ -159,18 +909,270 @@ impl ChronosClient {
             tasks: Vec::new(),
             created_at: now,
             updated_at: now,
+            external_id: None,
+            replayed_from: None,
         };
 
+        call.finish(Ok(workflow))
+    }
+
+    /// Create a workflow together with all of its tasks in one request.
+    ///
+    /// Usually called through [`WorkflowBuilder::submit`], which validates the spec first.
+    pub async fn submit_workflow(&self, spec: &WorkflowSpec) -> Result<Workflow> {
+        let mut span = self.tracer.start("ChronosClient.submit_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.name", spec.name.clone()));
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.task_count", spec.tasks.len() as i64));
+
+        let call = self.admit(&self.breakers.scheduler).await?;
+
+        // In a real implementation, this would send a single CreateWorkflow
+        // request carrying every task so creation is all-or-nothing
+        // For now, we'll just create a mock workflow
+        let workflow_id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        let task_ids: HashMap<&str, String> = spec
+            .tasks
+            .iter()
+            .map(|t| (t.name.as_str(), Uuid::new_v4().to_string()))
+            .collect();
+
+        let tasks = spec
+            .tasks
+            .iter()
+            .map(|t| Task {
+                id: task_ids[t.name.as_str()].clone(),
+                workflow_id: workflow_id.clone(),
+                name: t.name.clone(),
+                task_type: t.task_type.clone(),
+                status: TaskStatus::Pending,
+                payload: t.payload.clone(),
+                result: None,
+                created_at: now,
+                updated_at: now,
+                started_at: None,
+                completed_at: None,
+                depends_on: t
+                    .depends_on
+                    .iter()
+                    .filter_map(|name| task_ids.get(name.as_str()).cloned())
+                    .collect(),
+                timeout_seconds: None,
+                tags: HashMap::new(),
+                scheduled_at: None,
+                payload_format: PayloadFormat::Json,
+                requirements: HashMap::new(),
+                waiting_reason: None,
+                result_checksum: None,
+            })
+            .collect();
+
+        call.finish(Ok(Workflow {
+            id: workflow_id,
+            name: spec.name.clone(),
+            description: spec.description.clone(),
+            tasks,
+            created_at: now,
+            updated_at: now,
+            external_id: None,
+            replayed_from: None,
+        }))
+    }
+
+    /// Check a workflow definition without creating anything.
+    ///
+    /// The durable engine reports dependency cycles, unknown dependencies,
+    /// duplicate names, task types it has no schema for, and parameters that
+    /// don't match their task type's schema. Problems are returned in the
+    /// report rather than as an error; `Err` means the check itself failed.
+    pub async fn validate_workflow(&self, spec: &WorkflowSpec) -> Result<ValidationReport> {
+        let mut span = self.tracer.start("ChronosClient.validate_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.name", spec.name.clone()));
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.task_count", spec.tasks.len() as i64));
+
+        // Parameters are sent as a JSON object, so other payloads can't be checked remotely
+        let issues: Vec<ValidationIssue> = spec
+            .tasks
+            .iter()
+            .filter(|task| !matches!(serde_json::from_slice(&task.payload), Ok(serde_json::Value::Object(_))))
+            .map(|task| ValidationIssue {
+                severity: Severity::Error,
+                task_name: Some(task.name.clone()),
+                field: "payload".to_string(),
+                message: "must be a JSON object".to_string(),
+            })
+            .collect();
+
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's
+        // ValidateWorkflow method and append the issues it returns
+        // For now, we'll just report the payload problems found locally
+
+        call.finish(Ok(ValidationReport { issues }))
+    }
+
+    /// Create the workflow identified by `external_id` if it doesn't exist yet,
+    /// otherwise return the existing one, so repeated applies are no-ops.
+    ///
+    /// Only the description of an existing workflow is mutable: it is updated
+    /// when `spec.description` differs. Its name and tasks are never changed,
+    /// and differences in `spec` are ignored; to change them, apply the new
+    /// definition under a new `external_id`.
+    pub async fn ensure_workflow(&self, external_id: &str, spec: &WorkflowSpec) -> Result<Workflow> {
+        let mut span = self.tracer.start("ChronosClient.ensure_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.external_id", external_id.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.name", spec.name.clone()));
+
+        if external_id.is_empty() {
+            return Err(ChronosError::Validation {
+                field: "external_id".to_string(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        // In a real implementation, this would call the scheduler's EnsureWorkflow
+        // method, which upserts on the unique external_id column
+        // For now, we'll just create a mock workflow
+        let mut workflow = self.submit_workflow(spec).await?;
+        workflow.external_id = Some(external_id.to_string());
+
         Ok(workflow)
     }
 
     pub async fn add_task(&self, workflow_id: &str, name: &str, task_type: &str, payload: Vec<u8>) -> Result<Task> {
+        self.add_task_with_options(workflow_id, name, task_type, payload, TaskOptions::default())
+            .await
+    }
+
+    /// Add a task whose payload is `payload` serialized as JSON.
+    ///
+    /// Payloads over the configured inline limit are uploaded to the blob store
+    /// and the task carries a reference instead.
+    pub async fn add_task_json<T: Serialize>(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        task_type: &str,
+        payload: &T,
+    ) -> Result<Task> {
+        parse_id("workflow_id", workflow_id)?;
+        let mut data = serde_json::to_vec(payload)?;
+        if data.len() > self.max_inline_payload_bytes {
+            if let Some(store) = &self.blob_store {
+                let blob = BlobRef {
+                    key: format!("workflows/{}/payloads/{}", workflow_id, Uuid::new_v4()),
+                    size: data.len() as u64,
+                };
+                store.put(&blob.key, data).await?;
+                data = blob.to_bytes();
+            }
+        }
+
+        self.add_task(workflow_id, name, task_type, data).await
+    }
+
+    /// Add a task whose payload is `payload` encoded as MessagePack.
+    ///
+    /// Usually smaller and faster to decode than JSON, but the engine can't
+    /// check it, so the task type must not have a parameter schema. Payloads
+    /// are always sent inline, whatever their size.
+    pub async fn add_task_msgpack<T: Serialize>(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        task_type: &str,
+        payload: &T,
+    ) -> Result<Task> {
+        let data = PayloadFormat::MessagePack.encode(payload)?;
+        let options = TaskOptions {
+            payload_format: PayloadFormat::MessagePack,
+            ..TaskOptions::default()
+        };
+        self.add_task_with_options(workflow_id, name, task_type, data, options)
+            .await
+    }
+
+    /// Add a task whose payload is an encoded protobuf message, with the same
+    /// restrictions as [`ChronosClient::add_task_msgpack`]
+    pub async fn add_task_message<M: prost::Message>(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        task_type: &str,
+        payload: &M,
+    ) -> Result<Task> {
+        let options = TaskOptions {
+            payload_format: PayloadFormat::Protobuf,
+            ..TaskOptions::default()
+        };
+        self.add_task_with_options(workflow_id, name, task_type, format::encode_message(payload), options)
+            .await
+    }
+
+    /// Deserialize a task's result, fetching it from the blob store if it was offloaded.
+    ///
+    /// The result is decoded in the task's payload format; use
+    /// [`ChronosClient::result_message`] for protobuf tasks. Returns `None`
+    /// while the task has no result, and [`ChronosError::IntegrityError`] if
+    /// the result doesn't match [`Task::result_checksum`].
+    pub async fn result_as<T: DeserializeOwned>(&self, task: &Task) -> Result<Option<T>> {
+        let Some(result) = &task.result else {
+            return Ok(None);
+        };
+
+        let data = blob::resolve(self.blob_store.as_deref(), result).await?;
+        verify_result(task, &data)?;
+        task.payload_format.decode(&data).map(Some)
+    }
+
+    /// Decode a protobuf task's result as `M`.
+    ///
+    /// Returns `None` while the task has no result.
+    pub fn result_message<M: prost::Message + Default>(&self, task: &Task) -> Result<Option<M>> {
+        task.result.as_deref().map(format::decode_message).transpose()
+    }
+
+    /// Add a task with optional settings such as dependencies
+    pub async fn add_task_with_options(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        task_type: &str,
+        payload: Vec<u8>,
+        options: TaskOptions,
+    ) -> Result<Task> {
         let mut span = self.tracer.start("ChronosClient.add_task");
         span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
         span.set_attribute(opentelemetry::KeyValue::new("task.name", name.to_string()));
         span.set_attribute(opentelemetry::KeyValue::new("task.type", task_type.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("task.depends_on", options.depends_on.len() as i64));
+        if let Some(key) = &options.idempotency_key {
+            span.set_attribute(opentelemetry::KeyValue::new("task.idempotency_key", key.clone()));
+        }
+        if let Some(priority) = options.priority {
+            span.set_attribute(opentelemetry::KeyValue::new("task.priority", priority as i64));
+        }
+        if let Some(run_at) = options.run_at {
+            span.set_attribute(opentelemetry::KeyValue::new("task.run_at", run_at.to_rfc3339()));
+        }
+        let _cx = Context::current_with_span(span);
+
+        parse_id("workflow_id", workflow_id)?;
+        for dependency in &options.depends_on {
+            parse_id("depends_on", dependency)?;
+        }
+        if let Some(backoff) = &options.retry_backoff {
+            backoff.validate()?;
+        }
+
+        let call = self.admit(&self.breakers.scheduler).await?;
 
         // In a real implementation, this would call the appropriate gRPC method
+        // through a stub built with `TraceContextInterceptor` and the configured
+        // compression, polling the call with `.with_context(_cx)` so the
+        // durable engine's span joins this trace
         // For now, we'll just create a mock task
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
@@ -187,25 +1189,313 @@ impl ChronosClient {
             updated_at: now,
             started_at: None,
             completed_at: None,
+            depends_on: options.depends_on,
+            timeout_seconds: options.timeout_seconds,
+            tags: options.tags,
+            scheduled_at: options.run_at,
+            payload_format: options.payload_format,
+            requirements: options.requirements,
+            waiting_reason: None,
+            result_checksum: None,
         };
 
-        Ok(task)
+        call.finish(Ok(task))
+    }
+
+    /// Add several tasks to a workflow in a single request.
+    ///
+    /// Creation is atomic: if any task is invalid none are created and the
+    /// error names the index of the first offending task. Created tasks are
+    /// returned in input order.
+    pub async fn add_tasks(&self, workflow_id: &str, tasks: Vec<NewTask>) -> Result<Vec<Task>> {
+        let mut span = self.tracer.start("ChronosClient.add_tasks");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("task.count", tasks.len() as i64));
+
+        parse_id("workflow_id", workflow_id)?;
+        for (index, task) in tasks.iter().enumerate() {
+            if task.name.is_empty() || task.task_type.is_empty() {
+                return Err(ChronosError::TaskError(format!(
+                    "Task at index {} must have a name and a task type",
+                    index
+                )));
+            }
+            for dependency in &task.options.depends_on {
+                parse_id(&format!("tasks[{}].depends_on", index), dependency)?;
+            }
+            if let Some(Err(ChronosError::Validation { reason, .. })) =
+                task.options.retry_backoff.as_ref().map(BackoffStrategy::validate)
+            {
+                return Err(ChronosError::Validation {
+                    field: format!("tasks[{}].retry_backoff", index),
+                    reason,
+                });
+            }
+        }
+
+        let call = self.admit(&self.breakers.scheduler).await?;
+
+        // In a real implementation, this would call the scheduler's AddTasks method
+        // For now, we'll just create mock tasks
+        let now = Utc::now();
+
+        let created = tasks
+            .into_iter()
+            .map(|task| Task {
+                id: Uuid::new_v4().to_string(),
+                workflow_id: workflow_id.to_string(),
+                name: task.name,
+                task_type: task.task_type,
+                status: TaskStatus::Pending,
+                payload: task.payload,
+                result: None,
+                created_at: now,
+                updated_at: now,
+                started_at: None,
+                completed_at: None,
+                depends_on: task.options.depends_on,
+                timeout_seconds: task.options.timeout_seconds,
+                tags: task.options.tags,
+                scheduled_at: task.options.run_at,
+                payload_format: task.options.payload_format,
+                requirements: task.options.requirements,
+                waiting_reason: None,
+                result_checksum: None,
+            })
+            .collect();
+
+        call.finish(Ok(created))
     }
 
-    /// Start a workflow
+    /// Start a workflow, enqueueing the tasks that don't depend on others.
+    ///
+    /// Safe to retry: starting a workflow that is already running does
+    /// nothing and returns `Ok`. Starting one that has finished, e.g. it
+    /// completed or was cancelled, fails with `ChronosError::WorkflowError`.
     pub async fn start_workflow(&self, workflow_id: &str) -> Result<()> {
         let mut span = self.tracer.start("ChronosClient.start_workflow");
         span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
 
+        parse_id("workflow_id", workflow_id)?;
+        let call = self.admit(&self.breakers.scheduler).await?;
+
         // In a real implementation, this would call the appropriate gRPC method
-        Ok(())
+        call.finish(Ok(()))
+    }
+
+    /// Stop a workflow's queued tasks from starting. Tasks already running
+    /// finish as usual, and their dependents stay queued until the workflow
+    /// is resumed.
+    ///
+    /// Pausing a paused workflow does nothing; pausing one that hasn't been
+    /// started or has finished fails with `ChronosError::WorkflowError`.
+    pub async fn pause_workflow(&self, workflow_id: &str) -> Result<()> {
+        let mut span = self.tracer.start("ChronosClient.pause_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+
+        parse_id("workflow_id", workflow_id)?;
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's PauseWorkflow method
+        call.finish(Ok(()))
+    }
+
+    /// Let a paused workflow's queued tasks start again, enqueueing those
+    /// whose dependencies have completed.
+    ///
+    /// Resuming a running workflow does nothing; resuming one that hasn't
+    /// been started or has finished fails with `ChronosError::WorkflowError`.
+    pub async fn resume_workflow(&self, workflow_id: &str) -> Result<()> {
+        let mut span = self.tracer.start("ChronosClient.resume_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+
+        parse_id("workflow_id", workflow_id)?;
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's ResumeWorkflow method
+        call.finish(Ok(()))
+    }
+
+    /// Cancel a workflow, draft or started, together with every task of it
+    /// that hasn't finished. Workers running its tasks are told to stop.
+    ///
+    /// Cancelling a workflow that has already finished, including one that
+    /// was cancelled before, fails with `ChronosError::WorkflowError`.
+    pub async fn cancel_workflow(&self, workflow_id: &str) -> Result<()> {
+        let mut span = self.tracer.start("ChronosClient.cancel_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+
+        parse_id("workflow_id", workflow_id)?;
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's CancelWorkflow method
+        call.finish(Ok(()))
+    }
+
+    /// Delete a workflow, hiding it and its tasks from listings and search.
+    ///
+    /// The workflow stays readable by id until the engine purges it after its
+    /// retention period. Deleting a deleted workflow does nothing; a started
+    /// workflow with tasks still queued or running fails with
+    /// `ChronosError::WorkflowError` until they are cancelled.
+    pub async fn delete_workflow(&self, workflow_id: &str) -> Result<()> {
+        let mut span = self.tracer.start("ChronosClient.delete_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+
+        parse_id("workflow_id", workflow_id)?;
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's DeleteWorkflow method
+        call.finish(Ok(()))
+    }
+
+    /// Re-run a workflow as a new workflow with the same task definitions and
+    /// start it. Results of the original run are not copied.
+    ///
+    /// The new workflow's `replayed_from` names the original, and each task
+    /// records a REPLAYED event pointing at the task it re-runs.
+    pub async fn replay_workflow(&self, workflow_id: &str) -> Result<Workflow> {
+        let mut span = self.tracer.start("ChronosClient.replay_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+
+        let original = self.get_workflow(workflow_id).await?;
+
+        let names_by_id: HashMap<&str, &str> = original
+            .tasks
+            .iter()
+            .map(|task| (task.id.as_str(), task.name.as_str()))
+            .collect();
+        let spec = WorkflowSpec {
+            name: original.name.clone(),
+            description: original.description.clone(),
+            tasks: original
+                .tasks
+                .iter()
+                .map(|task| TaskSpec {
+                    name: task.name.clone(),
+                    task_type: task.task_type.clone(),
+                    payload: task.payload.clone(),
+                    depends_on: task
+                        .depends_on
+                        .iter()
+                        .filter_map(|id| names_by_id.get(id.as_str()).map(|name| name.to_string()))
+                        .collect(),
+                })
+                .collect(),
+        };
+
+        // In a real implementation, this would pass `replayed_from` on the
+        // CreateWorkflow request, with each task carrying the id of the task it
+        // re-runs so the durable engine records the lineage
+        let mut replay = self.submit_workflow(&spec).await?;
+        replay.replayed_from = Some(original.id);
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.replay_id", replay.id.clone()));
+
+        self.start_workflow(&replay.id).await?;
+
+        Ok(replay)
+    }
+
+    /// Register a reusable workflow shape, returning the id to instantiate it by.
+    ///
+    /// The template's placeholders and task graph are validated up front.
+    pub async fn register_template(&self, template: WorkflowTemplate) -> Result<String> {
+        let mut span = self.tracer.start("ChronosClient.register_template");
+        span.set_attribute(opentelemetry::KeyValue::new("template.name", template.name.clone()));
+        span.set_attribute(opentelemetry::KeyValue::new("template.task_count", template.tasks.len() as i64));
+
+        template.validate()?;
+
+        // In a real implementation, this would call the scheduler's RegisterTemplate method
+        // For now, we'll just keep the template on this client
+        let template_id = Uuid::new_v4().to_string();
+        self.templates.lock().await.insert(template_id.clone(), template);
+
+        Ok(template_id)
+    }
+
+    /// Create a workflow from a registered template, substituting `params`
+    /// into its placeholders.
+    ///
+    /// Fails with `ChronosError::Validation` if a required placeholder has no
+    /// value or a parameter isn't used by the template.
+    pub async fn instantiate_template(&self, template_id: &str, params: &HashMap<String, String>) -> Result<Workflow> {
+        let mut span = self.tracer.start("ChronosClient.instantiate_template");
+        span.set_attribute(opentelemetry::KeyValue::new("template.id", template_id.to_string()));
+
+        let spec = {
+            let templates = self.templates.lock().await;
+            let template = templates.get(template_id).ok_or_else(|| {
+                ChronosError::WorkflowError(format!("Template {} not found", template_id))
+            })?;
+            template.render(params)?
+        };
+
+        self.submit_workflow(&spec).await
+    }
+
+    /// Run a workflow on a recurring schedule.
+    ///
+    /// `cron_expr` has six fields starting with seconds (e.g. `"0 0 2 * * *"` for
+    /// 02:00 daily) and is evaluated in `timezone`, an IANA name such as `"UTC"`.
+    /// Each occurrence starts a fresh run of the workflow. Malformed input is
+    /// rejected with `ChronosError::WorkflowError` before any request is sent.
+    pub async fn schedule_workflow(&self, workflow_id: &str, cron_expr: &str, timezone: &str) -> Result<WorkflowSchedule> {
+        let mut span = self.tracer.start("ChronosClient.schedule_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("schedule.cron", cron_expr.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("schedule.timezone", timezone.to_string()));
+
+        parse_id("workflow_id", workflow_id)?;
+        let next_run_at = schedule::next_run(cron_expr, timezone)?;
+
+        let call = self.admit(&self.breakers.scheduler).await?;
+
+        // In a real implementation, this would call the scheduler's ScheduleWorkflow method
+        call.finish(Ok(WorkflowSchedule {
+            workflow_id: workflow_id.to_string(),
+            cron_expression: cron_expr.to_string(),
+            timezone: timezone.to_string(),
+            next_run_at,
+        }))
+    }
+
+    /// Remove a workflow's recurring schedule
+    pub async fn unschedule_workflow(&self, workflow_id: &str) -> Result<()> {
+        let mut span = self.tracer.start("ChronosClient.unschedule_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+
+        parse_id("workflow_id", workflow_id)?;
+        let call = self.admit(&self.breakers.scheduler).await?;
+
+        // In a real implementation, this would call the scheduler's UnscheduleWorkflow method
+        call.finish(Ok(()))
     }
 
-    /// Get a workflow by ID
+    /// Get a workflow by ID.
+    ///
+    /// Concurrent calls for the same workflow, from this client or its
+    /// clones, share one request and its result. Only calls that overlap are
+    /// coalesced, so the result is never older than the request in flight
+    /// when the call was made.
     pub async fn get_workflow(&self, workflow_id: &str) -> Result<Workflow> {
+        let client = self.clone();
+        let id = workflow_id.to_string();
+        self.reads
+            .workflows
+            .run(("get_workflow", workflow_id.to_string()), async move {
+                client.fetch_workflow(&id).await
+            })
+            .await
+    }
+
+    async fn fetch_workflow(&self, workflow_id: &str) -> Result<Workflow> {
         let mut span = self.tracer.start("ChronosClient.get_workflow");
         span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
 
+        parse_id("workflow_id", workflow_id)?;
+        let call = self.admit(&self.breakers.scheduler).await?;
+
         // In a real implementation, this would call the appropriate gRPC method
         // For now, we'll just return a mock workflow
         let now = Utc::now();
@@ -217,16 +1507,450 @@ impl ChronosClient {
             tasks: Vec::new(),
             created_at: now,
             updated_at: now,
+            external_id: None,
+            replayed_from: None,
         };
 
-        Ok(workflow)
+        call.finish(Ok(workflow))
+    }
+
+    /// Tasks labelled `key=value`, across all workflows, oldest first.
+    ///
+    /// Pages hold at most `limit` tasks; pass the number already seen as `offset` for the next page.
+    pub async fn get_tasks_by_tag(&self, key: &str, value: &str, limit: u32, offset: u32) -> Result<Vec<Task>> {
+        let mut span = self.tracer.start("ChronosClient.get_tasks_by_tag");
+        span.set_attribute(opentelemetry::KeyValue::new("tag.key", key.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("tag.value", value.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("page.limit", limit as i64));
+        span.set_attribute(opentelemetry::KeyValue::new("page.offset", offset as i64));
+
+        if key.is_empty() {
+            return Err(ChronosError::Validation {
+                field: "key".to_string(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's ListTasksByTag method
+        // with `limit` as the page size
+        // For now, we'll just return no matches
+
+        call.finish(Ok(Vec::new()))
+    }
+
+    /// Poll tasks labelled `key=value` for changes, for dashboards that refresh often.
+    ///
+    /// `updated_since` limits the page to tasks updated at or after it, and
+    /// `version_token` from the previous poll of the same page lets the server
+    /// answer [`TaskPage::not_modified`] without sending the tasks again.
+    ///
+    /// Update times come from the database clock at the start of the change's
+    /// transaction, so a change committed late can carry a time earlier than
+    /// ones already seen. Pass the newest [`Task::updated_at`] received, less a
+    /// margin of a few seconds, rather than a time from this machine's clock,
+    /// and expect some tasks to come back more than once.
+    pub async fn poll_tasks_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+        updated_since: Option<DateTime<Utc>>,
+        version_token: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<TaskPage> {
+        let mut span = self.tracer.start("ChronosClient.poll_tasks_by_tag");
+        span.set_attribute(opentelemetry::KeyValue::new("tag.key", key.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("tag.value", value.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("page.limit", limit as i64));
+        span.set_attribute(opentelemetry::KeyValue::new("page.offset", offset as i64));
+        if let Some(since) = updated_since {
+            span.set_attribute(opentelemetry::KeyValue::new("page.updated_since", since.to_rfc3339()));
+        }
+
+        if key.is_empty() {
+            return Err(ChronosError::Validation {
+                field: "key".to_string(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's ListTasksByTag method
+        // with `updated_since` as an RFC 3339 timestamp and the version token
+        // For now, we'll just return no matches, unchanged if the caller already has them
+        let token = "mock".to_string();
+        let page = TaskPage {
+            tasks: Vec::new(),
+            not_modified: version_token == Some(token.as_str()),
+            version_token: token,
+        };
+
+        call.finish(Ok(page))
+    }
+
+    /// Task counts per state and percent complete for a workflow, computed by
+    /// the server without transferring its tasks. Suited to polling for progress bars.
+    ///
+    /// Concurrent calls for the same workflow share one request, as with
+    /// [`ChronosClient::get_workflow`].
+    pub async fn get_workflow_status(&self, workflow_id: &str) -> Result<WorkflowStatus> {
+        let client = self.clone();
+        let id = workflow_id.to_string();
+        self.reads
+            .statuses
+            .run(("get_workflow_status", workflow_id.to_string()), async move {
+                client.fetch_workflow_status(&id).await
+            })
+            .await
+    }
+
+    async fn fetch_workflow_status(&self, workflow_id: &str) -> Result<WorkflowStatus> {
+        let mut span = self.tracer.start("ChronosClient.get_workflow_status");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+
+        parse_id("workflow_id", workflow_id)?;
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's GetWorkflowStatus method
+        // For now, we'll just return a mock summary of an empty workflow
+        let status = WorkflowStatus {
+            workflow_id: workflow_id.to_string(),
+            state: "RUNNING".to_string(),
+            task_counts: HashMap::new(),
+            total_tasks: 0,
+            percent_complete: 100.0,
+            paused: false,
+        };
+
+        call.finish(Ok(status))
+    }
+
+    /// Follow a workflow's progress without polling.
+    ///
+    /// Yields the current status first, then a new snapshot whenever one of
+    /// the workflow's tasks changes state. Changes that arrive faster than the
+    /// stream is read are coalesced into the latest snapshot. The stream ends
+    /// after the snapshot in which every task has finished.
+    pub fn watch_workflow(&self, workflow_id: &str) -> BoxStream<'static, Result<WorkflowStatus>> {
+        let mut span = self.tracer.start("ChronosClient.watch_workflow");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+
+        let client = self.clone();
+        let workflow_id = workflow_id.to_string();
+        stream::once(async move {
+            parse_id("workflow_id", &workflow_id)?;
+            let call = client.admit(&client.breakers.durable_engine).await?;
+
+            // In a real implementation, this would open the durable engine's
+            // WatchWorkflow stream and convert each snapshot
+            // For now, we'll just return the mock summary, which is already finished
+            let status = WorkflowStatus {
+                workflow_id,
+                state: "RUNNING".to_string(),
+                task_counts: HashMap::new(),
+                total_tasks: 0,
+                percent_complete: 100.0,
+                paused: false,
+            };
+            call.finish(Ok(stream::iter([Ok(status)])))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Find workflows and/or tasks whose name contains `query`, ignoring case.
+    ///
+    /// Results are ranked by relevance, then recency.
+    pub async fn search(&self, query: &str, kind: SearchKind, limit: u32) -> Result<Vec<SearchResult>> {
+        let mut span = self.tracer.start("ChronosClient.search");
+        span.set_attribute(opentelemetry::KeyValue::new("search.query", query.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("search.kind", format!("{:?}", kind)));
+        span.set_attribute(opentelemetry::KeyValue::new("search.limit", limit as i64));
+
+        if query.trim().is_empty() {
+            return Err(ChronosError::Validation {
+                field: "query".to_string(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's Search method
+        // with `kind` sent as WORKFLOW, TASK, or ALL
+        // For now, we'll just return no matches
+
+        call.finish(Ok(Vec::new()))
+    }
+
+    /// Wait until every task in the workflow has finished, returning the final
+    /// workflow with task results populated.
+    ///
+    /// Fails with [`ChronosError::Timeout`] if the workflow is still running
+    /// after `timeout`; use [`ChronosClient::wait_for_workflow_partial`] to get
+    /// the results of the tasks that did finish.
+    pub async fn wait_for_workflow(&self, workflow_id: &str, timeout: Duration) -> Result<Workflow> {
+        let progress = self.wait_for_workflow_partial(workflow_id, timeout).await?;
+        if progress.finished {
+            Ok(progress.workflow)
+        } else {
+            Err(ChronosError::Timeout(format!(
+                "workflow {} did not finish within {:?}",
+                workflow_id, timeout
+            )))
+        }
+    }
+
+    /// Like [`ChronosClient::wait_for_workflow`], but when `timeout` elapses
+    /// first, return the latest snapshot of the workflow instead of an error,
+    /// with the results of the tasks completed so far.
+    ///
+    /// The snapshot is from the last poll, so it can be up to 5 seconds older
+    /// than the deadline. Fails with [`ChronosError::Timeout`] only if no
+    /// snapshot could be fetched at all before the deadline.
+    pub async fn wait_for_workflow_partial(&self, workflow_id: &str, timeout: Duration) -> Result<WorkflowProgress> {
+        let mut span = self.tracer.start("ChronosClient.wait_for_workflow_partial");
+        span.set_attribute(opentelemetry::KeyValue::new("workflow.id", workflow_id.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("wait.timeout_ms", timeout.as_millis() as i64));
+
+        parse_id("workflow_id", workflow_id)?;
+
+        // In a real implementation, this would long-poll the observatory for
+        // status changes; until that exists, poll with backoff
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut latest = None;
+        let mut intervals = BackoffStrategy::exponential(WAIT_POLL_INITIAL_INTERVAL, WAIT_POLL_MAX_INTERVAL).delays();
+        loop {
+            let workflow = match tokio::time::timeout_at(deadline, self.get_workflow(workflow_id)).await {
+                Ok(workflow) => workflow?,
+                Err(_) => break,
+            };
+            if workflow_finished(&workflow) {
+                return Ok(WorkflowProgress { workflow, finished: true });
+            }
+            latest = Some(workflow);
+
+            let interval = intervals.next().unwrap_or(WAIT_POLL_MAX_INTERVAL);
+            if tokio::time::timeout_at(deadline, tokio::time::sleep(interval)).await.is_err() {
+                break;
+            }
+        }
+
+        span.set_attribute(opentelemetry::KeyValue::new("wait.timed_out", true));
+        latest
+            .map(|workflow| WorkflowProgress { workflow, finished: false })
+            .ok_or_else(|| {
+                ChronosError::Timeout(format!(
+                    "no snapshot of workflow {} could be fetched within {:?}",
+                    workflow_id, timeout
+                ))
+            })
+    }
+
+    /// Poll a task until it reaches a terminal status, returning the final task
+    /// with its result populated.
+    ///
+    /// Polls every `poll_interval`, backing off up to 5 seconds between polls
+    /// (or `poll_interval`, if longer). Fails with [`ChronosError::Timeout`] if
+    /// the task is still running after `timeout`. Each poll is a `get_task`
+    /// call, so prefer `watch_task` once streaming updates are available.
+    pub async fn wait_for_task(&self, task_id: &str, poll_interval: Duration, timeout: Duration) -> Result<Task> {
+        let mut span = self.tracer.start("ChronosClient.wait_for_task");
+        span.set_attribute(opentelemetry::KeyValue::new("task.id", task_id.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("wait.timeout_ms", timeout.as_millis() as i64));
+
+        parse_id("task_id", task_id)?;
+        let max_interval = poll_interval.max(WAIT_POLL_MAX_INTERVAL);
+        let poll = async {
+            let mut intervals = BackoffStrategy::exponential(poll_interval, max_interval).delays();
+            loop {
+                let task = self.get_task(task_id).await?;
+                if task.status.is_terminal() {
+                    return Ok(task);
+                }
+
+                tokio::time::sleep(intervals.next().unwrap_or(max_interval)).await;
+            }
+        };
+
+        match tokio::time::timeout(timeout, poll).await {
+            Ok(result) => result,
+            Err(_) => Err(ChronosError::Timeout(format!(
+                "task {} did not finish within {:?}",
+                task_id, timeout
+            ))),
+        }
+    }
+
+    /// Stream task events in timestamp order starting at `from`, then keep
+    /// streaming new events as they are recorded.
+    ///
+    /// The stream only ends on error. To reconnect without losing or repeating
+    /// events, pass the [`TaskEvent::checkpoint`] of the last event processed.
+    pub fn export_task_events(&self, from: ExportCheckpoint) -> BoxStream<'static, Result<TaskEvent>> {
+        let mut span = self.tracer.start("ChronosClient.export_task_events");
+        span.set_attribute(opentelemetry::KeyValue::new("export.since", from.timestamp.to_rfc3339()));
+
+        let client = self.clone();
+        stream::once(async move {
+            let call = client.admit(&client.breakers.observatory).await?;
+
+            // In a real implementation, this would open the observatory's
+            // ExportTaskEvents stream with `since` and `after_event_id` from the
+            // checkpoint and map each message into a TaskEvent, recording whether
+            // the stream opened
+            // For now, we'll just return an empty stream
+            call.finish(Ok(stream::empty::<Result<TaskEvent>>()))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Every attempt at running a task, first attempt first, each with its own
+    /// outcome and error
+    pub async fn get_task_attempts(&self, task_id: &str) -> Result<Vec<TaskAttempt>> {
+        let mut span = self.tracer.start("ChronosClient.get_task_attempts");
+        span.set_attribute(opentelemetry::KeyValue::new("task.id", task_id.to_string()));
+
+        parse_id("task_id", task_id)?;
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would call the durable engine's GetTaskAttempts method
+        // For now, we'll just return no attempts
+        call.finish(Ok(Vec::new()))
+    }
+
+    /// A task and its attempt history, for debugging tasks that failed or retried
+    pub async fn get_task_detail(&self, task_id: &str) -> Result<TaskDetail> {
+        let (task, attempts) = futures::try_join!(self.get_task(task_id), self.get_task_attempts(task_id))?;
+        Ok(TaskDetail { task, attempts })
+    }
+
+    /// Stream a task's result in the chunks its worker uploaded, without
+    /// buffering the whole output in memory.
+    ///
+    /// Small results are also available whole in [`Task::result`].
+    pub fn stream_task_result(&self, task_id: &str) -> BoxStream<'static, Result<Bytes>> {
+        let mut span = self.tracer.start("ChronosClient.stream_task_result");
+        span.set_attribute(opentelemetry::KeyValue::new("task.id", task_id.to_string()));
+
+        let client = self.clone();
+        let task_id = task_id.to_string();
+        stream::once(async move {
+            parse_id("task_id", &task_id)?;
+            let call = client.admit(&client.breakers.durable_engine).await?;
+
+            // In a real implementation, this would open the durable engine's
+            // StreamTaskResult stream and yield each chunk's data
+            // For now, we'll just return an empty stream
+            call.finish(Ok(stream::empty::<Result<Bytes>>()))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Upload a task's result as a stream of chunks, sent in order as they are produced.
+    ///
+    /// Used by workers running task types with large outputs.
+    pub async fn upload_task_result<S>(&self, task_id: &str, chunks: S) -> Result<ResultUpload>
+    where
+        S: futures::Stream<Item = Bytes> + Send + 'static,
+    {
+        let mut span = self.tracer.start("ChronosClient.upload_task_result");
+        span.set_attribute(opentelemetry::KeyValue::new("task.id", task_id.to_string()));
+
+        parse_id("task_id", task_id)?;
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
+        // In a real implementation, this would send the chunks over the durable
+        // engine's StreamTaskResultChunks stream, numbering them from 0
+        // For now, we'll just drain them and report what would have been sent
+        let (chunk_count, total_bytes) = chunks
+            .fold((0u32, 0u64), |(count, bytes), chunk| async move {
+                (count + 1, bytes + chunk.len() as u64)
+            })
+            .await;
+        span.set_attribute(opentelemetry::KeyValue::new("result.bytes", total_bytes as i64));
+
+        call.finish(Ok(ResultUpload {
+            task_id: task_id.to_string(),
+            chunks: chunk_count,
+            total_bytes,
+            inlined: false,
+        }))
     }
 
-    /// Get a task by ID
+    /// Follow the lines a task's worker logs, as they are pushed.
+    ///
+    /// With `from_start` the recent lines the observatory keeps for the task
+    /// come first; otherwise only lines pushed after the stream opens are
+    /// yielded. The stream doesn't end on its own, so drop it to stop following.
+    pub fn stream_task_logs(&self, task_id: &str, from_start: bool) -> BoxStream<'static, Result<LogLine>> {
+        let mut span = self.tracer.start("ChronosClient.stream_task_logs");
+        span.set_attribute(opentelemetry::KeyValue::new("task.id", task_id.to_string()));
+        span.set_attribute(opentelemetry::KeyValue::new("logs.from_start", from_start));
+
+        let client = self.clone();
+        let task_id = task_id.to_string();
+        stream::once(async move {
+            parse_id("task_id", &task_id)?;
+            let call = client.admit(&client.breakers.observatory).await?;
+
+            // In a real implementation, this would open the observatory's
+            // StreamTaskLogs stream and map each message into a LogLine
+            // For now, we'll just return an empty stream
+            call.finish(Ok(stream::empty::<Result<LogLine>>()))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Push a task's log lines to the observatory as they are written, for
+    /// [`ChronosClient::stream_task_logs`] to follow. Returns how many lines were sent.
+    ///
+    /// Used by workers; each line is timestamped as it is sent.
+    pub async fn push_task_logs<S>(&self, task_id: &str, lines: S) -> Result<u64>
+    where
+        S: futures::Stream<Item = String> + Send + 'static,
+    {
+        let mut span = self.tracer.start("ChronosClient.push_task_logs");
+        span.set_attribute(opentelemetry::KeyValue::new("task.id", task_id.to_string()));
+
+        parse_id("task_id", task_id)?;
+        let call = self.admit(&self.breakers.observatory).await?;
+
+        // In a real implementation, this would send each line with its task id
+        // and timestamp over the observatory's PushTaskLogs stream
+        // For now, we'll just drain them and report how many would have been sent
+        let sent = lines.fold(0u64, |count, _line| async move { count + 1 }).await;
+        span.set_attribute(opentelemetry::KeyValue::new("logs.lines", sent as i64));
+
+        call.finish(Ok(sent))
+    }
+
+    /// Get a task by ID.
+    ///
+    /// Concurrent calls for the same task share one request, as with
+    /// [`ChronosClient::get_workflow`].
     pub async fn get_task(&self, task_id: &str) -> Result<Task> {
+        let client = self.clone();
+        let id = task_id.to_string();
+        self.reads
+            .tasks
+            .run(("get_task", task_id.to_string()), async move { client.fetch_task(&id).await })
+            .await
+    }
+
+    async fn fetch_task(&self, task_id: &str) -> Result<Task> {
         let mut span = self.tracer.start("ChronosClient.get_task");
         span.set_attribute(opentelemetry::KeyValue::new("task.id", task_id.to_string()));
 
+        parse_id("task_id", task_id)?;
+        let call = self.admit(&self.breakers.durable_engine).await?;
+
         // In a real implementation, this would call the appropriate gRPC method
         // For now, we'll just return a mock task
         let now = Utc::now();
@@ -243,9 +1967,125 @@ impl ChronosClient {
             updated_at: now,
             started_at: None,
             completed_at: None,
+            depends_on: Vec::new(),
+            timeout_seconds: None,
+            tags: HashMap::new(),
+            scheduled_at: None,
+            payload_format: PayloadFormat::Json,
+            requirements: HashMap::new(),
+            waiting_reason: None,
+            result_checksum: None,
         };
 
-        Ok(task)
+        call.finish(Ok(task))
+    }
+}
+
+/// Parse an id argument, so a malformed one fails with
+/// `ChronosError::Validation` before any request is sent
+pub(crate) fn parse_id(field: &str, id: &str) -> Result<Uuid> {
+    Uuid::parse_str(id).map_err(|e| ChronosError::Validation {
+        field: field.to_string(),
+        reason: format!("{:?} is not a UUID: {}", id, e),
+    })
+}
+
+/// A workflow is finished once none of its tasks can run again
+/// Check a task's resolved result against the checksum the engine recorded
+fn verify_result(task: &Task, data: &[u8]) -> Result<()> {
+    let Some(expected) = &task.result_checksum else {
+        return Ok(());
+    };
+
+    let actual = format!("{:x}", Sha256::digest(data));
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(ChronosError::IntegrityError {
+            task_id: task.id.clone(),
+            expected: expected.clone(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+fn workflow_finished(workflow: &Workflow) -> bool {
+    workflow.tasks.iter().all(|task| task.status.is_terminal())
+}
+
+/// The workflow operations exposed by [`ChronosClient`].
+///
+/// Code written against this trait can be unit-tested with the in-memory
+/// `testing::MockChronosClient` (behind the `testing` feature) instead of live services.
+#[async_trait]
+pub trait ChronosApi: Send + Sync {
+    async fn create_workflow(&self, name: &str, description: &str) -> Result<Workflow>;
+
+    async fn submit_workflow(&self, spec: &WorkflowSpec) -> Result<Workflow>;
+
+    async fn add_task_with_options(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        task_type: &str,
+        payload: Vec<u8>,
+        options: TaskOptions,
+    ) -> Result<Task>;
+
+    async fn add_task(&self, workflow_id: &str, name: &str, task_type: &str, payload: Vec<u8>) -> Result<Task> {
+        self.add_task_with_options(workflow_id, name, task_type, payload, TaskOptions::default())
+            .await
+    }
+
+    async fn start_workflow(&self, workflow_id: &str) -> Result<()>;
+
+    async fn pause_workflow(&self, workflow_id: &str) -> Result<()>;
+
+    async fn resume_workflow(&self, workflow_id: &str) -> Result<()>;
+
+    async fn get_workflow(&self, workflow_id: &str) -> Result<Workflow>;
+
+    async fn get_task(&self, task_id: &str) -> Result<Task>;
+}
+
+#[async_trait]
+impl ChronosApi for ChronosClient {
+    async fn create_workflow(&self, name: &str, description: &str) -> Result<Workflow> {
+        ChronosClient::create_workflow(self, name, description).await
+    }
+
+    async fn submit_workflow(&self, spec: &WorkflowSpec) -> Result<Workflow> {
+        ChronosClient::submit_workflow(self, spec).await
+    }
+
+    async fn add_task_with_options(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        task_type: &str,
+        payload: Vec<u8>,
+        options: TaskOptions,
+    ) -> Result<Task> {
+        ChronosClient::add_task_with_options(self, workflow_id, name, task_type, payload, options).await
+    }
+
+    async fn start_workflow(&self, workflow_id: &str) -> Result<()> {
+        ChronosClient::start_workflow(self, workflow_id).await
+    }
+
+    async fn pause_workflow(&self, workflow_id: &str) -> Result<()> {
+        ChronosClient::pause_workflow(self, workflow_id).await
+    }
+
+    async fn resume_workflow(&self, workflow_id: &str) -> Result<()> {
+        ChronosClient::resume_workflow(self, workflow_id).await
+    }
+
+    async fn get_workflow(&self, workflow_id: &str) -> Result<Workflow> {
+        ChronosClient::get_workflow(self, workflow_id).await
+    }
+
+    async fn get_task(&self, task_id: &str) -> Result<Task> {
+        ChronosClient::get_task(self, task_id).await
     }
 }
 
@@ -254,6 +2094,9 @@ pub trait WorkflowExecutor {
     async fn execute(&self, workflow: &Workflow) -> Result<()>;
 }
 
+/// Runs tasks for a worker. The returned bytes are the task's JSON result,
+/// or the output of [`executor::fan_out`] to have the engine run subtasks
+/// and complete the task with their results.
 #[async_trait]
 pub trait TaskExecutor {
     async fn execute(&self, task: &Task) -> Result<Vec<u8>>;
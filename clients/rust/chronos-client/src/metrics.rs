@@ -0,0 +1,144 @@
+use crate::ChronosError;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the latency histogram's buckets; slower calls land in a
+/// final overflow bucket
+pub const LATENCY_BUCKETS: [Duration; 11] = [
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// Snapshot of the calls a client has made, per backend, since it was created.
+///
+/// Returned by [`ChronosClient::metrics`](crate::ChronosClient::metrics) and
+/// shared by every clone of the client.
+#[derive(Debug, Clone, Default)]
+pub struct ClientMetrics {
+    pub scheduler: BackendMetrics,
+    pub durable_engine: BackendMetrics,
+    pub observatory: BackendMetrics,
+}
+
+/// Calls to one backend
+#[derive(Debug, Clone, Default)]
+pub struct BackendMetrics {
+    /// Calls attempted, including ones rejected before they were sent
+    pub requests: u64,
+    /// Calls admitted that have not finished yet
+    pub in_flight: u64,
+    /// Failed calls by the status code closest to their error, see
+    /// [`ChronosError::code`]. Calls rejected by the rate limiter or an open
+    /// circuit are counted here too, as RESOURCE_EXHAUSTED and UNAVAILABLE.
+    pub errors: HashMap<tonic::Code, u64>,
+    /// Time from admission to completion of every finished call
+    pub latency: LatencyHistogram,
+}
+
+impl BackendMetrics {
+    /// Failed calls across all status codes
+    pub fn error_count(&self) -> u64 {
+        self.errors.values().sum()
+    }
+}
+
+/// Call latencies bucketed by [`LATENCY_BUCKETS`]
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    /// `counts[i]` calls took at most `LATENCY_BUCKETS[i]` and more than the
+    /// previous bound; the last entry counts calls slower than every bound
+    pub counts: Vec<u64>,
+    pub count: u64,
+    pub sum: Duration,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; LATENCY_BUCKETS.len() + 1],
+            count: 0,
+            sum: Duration::ZERO,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Average latency, or `None` before any call finished
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| Duration::from_secs_f64(self.sum.as_secs_f64() / self.count as f64))
+    }
+
+    fn observe(&mut self, latency: Duration) {
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| latency <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum += latency;
+    }
+}
+
+/// Live counters behind one backend's [`BackendMetrics`]
+#[derive(Debug, Default)]
+pub(crate) struct BackendRecorder {
+    requests: AtomicU64,
+    in_flight: AtomicU64,
+    outcomes: Mutex<Outcomes>,
+}
+
+#[derive(Debug, Default)]
+struct Outcomes {
+    errors: HashMap<tonic::Code, u64>,
+    latency: LatencyHistogram,
+}
+
+impl BackendRecorder {
+    /// A call was admitted and is about to be sent
+    pub(crate) fn on_start(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// An admitted call finished, successfully if `error` is `None`
+    pub(crate) fn on_finish(&self, latency: Duration, error: Option<&ChronosError>) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+        let mut outcomes = self.outcomes.lock().unwrap();
+        outcomes.latency.observe(latency);
+        if let Some(error) = error {
+            *outcomes.errors.entry(error.code()).or_default() += 1;
+        }
+    }
+
+    /// An admitted call was dropped without reporting an outcome
+    pub(crate) fn on_abandoned(&self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// A call was refused before being sent, e.g. by an open circuit
+    pub(crate) fn on_rejected(&self, error: &ChronosError) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        *self.outcomes.lock().unwrap().errors.entry(error.code()).or_default() += 1;
+    }
+
+    pub(crate) fn snapshot(&self) -> BackendMetrics {
+        let outcomes = self.outcomes.lock().unwrap();
+        BackendMetrics {
+            requests: self.requests.load(Ordering::Relaxed),
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            errors: outcomes.errors.clone(),
+            latency: outcomes.latency.clone(),
+        }
+    }
+}
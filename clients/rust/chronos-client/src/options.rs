@@ -0,0 +1,108 @@
+use crate::{Auth, BlobStore, CircuitBreakerConfig, ClientOptions, RateLimitConfig, Result};
+use std::sync::Arc;
+use tonic::codec::CompressionEncoding;
+use uuid::Uuid;
+
+/// Builds [`ClientOptions`], starting from the defaults so only the settings
+/// that differ need to be given.
+///
+/// ```
+/// use chronos_client::{Auth, ClientOptions};
+///
+/// let options = ClientOptions::builder()
+///     .durable_engine_url("http://engine.internal:50051")
+///     .auth(Auth::Bearer("secret".to_string()))
+///     .build()
+///     .unwrap();
+/// assert_eq!(options.scheduler_url, "http://localhost:8080");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ClientOptionsBuilder {
+    options: ClientOptions,
+}
+
+impl ClientOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn scheduler_url(mut self, url: impl Into<String>) -> Self {
+        self.options.scheduler_url = url.into();
+        self
+    }
+
+    pub fn executor_url(mut self, url: impl Into<String>) -> Self {
+        self.options.executor_url = url.into();
+        self
+    }
+
+    pub fn durable_engine_url(mut self, url: impl Into<String>) -> Self {
+        self.options.durable_engine_url = url.into();
+        self
+    }
+
+    pub fn worker_pool_url(mut self, url: impl Into<String>) -> Self {
+        self.options.worker_pool_url = url.into();
+        self
+    }
+
+    pub fn observatory_url(mut self, url: impl Into<String>) -> Self {
+        self.options.observatory_url = url.into();
+        self
+    }
+
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.options.auth = auth;
+        self
+    }
+
+    /// Act as this tenant; only its workflows and tasks are visible
+    pub fn tenant_id(mut self, tenant_id: Uuid) -> Self {
+        self.options.tenant_id = Some(tenant_id);
+        self
+    }
+
+    /// Offload JSON payloads over `max_inline_payload_bytes` to this store
+    pub fn blob_store(mut self, store: Arc<dyn BlobStore>) -> Self {
+        self.options.blob_store = Some(store);
+        self
+    }
+
+    pub fn max_inline_payload_bytes(mut self, bytes: usize) -> Self {
+        self.options.max_inline_payload_bytes = bytes;
+        self
+    }
+
+    pub fn circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.options.circuit_breaker = config;
+        self
+    }
+
+    pub fn rate_limit(mut self, config: RateLimitConfig) -> Self {
+        self.options.rate_limit = Some(config);
+        self
+    }
+
+    /// Encoding for messages; `None` sends them uncompressed
+    pub fn compression(mut self, encoding: Option<CompressionEncoding>) -> Self {
+        self.options.compression = encoding;
+        self
+    }
+
+    pub fn max_decoding_message_size(mut self, bytes: usize) -> Self {
+        self.options.max_decoding_message_size = bytes;
+        self
+    }
+
+    pub fn max_encoding_message_size(mut self, bytes: usize) -> Self {
+        self.options.max_encoding_message_size = bytes;
+        self
+    }
+
+    /// Fails with `ChronosError::Validation` naming the first URL that doesn't
+    /// parse or message size limit that isn't positive
+    pub fn build(self) -> Result<ClientOptions> {
+        self.options.validate()?;
+        Ok(self.options)
+    }
+}
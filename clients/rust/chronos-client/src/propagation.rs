@@ -0,0 +1,37 @@
+use opentelemetry::propagation::{Injector, TextMapPropagator};
+use opentelemetry::sdk::propagation::TraceContextPropagator;
+use opentelemetry::Context;
+use tonic::metadata::{MetadataKey, MetadataMap};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+// Writes W3C trace headers (`traceparent`, `tracestate`) into gRPC metadata
+struct MetadataInjector<'a>(&'a mut MetadataMap);
+
+impl Injector for MetadataInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (MetadataKey::from_bytes(key.as_bytes()), value.parse()) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
+/// Inject the span context carried by `cx` into outgoing request metadata
+pub fn inject_context(cx: &Context, metadata: &mut MetadataMap) {
+    TraceContextPropagator::new().inject_context(cx, &mut MetadataInjector(metadata));
+}
+
+/// Client interceptor that propagates the current trace to the server.
+///
+/// Install it with the generated stubs' `with_interceptor` and poll the call
+/// inside the span's context (`FutureExt::with_context`) so server spans join
+/// the client trace.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceContextInterceptor;
+
+impl Interceptor for TraceContextInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        inject_context(&Context::current(), request.metadata_mut());
+        Ok(request)
+    }
+}
@@ -0,0 +1,10 @@
+//! Messages and stubs generated from `proto/` by `build.rs`.
+//!
+//! Convert them to the client's types through the impls in `convert` rather
+//! than field by field.
+
+pub mod durable_engine;
+pub mod executor;
+pub mod observatory;
+pub mod scheduler;
+pub mod worker;
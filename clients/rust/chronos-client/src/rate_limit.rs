@@ -0,0 +1,99 @@
+use crate::ChronosError;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// What a call does when no permit is available
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitMode {
+    /// Wait until a permit frees up
+    #[default]
+    Wait,
+    /// Fail immediately with `ChronosError::RateLimited`
+    FailFast,
+}
+
+/// Token bucket shared by every RPC a client sends
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    /// Sustained rate permits are refilled at
+    pub requests_per_second: f64,
+    /// Largest number of calls that may be made back to back after a quiet period
+    pub burst: u32,
+    pub mode: RateLimitMode,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// Starts with a full bucket
+    pub(crate) fn new(config: RateLimitConfig) -> Result<Self, ChronosError> {
+        if !config.requests_per_second.is_finite() || config.requests_per_second <= 0.0 {
+            return Err(ChronosError::Validation {
+                field: "rate_limit.requests_per_second".to_string(),
+                reason: "must be positive".to_string(),
+            });
+        }
+        if config.burst == 0 {
+            return Err(ChronosError::Validation {
+                field: "rate_limit.burst".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+
+        Ok(Self {
+            config,
+            bucket: Mutex::new(Bucket {
+                tokens: config.burst as f64,
+                refilled_at: Instant::now(),
+            }),
+        })
+    }
+
+    /// Take one permit, waiting for it or failing depending on the mode
+    pub(crate) async fn acquire(&self) -> Result<(), ChronosError> {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                self.refill(&mut bucket);
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return Ok(());
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.config.requests_per_second)
+            };
+
+            if self.config.mode == RateLimitMode::FailFast {
+                return Err(ChronosError::RateLimited(format!(
+                    "limit of {} requests per second exceeded, next permit in {:?}",
+                    self.config.requests_per_second, wait
+                )));
+            }
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Calls that could be made right now without waiting
+    pub(crate) fn available_permits(&self) -> u32 {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        bucket.tokens as u32
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.config.requests_per_second).min(self.config.burst as f64);
+        bucket.refilled_at = now;
+    }
+}
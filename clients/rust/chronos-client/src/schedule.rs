@@ -0,0 +1,34 @@
+use crate::ChronosError;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A recurring trigger registered with the scheduler
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowSchedule {
+    pub workflow_id: String,
+    pub cron_expression: String,
+    pub timezone: String,
+    pub next_run_at: DateTime<Utc>,
+}
+
+/// Validate a six-field (seconds-first) cron expression and IANA timezone,
+/// returning the next fire time after now
+pub(crate) fn next_run(cron_expression: &str, timezone: &str) -> Result<DateTime<Utc>, ChronosError> {
+    let schedule = Schedule::from_str(cron_expression).map_err(|e| {
+        ChronosError::WorkflowError(format!("Invalid cron expression {:?}: {}", cron_expression, e))
+    })?;
+
+    let tz = Tz::from_str(timezone)
+        .map_err(|_| ChronosError::WorkflowError(format!("Invalid timezone: {}", timezone)))?;
+
+    schedule
+        .upcoming(tz)
+        .next()
+        .map(|next| next.with_timezone(&Utc))
+        .ok_or_else(|| {
+            ChronosError::WorkflowError(format!("Cron expression {:?} never fires", cron_expression))
+        })
+}
@@ -0,0 +1,149 @@
+use crate::{ChronosError, Result, Task, Workflow, WorkflowStatus};
+use futures::future::{BoxFuture, FutureExt, Shared, WeakShared};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// Identifies a read by the client method making it and the id it reads
+pub(crate) type ReadKey = (&'static str, String);
+
+type SharedCall<T> = Shared<BoxFuture<'static, Result<T, Arc<ChronosError>>>>;
+type WeakCall<T> = WeakShared<BoxFuture<'static, Result<T, Arc<ChronosError>>>>;
+
+/// Coalesces concurrent calls with the same key into one in-flight call
+/// whose result every caller receives.
+///
+/// Only calls that overlap share a result: the entry is removed as soon as
+/// the call completes, so a call made afterwards sends its own request and
+/// nothing is cached. A caller that joins late can still receive a response
+/// to a request sent up to one round trip before it called. The call keeps
+/// running as long as any caller is waiting on it, even if the one that
+/// started it has gone away, and is dropped once none is.
+pub(crate) struct SingleFlight<K, T> {
+    /// Weak so that a call nobody waits for any more doesn't keep running
+    calls: Arc<Mutex<HashMap<K, WeakCall<T>>>>,
+}
+
+impl<K, T> Default for SingleFlight<K, T> {
+    fn default() -> Self {
+        Self {
+            calls: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<K, T> SingleFlight<K, T>
+where
+    K: Hash + Eq + Clone + Send + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    /// Run `call`, unless a call with the same key is already in flight, in
+    /// which case wait for that one's result instead
+    pub(crate) async fn run<F>(&self, key: K, call: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        let shared = {
+            let mut calls = self.calls.lock().unwrap();
+            match calls.get(&key).and_then(WeakShared::upgrade) {
+                Some(in_flight) => in_flight,
+                None => {
+                    // Forget calls that were abandoned before they completed
+                    calls.retain(|_, call| call.upgrade().is_some());
+
+                    let registry = Arc::clone(&self.calls);
+                    let finished = key.clone();
+                    let shared: SharedCall<T> = async move {
+                        let result = call.await.map_err(Arc::new);
+                        registry.lock().unwrap().remove(&finished);
+                        result
+                    }
+                    .boxed()
+                    .shared();
+                    if let Some(weak) = shared.downgrade() {
+                        calls.insert(key, weak);
+                    }
+                    shared
+                }
+            }
+        };
+
+        shared
+            .await
+            .map_err(|error| Arc::try_unwrap(error).unwrap_or_else(|error| duplicate(&error)))
+    }
+}
+
+/// Reads that concurrent identical calls on a [`ChronosClient`](crate::ChronosClient)
+/// and its clones share
+#[derive(Default)]
+pub(crate) struct InFlightReads {
+    pub(crate) tasks: SingleFlight<ReadKey, Task>,
+    pub(crate) workflows: SingleFlight<ReadKey, Workflow>,
+    pub(crate) statuses: SingleFlight<ReadKey, WorkflowStatus>,
+}
+
+/// An equivalent error for each caller that shared a failed call.
+///
+/// The variant and message are kept; an underlying error stays reachable
+/// through `Error::source`.
+fn duplicate(error: &Arc<ChronosError>) -> ChronosError {
+    match error.as_ref() {
+        ChronosError::ConnectionError { message, source } => ChronosError::ConnectionError {
+            message: message.clone(),
+            source: source
+                .as_ref()
+                .map(|_| Box::new(SharedSource(Arc::clone(error))) as crate::BoxError),
+        },
+        ChronosError::WorkflowError(message) => ChronosError::WorkflowError(message.clone()),
+        ChronosError::TaskError(message) => ChronosError::TaskError(message.clone()),
+        ChronosError::InternalError(message) => ChronosError::InternalError(message.clone()),
+        ChronosError::Validation { field, reason } => ChronosError::Validation {
+            field: field.clone(),
+            reason: reason.clone(),
+        },
+        ChronosError::Timeout(message) => ChronosError::Timeout(message.clone()),
+        ChronosError::RateLimited(message) => ChronosError::RateLimited(message.clone()),
+        ChronosError::SerializationError(e) => {
+            ChronosError::SerializationError(serde::de::Error::custom(e.to_string()))
+        }
+        ChronosError::EncodingError(message) => ChronosError::EncodingError(message.clone()),
+        ChronosError::IntegrityError {
+            task_id,
+            expected,
+            actual,
+        } => ChronosError::IntegrityError {
+            task_id: task_id.clone(),
+            expected: expected.clone(),
+            actual: actual.clone(),
+        },
+    }
+}
+
+/// The source of a shared connection error, standing in for it in each copy
+#[derive(Debug)]
+struct SharedSource(Arc<ChronosError>);
+
+impl SharedSource {
+    fn inner(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl fmt::Display for SharedSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.inner() {
+            Some(source) => fmt::Display::fmt(source, f),
+            None => fmt::Display::fmt(&self.0, f),
+        }
+    }
+}
+
+impl Error for SharedSource {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.inner().and_then(Error::source)
+    }
+}
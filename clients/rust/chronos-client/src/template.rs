@@ -0,0 +1,210 @@
+use crate::builder::{TaskSpec, WorkflowBuilder, WorkflowSpec};
+use crate::ChronosError;
+use std::collections::{BTreeSet, HashMap};
+
+const PLACEHOLDER_OPEN: &str = "{{";
+const PLACEHOLDER_CLOSE: &str = "}}";
+
+/// A reusable workflow shape whose name, description and task payloads may
+/// contain `{{param}}` placeholders.
+///
+/// Values are substituted as raw text, so a value placed inside a JSON string
+/// must already be escaped for JSON. Task names and dependencies are fixed.
+///
+/// ```
+/// use chronos_client::{TaskSpec, WorkflowTemplate};
+/// use std::collections::HashMap;
+///
+/// let template = WorkflowTemplate::new("ETL {{date}}").task(TaskSpec {
+///     name: "extract".to_string(),
+///     task_type: "http".to_string(),
+///     payload: br#"{"url": "{{source}}/{{date}}"}"#.to_vec(),
+///     depends_on: Vec::new(),
+/// });
+///
+/// let params = HashMap::from([
+///     ("date".to_string(), "2025-01-01".to_string()),
+///     ("source".to_string(), "https://example.com".to_string()),
+/// ]);
+/// let spec = template.render(&params).unwrap();
+/// assert_eq!(spec.name, "ETL 2025-01-01");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowTemplate {
+    pub name: String,
+    pub description: String,
+    pub tasks: Vec<TaskSpec>,
+    /// Values for placeholders the caller may leave out
+    pub defaults: HashMap<String, String>,
+}
+
+impl WorkflowTemplate {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn task(mut self, task: TaskSpec) -> Self {
+        self.tasks.push(task);
+        self
+    }
+
+    pub fn default_value(mut self, param: impl Into<String>, value: impl Into<String>) -> Self {
+        self.defaults.insert(param.into(), value.into());
+        self
+    }
+
+    /// Every placeholder name used in the template
+    pub fn placeholders(&self) -> Result<BTreeSet<String>, ChronosError> {
+        let mut names = BTreeSet::new();
+        for text in self.texts() {
+            for name in placeholders_in(text)? {
+                names.insert(name.to_string());
+            }
+        }
+        Ok(names)
+    }
+
+    /// Placeholders that have no default and must be supplied on every instantiation
+    pub fn required_params(&self) -> Result<BTreeSet<String>, ChronosError> {
+        let mut required = self.placeholders()?;
+        required.retain(|name| !self.defaults.contains_key(name));
+        Ok(required)
+    }
+
+    /// Check the placeholders parse and the task graph is well formed
+    pub fn validate(&self) -> Result<(), ChronosError> {
+        self.placeholders()?;
+        builder_for(self.name.clone(), self.description.clone(), self.tasks.clone()).build()?;
+        Ok(())
+    }
+
+    /// Substitute `params` into a concrete workflow definition.
+    ///
+    /// Fails with `ChronosError::Validation` naming any required placeholder
+    /// without a value, or any parameter the template doesn't use.
+    pub fn render(&self, params: &HashMap<String, String>) -> Result<WorkflowSpec, ChronosError> {
+        let placeholders = self.placeholders()?;
+
+        let missing: Vec<&str> = placeholders
+            .iter()
+            .filter(|name| !params.contains_key(*name) && !self.defaults.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(ChronosError::Validation {
+                field: "params".to_string(),
+                reason: format!("missing values for {}", missing.join(", ")),
+            });
+        }
+
+        let mut unknown: Vec<&str> = params
+            .keys()
+            .filter(|name| !placeholders.contains(*name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            return Err(ChronosError::Validation {
+                field: "params".to_string(),
+                reason: format!("not used by the template: {}", unknown.join(", ")),
+            });
+        }
+
+        let lookup = |name: &str| {
+            params
+                .get(name)
+                .or_else(|| self.defaults.get(name))
+                .map(String::as_str)
+                .unwrap_or_default()
+        };
+
+        let tasks = self
+            .tasks
+            .iter()
+            .map(|task| TaskSpec {
+                payload: match std::str::from_utf8(&task.payload) {
+                    Ok(text) => substitute(text, lookup).into_bytes(),
+                    // Binary payloads can't hold placeholders
+                    Err(_) => task.payload.clone(),
+                },
+                ..task.clone()
+            })
+            .collect();
+
+        builder_for(
+            substitute(&self.name, lookup),
+            substitute(&self.description, lookup),
+            tasks,
+        )
+        .build()
+    }
+
+    fn texts(&self) -> impl Iterator<Item = &str> {
+        [self.name.as_str(), self.description.as_str()]
+            .into_iter()
+            .chain(self.tasks.iter().filter_map(|task| std::str::from_utf8(&task.payload).ok()))
+    }
+}
+
+/// Reuse the builder's checks for duplicate names and unknown dependencies
+fn builder_for(name: String, description: String, tasks: Vec<TaskSpec>) -> WorkflowBuilder {
+    tasks
+        .into_iter()
+        .fold(WorkflowBuilder::new(name).description(description), |builder, task| {
+            let depends_on: Vec<&str> = task.depends_on.iter().map(String::as_str).collect();
+            builder.task_with_dependencies(task.name, task.task_type, task.payload, &depends_on)
+        })
+}
+
+/// Names of the `{{name}}` placeholders in `text`, in order of appearance
+fn placeholders_in(text: &str) -> Result<Vec<&str>, ChronosError> {
+    let mut names = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        let after_open = &rest[start + PLACEHOLDER_OPEN.len()..];
+        let end = after_open.find(PLACEHOLDER_CLOSE).ok_or_else(|| ChronosError::Validation {
+            field: "template".to_string(),
+            reason: format!("unterminated placeholder in {:?}", text),
+        })?;
+
+        let name = after_open[..end].trim();
+        if name.is_empty() {
+            return Err(ChronosError::Validation {
+                field: "template".to_string(),
+                reason: format!("empty placeholder in {:?}", text),
+            });
+        }
+
+        names.push(name);
+        rest = &after_open[end + PLACEHOLDER_CLOSE.len()..];
+    }
+
+    Ok(names)
+}
+
+/// Replace every placeholder in `text`, which must already have parsed
+fn substitute<'a>(text: &str, lookup: impl Fn(&str) -> &'a str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        let after_open = &rest[start + PLACEHOLDER_OPEN.len()..];
+        let Some(end) = after_open.find(PLACEHOLDER_CLOSE) else { break };
+
+        output.push_str(&rest[..start]);
+        output.push_str(lookup(after_open[..end].trim()));
+        rest = &after_open[end + PLACEHOLDER_CLOSE.len()..];
+    }
+
+    output.push_str(rest);
+    output
+}
@@ -0,0 +1,281 @@
+//! In-memory [`ChronosApi`] implementation for unit tests.
+//!
+//! Enable with the `testing` feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! chronos-client = { version = "0.1", features = ["testing"] }
+//! ```
+
+use crate::{parse_id, ChronosApi, ChronosError, Result, Task, TaskOptions, TaskStatus, Workflow, WorkflowSpec};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[derive(Debug, Default)]
+struct MockState {
+    workflows: HashMap<String, Workflow>,
+    tasks: HashMap<String, Task>,
+    /// Task ids per workflow in insertion order
+    workflow_tasks: HashMap<String, Vec<String>>,
+    /// Workflows `start_workflow` has been called on
+    started: HashSet<String>,
+    /// Workflows paused and not yet resumed
+    paused: HashSet<String>,
+}
+
+impl MockState {
+    fn is_finished(&self, workflow_id: &str) -> bool {
+        self.started.contains(workflow_id)
+            && self
+                .tasks
+                .values()
+                .filter(|task| task.workflow_id == workflow_id)
+                .all(|task| task.status.is_terminal())
+    }
+
+    /// Mark the workflow's pending tasks `Running`
+    fn run_pending(&mut self, workflow_id: &str) {
+        let now = Utc::now();
+        for task in self.tasks.values_mut() {
+            if task.workflow_id == workflow_id && task.status == TaskStatus::Pending {
+                task.status = TaskStatus::Running;
+                task.started_at = Some(now);
+                task.updated_at = now;
+            }
+        }
+    }
+}
+
+/// A [`ChronosApi`] backed by an in-memory store instead of live services.
+///
+/// Created workflows are retrievable, added tasks show up in their workflow,
+/// and `start_workflow` marks the workflow's pending tasks `Running`. Starting
+/// a started workflow is a no-op until all its tasks are terminal, after which
+/// it fails as the live client does. Pending tasks of a paused workflow stay
+/// `Pending` until `resume_workflow`. Clones share the same store.
+#[derive(Debug, Clone, Default)]
+pub struct MockChronosClient {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockChronosClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overwrite a stored task, e.g. to simulate a worker completing it
+    pub fn set_task(&self, task: Task) {
+        self.state.lock().unwrap().tasks.insert(task.id.clone(), task);
+    }
+}
+
+fn workflow_not_found(workflow_id: &str) -> ChronosError {
+    ChronosError::WorkflowError(format!("Workflow not found: {}", workflow_id))
+}
+
+#[async_trait]
+impl ChronosApi for MockChronosClient {
+    async fn create_workflow(&self, name: &str, description: &str) -> Result<Workflow> {
+        let now = Utc::now();
+        let workflow = Workflow {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            tasks: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            external_id: None,
+            replayed_from: None,
+        };
+
+        self.state
+            .lock()
+            .unwrap()
+            .workflows
+            .insert(workflow.id.clone(), workflow.clone());
+
+        Ok(workflow)
+    }
+
+    async fn submit_workflow(&self, spec: &WorkflowSpec) -> Result<Workflow> {
+        let workflow = self.create_workflow(&spec.name, &spec.description).await?;
+
+        let mut ids_by_name = HashMap::new();
+        for task_spec in &spec.tasks {
+            let options = TaskOptions {
+                depends_on: task_spec
+                    .depends_on
+                    .iter()
+                    .filter_map(|name| ids_by_name.get(name).cloned())
+                    .collect(),
+                ..Default::default()
+            };
+            let task = self
+                .add_task_with_options(
+                    &workflow.id,
+                    &task_spec.name,
+                    &task_spec.task_type,
+                    task_spec.payload.clone(),
+                    options,
+                )
+                .await?;
+            ids_by_name.insert(task_spec.name.clone(), task.id);
+        }
+
+        self.get_workflow(&workflow.id).await
+    }
+
+    async fn add_task_with_options(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        task_type: &str,
+        payload: Vec<u8>,
+        options: TaskOptions,
+    ) -> Result<Task> {
+        // Malformed ids and backoffs fail the same way as with the real client
+        parse_id("workflow_id", workflow_id)?;
+        if let Some(backoff) = &options.retry_backoff {
+            backoff.validate()?;
+        }
+        let mut state = self.state.lock().unwrap();
+        if !state.workflows.contains_key(workflow_id) {
+            return Err(workflow_not_found(workflow_id));
+        }
+
+        let now = Utc::now();
+        let task = Task {
+            id: Uuid::new_v4().to_string(),
+            workflow_id: workflow_id.to_string(),
+            name: name.to_string(),
+            task_type: task_type.to_string(),
+            status: TaskStatus::Pending,
+            payload,
+            result: None,
+            created_at: now,
+            updated_at: now,
+            started_at: None,
+            completed_at: None,
+            depends_on: options.depends_on,
+            timeout_seconds: options.timeout_seconds,
+            tags: options.tags,
+            scheduled_at: options.run_at,
+            payload_format: options.payload_format,
+            requirements: options.requirements,
+            waiting_reason: None,
+            result_checksum: None,
+        };
+
+        state
+            .workflow_tasks
+            .entry(workflow_id.to_string())
+            .or_default()
+            .push(task.id.clone());
+        state.tasks.insert(task.id.clone(), task.clone());
+
+        Ok(task)
+    }
+
+    async fn start_workflow(&self, workflow_id: &str) -> Result<()> {
+        parse_id("workflow_id", workflow_id)?;
+        let mut state = self.state.lock().unwrap();
+        if !state.workflows.contains_key(workflow_id) {
+            return Err(workflow_not_found(workflow_id));
+        }
+
+        if state.is_finished(workflow_id) {
+            return Err(ChronosError::WorkflowError(format!(
+                "Workflow {} has already finished and cannot be started again",
+                workflow_id
+            )));
+        }
+        if !state.started.insert(workflow_id.to_string()) {
+            return Ok(());
+        }
+
+        state.run_pending(workflow_id);
+        Ok(())
+    }
+
+    async fn pause_workflow(&self, workflow_id: &str) -> Result<()> {
+        parse_id("workflow_id", workflow_id)?;
+        let mut state = self.state.lock().unwrap();
+        if !state.workflows.contains_key(workflow_id) {
+            return Err(workflow_not_found(workflow_id));
+        }
+        if state.is_finished(workflow_id) {
+            return Err(ChronosError::WorkflowError(format!(
+                "Workflow {} has already finished and cannot be paused",
+                workflow_id
+            )));
+        }
+        if !state.started.contains(workflow_id) {
+            return Err(ChronosError::WorkflowError(format!(
+                "Workflow {} has not been started and cannot be paused",
+                workflow_id
+            )));
+        }
+
+        state.paused.insert(workflow_id.to_string());
+        Ok(())
+    }
+
+    async fn resume_workflow(&self, workflow_id: &str) -> Result<()> {
+        parse_id("workflow_id", workflow_id)?;
+        let mut state = self.state.lock().unwrap();
+        if !state.workflows.contains_key(workflow_id) {
+            return Err(workflow_not_found(workflow_id));
+        }
+        if state.is_finished(workflow_id) {
+            return Err(ChronosError::WorkflowError(format!(
+                "Workflow {} has already finished and cannot be resumed",
+                workflow_id
+            )));
+        }
+        if !state.started.contains(workflow_id) {
+            return Err(ChronosError::WorkflowError(format!(
+                "Workflow {} has not been started and cannot be resumed",
+                workflow_id
+            )));
+        }
+
+        if state.paused.remove(workflow_id) {
+            state.run_pending(workflow_id);
+        }
+        Ok(())
+    }
+
+    async fn get_workflow(&self, workflow_id: &str) -> Result<Workflow> {
+        parse_id("workflow_id", workflow_id)?;
+        let state = self.state.lock().unwrap();
+        let mut workflow = state
+            .workflows
+            .get(workflow_id)
+            .cloned()
+            .ok_or_else(|| workflow_not_found(workflow_id))?;
+
+        workflow.tasks = state
+            .workflow_tasks
+            .get(workflow_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| state.tasks.get(id).cloned())
+            .collect();
+
+        Ok(workflow)
+    }
+
+    async fn get_task(&self, task_id: &str) -> Result<Task> {
+        parse_id("task_id", task_id)?;
+        self.state
+            .lock()
+            .unwrap()
+            .tasks
+            .get(task_id)
+            .cloned()
+            .ok_or_else(|| ChronosError::TaskError(format!("Task not found: {}", task_id)))
+    }
+}
@@ -0,0 +1,17 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // sqlx::migrate! embeds the migrations at compile time, so adding one
+    // must rebuild the crate
+    println!("cargo:rerun-if-changed=migrations");
+    // Printing any rerun-if-changed turns off cargo's default of rerunning on
+    // every package change, so the proto must be listed too
+    println!("cargo:rerun-if-changed=../proto/durable_engine.proto");
+
+    // The engine serves the API and chronos-admin calls it, so both halves
+    // are generated
+    tonic_prost_build::configure()
+        .build_server(true)
+        .build_client(true)
+        .compile_protos(&["../proto/durable_engine.proto"], &["../proto"])?;
+
+    Ok(())
+}
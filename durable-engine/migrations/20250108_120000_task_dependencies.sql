@@ -0,0 +1,11 @@
+CREATE TABLE task_dependencies (
+    task_id UUID NOT NULL,
+    depends_on_task_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (task_id, depends_on_task_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id),
+    FOREIGN KEY (depends_on_task_id) REFERENCES tasks(id),
+    CHECK (task_id <> depends_on_task_id)
+);
+
+CREATE INDEX idx_task_dependencies_depends_on ON task_dependencies(depends_on_task_id);
@@ -0,0 +1,4 @@
+ALTER TABLE tasks ADD COLUMN priority INT NOT NULL DEFAULT 0;
+
+-- Supports pulling queued tasks highest priority first, oldest first within a priority
+CREATE INDEX idx_tasks_state_priority ON tasks(state, priority DESC, created_at);
@@ -0,0 +1,2 @@
+-- Supports paging through tasks by state, e.g. all failed tasks across workflows
+CREATE INDEX idx_tasks_state_created_at ON tasks(state, created_at, id);
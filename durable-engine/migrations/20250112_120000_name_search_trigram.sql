@@ -0,0 +1,5 @@
+-- Trigram indexes back case-insensitive substring search over names
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_workflows_name_trgm ON workflows USING GIN (name gin_trgm_ops);
+CREATE INDEX idx_tasks_name_trgm ON tasks USING GIN (name gin_trgm_ops);
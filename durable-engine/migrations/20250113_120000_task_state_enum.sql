@@ -0,0 +1,17 @@
+-- Store task states as a Postgres enum so invalid values are rejected by the database
+CREATE TYPE task_state AS ENUM (
+    'QUEUED',
+    'RUNNING',
+    'COMPLETED',
+    'FAILED',
+    'RETRYING',
+    'CANCELLED',
+    'TIMED_OUT'
+);
+
+ALTER TABLE tasks
+    ALTER COLUMN state TYPE task_state USING state::task_state;
+
+ALTER TABLE task_events
+    ALTER COLUMN previous_state TYPE task_state USING previous_state::task_state,
+    ALTER COLUMN new_state TYPE task_state USING new_state::task_state;
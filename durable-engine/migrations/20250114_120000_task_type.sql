@@ -0,0 +1,2 @@
+-- Task type selects the executor and parameter schema; existing tasks are untyped
+ALTER TABLE tasks ADD COLUMN task_type VARCHAR(255) NOT NULL DEFAULT '';
@@ -0,0 +1,5 @@
+-- Stable caller-chosen key so declarative applies can upsert workflows
+ALTER TABLE workflows ADD COLUMN external_id VARCHAR(255) UNIQUE;
+
+-- The only field of an existing workflow an upsert may change
+ALTER TABLE workflows ADD COLUMN description TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,2 @@
+-- Set by workers while a task runs; NULL until the first heartbeat of an attempt
+ALTER TABLE tasks ADD COLUMN last_heartbeat_at TIMESTAMPTZ;
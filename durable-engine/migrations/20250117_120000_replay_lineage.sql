@@ -0,0 +1,5 @@
+-- Lineage of workflows and tasks re-run by a replay
+ALTER TABLE workflows ADD COLUMN replayed_from UUID REFERENCES workflows(id);
+ALTER TABLE tasks ADD COLUMN replayed_from UUID REFERENCES tasks(id);
+
+CREATE INDEX idx_workflows_replayed_from ON workflows(replayed_from) WHERE replayed_from IS NOT NULL;
@@ -0,0 +1,7 @@
+-- Streamed task output, in the order the worker sent it
+CREATE TABLE task_result_chunks (
+    task_id UUID NOT NULL REFERENCES tasks(id),
+    seq INT NOT NULL,
+    data BYTEA NOT NULL,
+    PRIMARY KEY (task_id, seq)
+);
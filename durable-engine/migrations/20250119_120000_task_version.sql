@@ -0,0 +1,3 @@
+-- Optimistic lock: bumped on every change to a task, so a writer holding a
+-- stale copy can detect that another engine updated it first
+ALTER TABLE tasks ADD COLUMN version INT NOT NULL DEFAULT 0;
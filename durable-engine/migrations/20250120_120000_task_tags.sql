@@ -0,0 +1,5 @@
+-- Free-form labels (team, environment, customer, ...) for slicing tasks across workflows
+ALTER TABLE tasks ADD COLUMN tags JSONB NOT NULL DEFAULT '{}'::JSONB;
+
+-- Serves containment lookups such as tags @> '{"team": "billing"}'
+CREATE INDEX idx_tasks_tags ON tasks USING GIN (tags jsonb_path_ops);
@@ -0,0 +1,5 @@
+-- Earliest time a task may start; NULL runs it as soon as it is dequeued
+ALTER TABLE tasks ADD COLUMN scheduled_at TIMESTAMPTZ;
+
+-- Lets the queued-task sweep find deferred tasks that have come due
+CREATE INDEX idx_tasks_queued_scheduled_at ON tasks(scheduled_at) WHERE state = 'QUEUED' AND scheduled_at IS NOT NULL;
@@ -0,0 +1,14 @@
+-- One row per time a task was picked up, so every attempt's outcome and error is kept
+CREATE TABLE task_attempts (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL REFERENCES tasks(id),
+    attempt INT NOT NULL,
+    worker_id VARCHAR(255) NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    -- State the attempt left the task in; NULL while it is still running
+    outcome task_state,
+    error TEXT
+);
+
+CREATE INDEX idx_task_attempts_task_id ON task_attempts(task_id, attempt);
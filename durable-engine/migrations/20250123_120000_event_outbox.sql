@@ -0,0 +1,10 @@
+-- Task events awaiting publication to Kafka. Rows are written in the same
+-- transaction as the event, so a crash after commit can't lose one: the
+-- relay publishes whatever is still unpublished.
+CREATE TABLE event_outbox (
+    event_id UUID PRIMARY KEY REFERENCES task_events(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    published_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_event_outbox_unpublished ON event_outbox(created_at) WHERE published_at IS NULL;
@@ -0,0 +1,11 @@
+-- How a task's parameters are encoded. JSON parameters stay in the parameters
+-- column; MessagePack and protobuf payloads are stored as raw bytes in payload
+CREATE TYPE payload_format AS ENUM (
+    'JSON',
+    'MSGPACK',
+    'PROTOBUF'
+);
+
+ALTER TABLE tasks
+    ADD COLUMN payload_format payload_format NOT NULL DEFAULT 'JSON',
+    ADD COLUMN payload BYTEA;
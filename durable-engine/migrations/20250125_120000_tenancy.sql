@@ -0,0 +1,13 @@
+-- Owner of every workflow and task. Rows created before multi-tenancy belong
+-- to the default tenant, the nil UUID, as do requests that name no tenant
+ALTER TABLE workflows
+    ADD COLUMN tenant_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000',
+    ADD COLUMN created_by VARCHAR(255) NOT NULL DEFAULT '';
+
+ALTER TABLE tasks
+    ADD COLUMN tenant_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000',
+    ADD COLUMN created_by VARCHAR(255) NOT NULL DEFAULT '';
+
+-- Every client-facing query filters by tenant first
+CREATE INDEX idx_workflows_tenant ON workflows(tenant_id);
+CREATE INDEX idx_tasks_tenant_state ON tasks(tenant_id, state, created_at);
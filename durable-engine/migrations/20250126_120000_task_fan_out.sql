@@ -0,0 +1,9 @@
+-- A task can complete with a list of subtasks instead of a result. The
+-- subtasks point back at the task that created them, which waits on them and
+-- then completes with their results
+ALTER TABLE tasks
+    ADD COLUMN parent_task_id UUID REFERENCES tasks(id) ON DELETE CASCADE,
+    -- Set once a task fans out: its subtasks in the order it listed them
+    ADD COLUMN fan_out_children UUID[];
+
+CREATE INDEX idx_tasks_parent ON tasks(parent_task_id) WHERE parent_task_id IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Labels a worker must advertise to run the task, e.g. {"gpu": "true"}.
+-- Engines only claim tasks whose requirements their worker labels contain
+ALTER TABLE tasks
+    ADD COLUMN requirements JSONB NOT NULL DEFAULT '{}'::JSONB,
+    -- Why a QUEUED task hasn't started, e.g. no worker matched its requirements
+    ADD COLUMN waiting_reason TEXT;
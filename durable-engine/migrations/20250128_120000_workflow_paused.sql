@@ -0,0 +1,2 @@
+-- Paused workflows keep their QUEUED tasks queued; tasks already running finish
+ALTER TABLE workflows ADD COLUMN paused BOOLEAN NOT NULL DEFAULT FALSE;
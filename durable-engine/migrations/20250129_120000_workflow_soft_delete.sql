@@ -0,0 +1,6 @@
+-- Deleted workflows are hidden from listings but kept, with their tasks and
+-- history, until the retention job purges them
+ALTER TABLE workflows ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX idx_workflows_deleted_at ON workflows(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_workflows_completed_at ON workflows(completed_at) WHERE completed_at IS NOT NULL;
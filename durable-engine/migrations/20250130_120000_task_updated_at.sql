@@ -0,0 +1,3 @@
+-- Serves polling for changes with ListTasks/ListTasksByTag updated_since, and
+-- the aggregates behind their version tokens
+CREATE INDEX idx_tasks_tenant_updated_at ON tasks(tenant_id, updated_at, id);
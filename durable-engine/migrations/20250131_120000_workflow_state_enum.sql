@@ -0,0 +1,44 @@
+-- Workflows get states of their own rather than borrowing task state names.
+-- DRAFT replaces CREATED and PAUSED replaces the paused flag; a paused draft
+-- becomes a plain draft, as nothing in it runs until it is started anyway.
+CREATE TYPE workflow_state AS ENUM (
+    'DRAFT',
+    'RUNNING',
+    'PAUSED',
+    'COMPLETED',
+    'FAILED',
+    'CANCELLED'
+);
+
+ALTER TABLE workflows
+    ALTER COLUMN state TYPE workflow_state USING (
+        CASE
+            WHEN state = 'CREATED' THEN 'DRAFT'
+            WHEN state IN ('COMPLETED', 'FAILED', 'CANCELLED') THEN state
+            WHEN state = 'TIMED_OUT' THEN 'FAILED'
+            WHEN paused THEN 'PAUSED'
+            ELSE 'RUNNING'
+        END
+    )::workflow_state;
+
+ALTER TABLE workflows DROP COLUMN paused;
+
+-- Settle started workflows whose tasks had all finished before workflow
+-- states were tracked
+UPDATE workflows w
+SET state = CASE
+        WHEN t.failed THEN 'FAILED'::workflow_state
+        WHEN t.cancelled THEN 'CANCELLED'::workflow_state
+        ELSE 'COMPLETED'::workflow_state
+    END,
+    completed_at = COALESCE(w.completed_at, t.last_completed_at, NOW())
+FROM (
+    SELECT workflow_id,
+        bool_and(state IN ('COMPLETED', 'FAILED', 'CANCELLED', 'TIMED_OUT')) AS finished,
+        bool_or(state IN ('FAILED', 'TIMED_OUT')) AS failed,
+        bool_or(state = 'CANCELLED') AS cancelled,
+        MAX(completed_at) AS last_completed_at
+    FROM tasks
+    GROUP BY workflow_id
+) t
+WHERE t.workflow_id = w.id AND t.finished AND w.state IN ('RUNNING', 'PAUSED');
@@ -0,0 +1,3 @@
+-- Hex SHA-256 of a task's result as clients receive it, so truncation or
+-- corruption anywhere between worker and reader is detected
+ALTER TABLE tasks ADD COLUMN result_checksum CHAR(64);
@@ -0,0 +1,3 @@
+-- Per-task retry backoff as BackoffStrategy JSON; NULL uses the engine's
+-- TASK_RETRY_BACKOFF
+ALTER TABLE tasks ADD COLUMN retry_backoff JSONB;
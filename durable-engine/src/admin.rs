@@ -0,0 +1,282 @@
+//! `chronos-admin`: inspect and intervene in a durable engine from the shell.
+//!
+//! Talks to the engine at `DURABLE_ENGINE_URL` (default `localhost:50051`).
+//! Task commands act for the tenant in `CHRONOS_TENANT_ID`, which they
+//! require; `requeue`, `pause` and `resume` are admin RPCs and send
+//! `ADMIN_TOKEN` as a bearer token instead.
+
+mod client;
+
+use anyhow::{bail, Context, Result};
+use client::durable_engine::durable_engine_service_client::DurableEngineServiceClient;
+use client::durable_engine::{
+    CancelTaskRequest, GetTaskRequest, ListTasksRequest, RequeueFailedTasksRequest, SetProcessingEnabledRequest, Task,
+};
+use client::ClientConfig;
+use tonic::metadata::MetadataValue;
+use tonic::transport::Channel;
+use tonic::Request;
+
+const USAGE: &str = "\
+Usage: chronos-admin <command>
+
+Commands:
+  get-task <task-id>                          Show a task
+  list-failed --workflow <workflow-id>        List a workflow's FAILED tasks
+  requeue <workflow-id> [--keep-retry-count]  Put a workflow's FAILED tasks back in the queue
+  cancel <task-id> [--reason <reason>]        Cancel a task and its queued dependents
+  pause                                       Stop the engine taking new work
+  resume                                      Let the engine take new work again
+
+Environment:
+  DURABLE_ENGINE_URL  Engine address (default localhost:50051)
+  CHRONOS_TENANT_ID   Tenant for get-task, list-failed and cancel
+  CHRONOS_PRINCIPAL   Recorded as the caller (default chronos-admin)
+  ADMIN_TOKEN         Bearer token for requeue, pause and resume";
+
+/// Tasks fetched per ListTasks call by `list-failed`
+const PAGE_SIZE: i32 = 100;
+
+#[derive(Debug)]
+enum Command {
+    GetTask { task_id: String },
+    ListFailed { workflow_id: String },
+    Requeue { workflow_id: String, keep_retry_count: bool },
+    Cancel { task_id: String, reason: String },
+    SetProcessing { enabled: bool },
+}
+
+impl Command {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let Some(name) = args.next() else {
+            bail!("no command given");
+        };
+        let mut positional = Vec::new();
+        let mut workflow = None;
+        let mut reason = None;
+        let mut keep_retry_count = false;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--workflow" => workflow = Some(args.next().context("--workflow needs a workflow id")?),
+                "--reason" => reason = Some(args.next().context("--reason needs a value")?),
+                "--keep-retry-count" => keep_retry_count = true,
+                flag if flag.starts_with("--") => bail!("unknown option {}", flag),
+                _ => positional.push(arg),
+            }
+        }
+
+        let command = match (name.as_str(), positional.as_slice()) {
+            ("get-task", [task_id]) => Command::GetTask { task_id: task_id.clone() },
+            ("list-failed", []) => Command::ListFailed {
+                workflow_id: workflow.take().context("list-failed needs --workflow <workflow-id>")?,
+            },
+            ("requeue", [workflow_id]) => Command::Requeue {
+                workflow_id: workflow_id.clone(),
+                keep_retry_count,
+            },
+            ("cancel", [task_id]) => Command::Cancel {
+                task_id: task_id.clone(),
+                reason: reason.take().unwrap_or_else(|| "Cancelled with chronos-admin".to_string()),
+            },
+            ("pause", []) => Command::SetProcessing { enabled: false },
+            ("resume", []) => Command::SetProcessing { enabled: true },
+            ("get-task" | "list-failed" | "requeue" | "cancel" | "pause" | "resume", _) => {
+                bail!("wrong arguments for {}", name)
+            }
+            _ => bail!("unknown command {}", name),
+        };
+        if workflow.is_some() || reason.is_some() || (keep_retry_count && !matches!(command, Command::Requeue { .. })) {
+            bail!("option not accepted by {}", name);
+        }
+        Ok(command)
+    }
+}
+
+/// Identity sent with every call, as the gateway would forward it
+struct Credentials {
+    tenant_id: Option<String>,
+    principal: String,
+    admin_token: Option<String>,
+}
+
+impl Credentials {
+    fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        Self {
+            tenant_id: var("CHRONOS_TENANT_ID"),
+            principal: var("CHRONOS_PRINCIPAL").unwrap_or_else(|| "chronos-admin".to_string()),
+            admin_token: var("ADMIN_TOKEN"),
+        }
+    }
+
+    /// A request carrying the principal header
+    fn principal_request<T>(&self, message: T) -> Result<Request<T>> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert("x-chronos-principal", self.principal.parse().context("Invalid CHRONOS_PRINCIPAL")?);
+        Ok(request)
+    }
+
+    /// A request carrying the tenant and principal headers
+    fn tenant_request<T>(&self, message: T) -> Result<Request<T>> {
+        let tenant_id = self.tenant_id.as_deref().context("CHRONOS_TENANT_ID must be set for task commands")?;
+        let mut request = self.principal_request(message)?;
+        request
+            .metadata_mut()
+            .insert("x-chronos-tenant-id", tenant_id.parse().context("Invalid CHRONOS_TENANT_ID")?);
+        Ok(request)
+    }
+
+    /// A request carrying the admin bearer token
+    fn admin_request<T>(&self, message: T) -> Result<Request<T>> {
+        let token = self.admin_token.as_deref().context("ADMIN_TOKEN must be set for admin commands")?;
+        let mut request = self.principal_request(message)?;
+        let value: MetadataValue<_> = format!("Bearer {}", token).parse().context("Invalid ADMIN_TOKEN")?;
+        request.metadata_mut().insert("authorization", value);
+        Ok(request)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().is_none_or(|arg| arg == "-h" || arg == "--help" || arg == "help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let command = match Command::parse(args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let credentials = Credentials::from_env();
+    let mut client = client::create_client(&engine_url(), &ClientConfig::default()).await?;
+    run(&mut client, &credentials, command).await
+}
+
+/// `DURABLE_ENGINE_URL`, given a scheme if it has none, as in docker-compose
+fn engine_url() -> String {
+    let url = std::env::var("DURABLE_ENGINE_URL").unwrap_or_else(|_| "localhost:50051".to_string());
+    if url.contains("://") {
+        url
+    } else {
+        format!("http://{}", url)
+    }
+}
+
+async fn run(client: &mut DurableEngineServiceClient<Channel>, credentials: &Credentials, command: Command) -> Result<()> {
+    match command {
+        Command::GetTask { task_id } => {
+            let response = client
+                .get_task(credentials.tenant_request(GetTaskRequest { task_id: task_id.clone() })?)
+                .await
+                .with_context(|| format!("Failed to get task {}", task_id))?
+                .into_inner();
+            let task = response.task.with_context(|| format!("Task {} not found", task_id))?;
+            print_tasks(&[task]);
+        }
+        Command::ListFailed { workflow_id } => {
+            let mut tasks = Vec::new();
+            let mut offset = 0;
+            while offset >= 0 {
+                let request = ListTasksRequest {
+                    state: "FAILED".to_string(),
+                    workflow_id: workflow_id.clone(),
+                    page_size: PAGE_SIZE,
+                    offset,
+                    updated_since: String::new(),
+                    version_token: String::new(),
+                };
+                let page = client
+                    .list_tasks(credentials.tenant_request(request)?)
+                    .await
+                    .with_context(|| format!("Failed to list failed tasks of workflow {}", workflow_id))?
+                    .into_inner();
+                tasks.extend(page.tasks);
+                offset = page.next_offset;
+            }
+            if tasks.is_empty() {
+                println!("Workflow {} has no failed tasks", workflow_id);
+            } else {
+                print_tasks(&tasks);
+            }
+        }
+        Command::Requeue { workflow_id, keep_retry_count } => {
+            let request = RequeueFailedTasksRequest {
+                workflow_id: workflow_id.clone(),
+                keep_retry_count,
+            };
+            let response = client
+                .requeue_failed_tasks(credentials.admin_request(request)?)
+                .await
+                .with_context(|| format!("Failed to requeue tasks of workflow {}", workflow_id))?
+                .into_inner();
+            println!("Requeued {} failed tasks of workflow {}", response.requeued, workflow_id);
+        }
+        Command::Cancel { task_id, reason } => {
+            let request = CancelTaskRequest {
+                task_id: task_id.clone(),
+                reason,
+            };
+            let response = client
+                .cancel_task(credentials.tenant_request(request)?)
+                .await
+                .with_context(|| format!("Failed to cancel task {}", task_id))?
+                .into_inner();
+            print_table(&["TASK", "STATE"], vec![vec![response.task_id, response.state]]);
+        }
+        Command::SetProcessing { enabled } => {
+            let response = client
+                .set_processing_enabled(credentials.admin_request(SetProcessingEnabledRequest { enabled })?)
+                .await
+                .context("Failed to set processing")?
+                .into_inner();
+            let describe = |enabled: bool| if enabled { "running" } else { "paused" };
+            print_table(
+                &["PROCESSING", "PREVIOUSLY"],
+                vec![vec![
+                    describe(response.enabled).to_string(),
+                    describe(response.previously_enabled).to_string(),
+                ]],
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_tasks(tasks: &[Task]) {
+    let rows = tasks
+        .iter()
+        .map(|task| vec![task.id.clone(), task.workflow_id.clone(), task.name.clone(), task.state.clone()])
+        .collect();
+    print_table(&["ID", "WORKFLOW", "NAME", "STATE"], rows);
+}
+
+/// Print rows under `headers`, each column padded to its widest value
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", format_row(headers.to_vec()));
+    for row in &rows {
+        println!("{}", format_row(row.iter().map(String::as_str).collect()));
+    }
+}
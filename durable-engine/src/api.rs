@@ -1,109 +1,2441 @@
+use crate::auth::{AdminAuth, Principal};
+use crate::backoff::BackoffStrategy;
+use crate::blob::{self, OffloadError, PayloadStore};
+use crate::database::{
+    self, CancelOutcome, CancelWorkflowOutcome, DbError, DeleteWorkflowOutcome, ForceOutcome, NewTask,
+    PauseWorkflowOutcome, SearchKind, StartWorkflowOutcome, StateUpdate,
+};
+use crate::engine::{TaskEngine, COLLECTOR_TASK_TYPE};
+use crate::events::EventPublisher;
+use crate::models::{PayloadFormat, Task, TaskState, WorkflowState};
+use crate::propagation::{self, request_id, server_span};
+use crate::queue::{self, ControlMessage, KafkaConfig};
+use crate::references;
+use crate::schema::{SchemaRegistry, SchemaViolation};
+use crate::timeouts::TimeoutRegistry;
+use crate::validation::{self, Severity, TaskDefinition};
 use anyhow::Result;
+use futures::stream::{self, BoxStream, StreamExt};
+use rdkafka::producer::FutureProducer;
 use sqlx::PgPool;
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use tonic::{transport::Server, Request, Response, Status};
-use tracing::info;
+use std::sync::Arc;
+use durable_engine::durable_engine_service_server::DurableEngineServiceServer;
+use tonic::codec::CompressionEncoding;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::server::TcpIncoming;
+use tonic::{transport::Server, Code, Request, Response, Status};
+use tonic_types::{ErrorDetails, FieldViolation, StatusExt};
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
 
-// In a real implementation, this would be generated from the proto files
-// For this sample, we'll define a simplified version manually
-pub mod durable_engine {
-    tonic::include_proto!("durable_engine");
-    
-    // Since we don't have actual proto files yet, we'll define these manually
-    #[derive(Debug)]
-    pub struct Task {
-        pub id: String,
-        pub workflow_id: String,
-        pub name: String,
-        pub state: String,
-    }
-    
-    #[derive(Debug)]
-    pub struct GetTaskRequest {
-        pub task_id: String,
-    }
-    
-    #[derive(Debug)]
-    pub struct GetTaskResponse {
-        pub task: Option<Task>,
+/// Page size used by ListTasks and Search when the request leaves it unset
+const DEFAULT_PAGE_SIZE: i32 = 50;
+const MAX_PAGE_SIZE: i32 = 500;
+/// Most task ids a single GetTasks request may ask for
+const MAX_GET_TASKS_BATCH: usize = 500;
+/// Most tasks a single StartTasks request may create
+const MAX_START_TASKS_BATCH: usize = 500;
+
+/// tonic's default cap on a decoded message; larger requests fail with OUT_OF_RANGE
+pub const DEFAULT_MAX_DECODING_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Largest gRPC messages the server accepts and sends
+#[derive(Debug, Clone, Copy)]
+pub struct MessageSizeLimits {
+    /// Largest request the server will decode
+    pub max_decoding_message_size: usize,
+    /// Largest response the server will send
+    pub max_encoding_message_size: usize,
+}
+
+impl MessageSizeLimits {
+    /// Read from `GRPC_MAX_DECODING_MESSAGE_SIZE` (default 4 MiB, tonic's
+    /// default) and `GRPC_MAX_ENCODING_MESSAGE_SIZE` (default unlimited);
+    /// both are in bytes and must be positive
+    pub fn from_env() -> Result<Self> {
+        let max_decoding_message_size: usize =
+            database::env_or("GRPC_MAX_DECODING_MESSAGE_SIZE", DEFAULT_MAX_DECODING_MESSAGE_SIZE)?;
+        let max_encoding_message_size: usize = database::env_or("GRPC_MAX_ENCODING_MESSAGE_SIZE", usize::MAX)?;
+        
+        if max_decoding_message_size == 0 {
+            anyhow::bail!("GRPC_MAX_DECODING_MESSAGE_SIZE must be positive");
+        }
+        if max_encoding_message_size == 0 {
+            anyhow::bail!("GRPC_MAX_ENCODING_MESSAGE_SIZE must be positive");
+        }
+        
+        Ok(Self {
+            max_decoding_message_size,
+            max_encoding_message_size,
+        })
     }
-    
-    #[derive(Debug)]
-    pub struct UpdateTaskStateRequest {
-        pub task_id: String,
-        pub new_state: String,
+}
+
+/// Settings of the gRPC server, apart from the handles it shares with the engine
+pub struct ServerConfig {
+    pub retry_limits: RetryLimits,
+    pub admin: AdminAuth,
+    pub limits: MessageSizeLimits,
+    /// How often WatchWorkflow re-reads a workflow's progress
+    pub watch_poll_interval: std::time::Duration,
+    /// Where new tasks and cancellations are published
+    pub kafka: KafkaConfig,
+}
+
+/// How often WatchWorkflow re-reads a workflow's progress, from
+/// `WATCH_POLL_INTERVAL_MS` (default 1000); must be positive
+pub fn watch_poll_interval_from_env() -> Result<std::time::Duration> {
+    let interval_ms: u64 = database::env_or("WATCH_POLL_INTERVAL_MS", 1000)?;
+    if interval_ms == 0 {
+        anyhow::bail!("WATCH_POLL_INTERVAL_MS must be positive");
     }
-    
-    #[derive(Debug)]
-    pub struct UpdateTaskStateResponse {
-        pub success: bool,
+    Ok(std::time::Duration::from_millis(interval_ms))
+}
+
+/// Bounds on how often a task may be retried, applied when it is created
+#[derive(Debug, Clone, Copy)]
+pub struct RetryLimits {
+    /// Used when StartTask leaves max_retries unset
+    pub default_max_retries: i32,
+    /// Larger requested values are lowered to this, so a task can't retry
+    /// often enough to hammer whatever it calls
+    pub max_retries_ceiling: i32,
+}
+
+impl RetryLimits {
+    /// Read from `DEFAULT_MAX_RETRIES` (default 3) and `MAX_RETRIES_CEILING`
+    /// (default 25); neither may be negative, and the default may not exceed
+    /// the ceiling
+    pub fn from_env() -> Result<Self> {
+        let default_max_retries: i32 = database::env_or("DEFAULT_MAX_RETRIES", 3)?;
+        let max_retries_ceiling: i32 = database::env_or("MAX_RETRIES_CEILING", 25)?;
+        
+        if default_max_retries < 0 {
+            anyhow::bail!("DEFAULT_MAX_RETRIES must not be negative");
+        }
+        if max_retries_ceiling < default_max_retries {
+            anyhow::bail!(
+                "DEFAULT_MAX_RETRIES ({}) exceeds MAX_RETRIES_CEILING ({})",
+                default_max_retries,
+                max_retries_ceiling
+            );
+        }
+        
+        Ok(Self {
+            default_max_retries,
+            max_retries_ceiling,
+        })
     }
     
-    #[tonic::async_trait]
-    pub trait DurableEngine {
-        async fn get_task(
-            &self,
-            request: Request<GetTaskRequest>,
-        ) -> Result<Response<GetTaskResponse>, Status>;
-        
-        async fn update_task_state(
-            &self,
-            request: Request<UpdateTaskStateRequest>,
-        ) -> Result<Response<UpdateTaskStateResponse>, Status>;
+    /// The max_retries a new task gets for the requested value
+    fn effective(&self, requested: Option<i32>) -> Result<i32, Status> {
+        match requested {
+            None => Ok(self.default_max_retries),
+            Some(retries) if retries < 0 => Err(invalid_argument("max_retries", "must not be negative")),
+            Some(retries) => Ok(retries.min(self.max_retries_ceiling)),
+        }
     }
 }
 
+pub mod durable_engine {
+    tonic::include_proto!("durable_engine");
+}
+
 pub struct DurableEngineService {
     db_pool: PgPool,
+    payloads: PayloadStore,
+    schemas: Arc<SchemaRegistry>,
+    events: EventPublisher,
+    retry_limits: RetryLimits,
+    timeouts: TimeoutRegistry,
+    /// Records what workers report about the tasks they run
+    engine: TaskEngine,
+    admin: AdminAuth,
+    producer: FutureProducer,
+    task_topic: String,
+    control_topic: String,
+    watch_poll_interval: std::time::Duration,
 }
 
 #[tonic::async_trait]
-impl durable_engine::DurableEngine for DurableEngineService {
+impl durable_engine::durable_engine_service_server::DurableEngineService for DurableEngineService {
+    type WatchWorkflowStream = BoxStream<'static, Result<durable_engine::GetWorkflowStatusResponse, Status>>;
+    type StreamTaskResultStream = BoxStream<'static, Result<durable_engine::TaskResultChunk, Status>>;
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn start_task(
+        &self,
+        request: Request<durable_engine::StartTaskRequest>,
+    ) -> Result<Response<durable_engine::StartTaskResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/StartTask");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        let workflow = self.open_workflow(&principal, workflow_id).await?;
+        let new_task = self.prepare_task(&principal, workflow_id, req).await?;
+        
+        let (task, event) = database::create_task(&self.db_pool, &new_task)
+            .await
+            .map_err(|e| db_status(e, "Failed to create task"))?;
+        self.events.publish(event).await;
+        self.queue_new_task(&workflow, &task).await;
+        
+        Ok(Response::new(durable_engine::StartTaskResponse {
+            task_id: task.id.to_string(),
+            state: task.state.to_string(),
+            max_retries: task.max_retries,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn start_tasks(
+        &self,
+        request: Request<durable_engine::StartTasksRequest>,
+    ) -> Result<Response<durable_engine::StartTasksResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/StartTasks");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        if req.tasks.len() > MAX_START_TASKS_BATCH {
+            return Err(invalid_argument(
+                "tasks",
+                format!("at most {} tasks per request, got {}", MAX_START_TASKS_BATCH, req.tasks.len()),
+            ));
+        }
+        let workflow = self.open_workflow(&principal, workflow_id).await?;
+        
+        let mut new_tasks = Vec::with_capacity(req.tasks.len());
+        for (index, task) in req.tasks.into_iter().enumerate() {
+            if !task.workflow_id.is_empty() && task.workflow_id != req.workflow_id {
+                let status = invalid_argument("workflow_id", "must be empty or the workflow_id of the request");
+                return Err(at_index(index, status));
+            }
+            let new_task = self
+                .prepare_task(&principal, workflow_id, task)
+                .await
+                .map_err(|status| at_index(index, status))?;
+            new_tasks.push(new_task);
+        }
+        
+        let (tasks, events): (Vec<_>, Vec<_>) = database::create_tasks(&self.db_pool, &new_tasks)
+            .await
+            .map_err(|e| db_status(e, "Failed to create tasks"))?
+            .into_iter()
+            .unzip();
+        self.events.publish(events.into_iter().flatten()).await;
+        for task in &tasks {
+            self.queue_new_task(&workflow, task).await;
+        }
+        
+        Ok(Response::new(durable_engine::StartTasksResponse {
+            tasks: tasks
+                .into_iter()
+                .map(|task| durable_engine::StartTaskResponse {
+                    task_id: task.id.to_string(),
+                    state: task.state.to_string(),
+                    max_retries: task.max_retries,
+                })
+                .collect(),
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
     async fn get_task(
         &self,
         request: Request<durable_engine::GetTaskRequest>,
     ) -> Result<Response<durable_engine::GetTaskResponse>, Status> {
-        let task_id = request.into_inner().task_id;
+        let _span = server_span(&request, "DurableEngine/GetTask");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
         
-        // In a real implementation, this would query the database
-        // For this sample, we'll return a mock response
-        let task = durable_engine::Task {
-            id: task_id,
-            workflow_id: "mock-workflow-id".to_string(),
-            name: "mock-task".to_string(),
-            state: "RUNNING".to_string(),
-        };
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        let task = self.require_visible_task(&principal, task_id).await?;
         
         Ok(Response::new(durable_engine::GetTaskResponse {
-            task: Some(task),
+            task: Some(task_message(task)),
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn get_tasks(
+        &self,
+        request: Request<durable_engine::GetTasksRequest>,
+    ) -> Result<Response<durable_engine::GetTasksResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/GetTasks");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        if req.task_ids.len() > MAX_GET_TASKS_BATCH {
+            return Err(invalid_argument(
+                "task_ids",
+                format!("at most {} ids per request, got {}", MAX_GET_TASKS_BATCH, req.task_ids.len()),
+            ));
+        }
+        let mut task_ids = Vec::with_capacity(req.task_ids.len());
+        for (i, id) in req.task_ids.iter().enumerate() {
+            let task_id = parse_uuid(&format!("task_ids[{}]", i), id)?;
+            if !task_ids.contains(&task_id) {
+                task_ids.push(task_id);
+            }
+        }
+        
+        // Other tenants' tasks are reported missing, as GetTask reports them not found
+        let tasks: std::collections::HashMap<String, durable_engine::Task> =
+            database::get_tasks_by_ids(&self.db_pool, principal.tenant_id, &task_ids)
+                .await
+                .map_err(|e| db_status(e, "Failed to get tasks"))?
+                .into_iter()
+                .map(|task| (task.id.to_string(), task_message(task)))
+                .collect();
+        let missing_ids = task_ids
+            .iter()
+            .map(Uuid::to_string)
+            .filter(|id| !tasks.contains_key(id))
+            .collect();
+        
+        Ok(Response::new(durable_engine::GetTasksResponse { tasks, missing_ids }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn get_task_attempts(
+        &self,
+        request: Request<durable_engine::GetTaskAttemptsRequest>,
+    ) -> Result<Response<durable_engine::GetTaskAttemptsResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/GetTaskAttempts");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        self.require_visible_task(&principal, task_id).await?;
+        
+        let attempts = database::get_attempts_by_task(&self.db_pool, task_id)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to get attempts of task {}", task_id)))?;
+        
+        Ok(Response::new(durable_engine::GetTaskAttemptsResponse {
+            attempts: attempts
+                .into_iter()
+                .map(|attempt| durable_engine::TaskAttempt {
+                    attempt: attempt.attempt,
+                    worker_id: attempt.worker_id,
+                    started_at: attempt.started_at.to_rfc3339(),
+                    finished_at: attempt.finished_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                    outcome: attempt.outcome.map(|state| state.to_string()).unwrap_or_default(),
+                    error: attempt.error.unwrap_or_default(),
+                })
+                .collect(),
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn get_task_timeline(
+        &self,
+        request: Request<durable_engine::GetTaskTimelineRequest>,
+    ) -> Result<Response<durable_engine::GetTaskTimelineResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/GetTaskTimeline");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        self.require_visible_task(&principal, task_id).await?;
+        
+        let events = database::get_task_timeline(&self.db_pool, task_id)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to get timeline of task {}", task_id)))?;
+        
+        Ok(Response::new(durable_engine::GetTaskTimelineResponse {
+            events: events
+                .into_iter()
+                .map(|event| durable_engine::TaskTimelineEvent {
+                    id: event.id.to_string(),
+                    event_type: event.event_type,
+                    previous_state: event.previous_state.map(|state| state.to_string()).unwrap_or_default(),
+                    new_state: event.new_state.to_string(),
+                    timestamp: event.timestamp.to_rfc3339(),
+                    metadata: event.metadata.map(|metadata| metadata.to_string()).unwrap_or_default(),
+                    reset_reason: event.reset_reason.map(|reason| reason.to_string()).unwrap_or_default(),
+                })
+                .collect(),
         }))
     }
     
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
     async fn update_task_state(
         &self,
         request: Request<durable_engine::UpdateTaskStateRequest>,
     ) -> Result<Response<durable_engine::UpdateTaskStateResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/UpdateTaskState");
+        let principal = Principal::from_request(&request)?;
         let req = request.into_inner();
         
-        // In a real implementation, this would update the database
-        // For this sample, we'll just log and return success
-        info!("Updating task {} state to {}", req.task_id, req.new_state);
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        let new_state = req
+            .new_state
+            .parse::<TaskState>()
+            .map_err(|e| invalid_argument("new_state", e.to_string()))?;
+        
+        let reason = Some(req.reason.as_str()).filter(|reason| !reason.is_empty());
+        
+        let outcome = database::update_task_state(&self.db_pool, principal.tenant_id, task_id, new_state, req.expected_version, reason)
+            .await
+            // A conflict means the caller should re-read the task and decide again
+            .map_err(|e| db_status(e, format!("Failed to update task {}", task_id)))?;
+        
+        let (version, workflow_id, previous_state, events) = match outcome {
+            StateUpdate::Updated { version, workflow_id, previous_state, events } => {
+                (version, workflow_id, previous_state, events)
+            }
+            StateUpdate::NotAllowed(state) => {
+                return Err(Status::failed_precondition(format!(
+                    "Task {} can't move from {} to {}",
+                    task_id, state, new_state
+                )));
+            }
+        };
+        self.events.publish(events).await;
+        
+        if previous_state == TaskState::Running {
+            self.engine.remove_active_task(task_id).await;
+        }
+        match new_state {
+            // Reconciliation picks it up anyway if the publish is lost
+            TaskState::Queued => {
+                if let Err(e) = queue::publish_task(&self.producer, &self.task_topic, task_id, workflow_id).await {
+                    warn!("Failed to publish requeued task {}: {:?}", task_id, e);
+                }
+            }
+            TaskState::Completed => self.queue_ready_dependents(task_id, workflow_id).await?,
+            _ => {}
+        }
+        
+        info!("Updated task {} state from {} to {} (version {})", task_id, previous_state, new_state, version);
         
         Ok(Response::new(durable_engine::UpdateTaskStateResponse {
             success: true,
+            message: String::new(),
+            version,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn complete_task(
+        &self,
+        request: Request<durable_engine::CompleteTaskRequest>,
+    ) -> Result<Response<durable_engine::CompleteTaskResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/CompleteTask");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        let result = match req.result.as_str() {
+            "" => serde_json::Value::Null,
+            result => serde_json::from_str(result)
+                .map_err(|e| invalid_argument("result", format!("not valid JSON: {}", e)))?,
+        };
+        self.require_running_task(&principal, task_id).await?;
+        
+        self.engine
+            .complete_task(task_id, result)
+            .await
+            .map_err(|e| report_status(e, format!("Failed to complete task {}", task_id)))?;
+        
+        Ok(Response::new(durable_engine::CompleteTaskResponse {
+            success: true,
+            message: String::new(),
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn fail_task(
+        &self,
+        request: Request<durable_engine::FailTaskRequest>,
+    ) -> Result<Response<durable_engine::FailTaskResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/FailTask");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        if req.error.trim().is_empty() {
+            return Err(invalid_argument("error", "must not be empty"));
+        }
+        self.require_running_task(&principal, task_id).await?;
+        
+        let will_retry = if req.timed_out {
+            self.engine.time_out_task(task_id, &req.error, req.retry).await
+        } else {
+            self.engine.fail_task(task_id, &req.error, req.retry).await
+        }
+        .map_err(|e| report_status(e, format!("Failed to fail task {}", task_id)))?;
+        
+        Ok(Response::new(durable_engine::FailTaskResponse {
+            success: true,
+            message: String::new(),
+            will_retry,
+        }))
+    }
+    
+    // The engine claims tasks itself, so it doesn't hand them out to external workers
+    async fn poll_for_tasks(
+        &self,
+        _request: Request<durable_engine::PollForTasksRequest>,
+    ) -> Result<Response<durable_engine::PollForTasksResponse>, Status> {
+        Err(Status::unimplemented("PollForTasks is not served; the engine claims tasks from the task queue"))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn list_tasks(
+        &self,
+        request: Request<durable_engine::ListTasksRequest>,
+    ) -> Result<Response<durable_engine::ListTasksResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/ListTasks");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let state = req
+            .state
+            .parse::<TaskState>()
+            .map_err(|e| invalid_argument("state", e.to_string()))?;
+        let workflow_id = match req.workflow_id.as_str() {
+            "" => None,
+            id => Some(parse_uuid("workflow_id", id)?),
+        };
+        if req.offset < 0 {
+            return Err(invalid_argument("offset", "must not be negative"));
+        }
+        let page_size = match req.page_size {
+            size if size <= 0 => DEFAULT_PAGE_SIZE,
+            size => size.min(MAX_PAGE_SIZE),
+        };
+        let updated_since = parse_timestamp("updated_since", &req.updated_since)?;
+        
+        let version_token = database::tasks_by_state_version(
+            &self.db_pool,
+            principal.tenant_id,
+            state,
+            workflow_id,
+            updated_since,
+            page_size as i64,
+            req.offset as i64,
+        )
+        .await
+        .map_err(|e| db_status(e, "Failed to list tasks"))?;
+        if !req.version_token.is_empty() && req.version_token == version_token {
+            return Ok(Response::new(not_modified(version_token)));
+        }
+        
+        let tasks = database::get_tasks_by_state(
+            &self.db_pool,
+            principal.tenant_id,
+            state,
+            workflow_id,
+            updated_since,
+            page_size as i64,
+            req.offset as i64,
+        )
+        .await
+        .map_err(|e| db_status(e, "Failed to list tasks"))?;
+        
+        let next_offset = if tasks.len() < page_size as usize {
+            -1
+        } else {
+            req.offset + page_size
+        };
+        
+        Ok(Response::new(durable_engine::ListTasksResponse {
+            tasks: tasks
+                .into_iter()
+                .map(task_message)
+                .collect(),
+            next_offset,
+            version_token,
+            not_modified: false,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn list_tasks_by_tag(
+        &self,
+        request: Request<durable_engine::ListTasksByTagRequest>,
+    ) -> Result<Response<durable_engine::ListTasksResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/ListTasksByTag");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        if req.key.is_empty() {
+            return Err(invalid_argument("key", "must not be empty"));
+        }
+        if req.offset < 0 {
+            return Err(invalid_argument("offset", "must not be negative"));
+        }
+        let page_size = match req.page_size {
+            size if size <= 0 => DEFAULT_PAGE_SIZE,
+            size => size.min(MAX_PAGE_SIZE),
+        };
+        let updated_since = parse_timestamp("updated_since", &req.updated_since)?;
+        
+        let version_token = database::tasks_by_tag_version(
+            &self.db_pool,
+            principal.tenant_id,
+            &req.key,
+            &req.value,
+            updated_since,
+            page_size as i64,
+            req.offset as i64,
+        )
+        .await
+        .map_err(|e| db_status(e, "Failed to list tasks by tag"))?;
+        if !req.version_token.is_empty() && req.version_token == version_token {
+            return Ok(Response::new(not_modified(version_token)));
+        }
+        
+        let tasks = database::get_tasks_by_tag(
+            &self.db_pool,
+            principal.tenant_id,
+            &req.key,
+            &req.value,
+            updated_since,
+            page_size as i64,
+            req.offset as i64,
+        )
+        .await
+        .map_err(|e| db_status(e, "Failed to list tasks by tag"))?;
+        
+        let next_offset = if tasks.len() < page_size as usize {
+            -1
+        } else {
+            req.offset + page_size
+        };
+        
+        Ok(Response::new(durable_engine::ListTasksResponse {
+            tasks: tasks
+                .into_iter()
+                .map(task_message)
+                .collect(),
+            next_offset,
+            version_token,
+            not_modified: false,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn cancel_task(
+        &self,
+        request: Request<durable_engine::CancelTaskRequest>,
+    ) -> Result<Response<durable_engine::CancelTaskResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/CancelTask");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        let reason = Some(req.reason.as_str()).filter(|reason| !reason.is_empty());
+        
+        let outcome = database::cancel_task(&self.db_pool, principal.tenant_id, task_id, reason)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to cancel task {}", task_id)))?;
+        
+        let task = match outcome {
+            CancelOutcome::Cancelled(task, events) => {
+                if events.len() > 1 {
+                    info!("Cancelled {} dependents of task {}", events.len() - 1, task_id);
+                }
+                self.events.publish(events).await;
+                self.engine.remove_active_task(task_id).await;
+                task
+            }
+            CancelOutcome::AlreadyTerminal(state) => {
+                return Err(Status::failed_precondition(format!(
+                    "Task {} is already {}",
+                    task_id, state
+                )));
+            }
+            CancelOutcome::NotFound => {
+                return Err(Status::not_found(format!("Task {} not found", task_id)));
+            }
+        };
+        
+        // The cancellation is already committed; a worker that misses the
+        // message finds out when it tries to report on the task
+        let message = ControlMessage::CancelTask {
+            task_id,
+            workflow_id: task.workflow_id,
+        };
+        if let Err(e) = queue::publish_control(&self.producer, &self.control_topic, &message).await {
+            warn!("Task {} cancelled but workers were not notified: {:?}", task_id, e);
+        }
+        
+        info!("Cancelled task {}", task_id);
+        
+        Ok(Response::new(durable_engine::CancelTaskResponse {
+            task_id: task.id.to_string(),
+            state: task.state.to_string(),
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn search(
+        &self,
+        request: Request<durable_engine::SearchRequest>,
+    ) -> Result<Response<durable_engine::SearchResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/Search");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let query = req.query.trim();
+        if query.is_empty() {
+            return Err(invalid_argument("query", "must not be empty"));
+        }
+        let kind = match req.kind.as_str() {
+            "" | "ALL" => SearchKind::All,
+            "WORKFLOW" => SearchKind::Workflow,
+            "TASK" => SearchKind::Task,
+            other => return Err(invalid_argument("kind", format!("unknown kind: {}", other))),
+        };
+        let limit = match req.limit {
+            limit if limit <= 0 => DEFAULT_PAGE_SIZE,
+            limit => limit.min(MAX_PAGE_SIZE),
+        };
+        
+        let hits = database::search_by_name(&self.db_pool, principal.tenant_id, query, kind, limit as i64)
+            .await
+            .map_err(|e| db_status(e, "Search failed"))?;
+        
+        Ok(Response::new(durable_engine::SearchResponse {
+            results: hits
+                .into_iter()
+                .map(|hit| durable_engine::SearchResult {
+                    kind: hit.kind,
+                    id: hit.id.to_string(),
+                    name: hit.name,
+                    state: hit.state,
+                    created_at: hit.created_at.to_rfc3339(),
+                })
+                .collect(),
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn heartbeat(
+        &self,
+        request: Request<durable_engine::HeartbeatRequest>,
+    ) -> Result<Response<durable_engine::HeartbeatResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/Heartbeat");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        
+        let state = database::record_heartbeat(&self.db_pool, principal.tenant_id, task_id)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to record heartbeat for task {}", task_id)))?
+            .ok_or_else(|| Status::not_found(format!("Task {} not found", task_id)))?;
+        
+        Ok(Response::new(durable_engine::HeartbeatResponse {
+            task_id: task_id.to_string(),
+            state: state.to_string(),
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn set_processing_enabled(
+        &self,
+        request: Request<durable_engine::SetProcessingEnabledRequest>,
+    ) -> Result<Response<durable_engine::SetProcessingEnabledResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/SetProcessingEnabled");
+        self.admin.authorize(&request)?;
+        let req = request.into_inner();
+        
+        let previously_enabled = self.engine.processing().set_enabled(req.enabled);
+        
+        Ok(Response::new(durable_engine::SetProcessingEnabledResponse {
+            enabled: req.enabled,
+            previously_enabled,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn force_fail_task(
+        &self,
+        request: Request<durable_engine::ForceFailTaskRequest>,
+    ) -> Result<Response<durable_engine::ForceResolveTaskResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/ForceFailTask");
+        self.admin.authorize(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        if req.reason.trim().is_empty() {
+            return Err(invalid_argument("reason", "must not be empty"));
+        }
+        
+        let outcome = database::force_resolve_task(&self.db_pool, task_id, TaskState::Failed, None, None, &req.reason)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to force-fail task {}", task_id)))?;
+        
+        self.finish_forced(task_id, outcome).await
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn force_complete_task(
+        &self,
+        request: Request<durable_engine::ForceCompleteTaskRequest>,
+    ) -> Result<Response<durable_engine::ForceResolveTaskResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/ForceCompleteTask");
+        self.admin.authorize(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        let (result, result_checksum) = match req.result.as_str() {
+            "" => (None, None),
+            result => {
+                let result: serde_json::Value = serde_json::from_str(result)
+                    .map_err(|e| invalid_argument("result", format!("not valid JSON: {}", e)))?;
+                let checksum = blob::checksum(&result);
+                let result = self.payloads
+                        .offload(&format!("tasks/{}/result", task_id), result)
+                        .await
+                        .map_err(|e| match e {
+                            OffloadError::TooLarge { .. } => invalid_argument("result", e.to_string()),
+                            OffloadError::Store(e) => {
+                                error!("Failed to offload forced result: {:?}", e);
+                                Status::internal("Failed to store task result")
+                            }
+                        })?;
+                (Some(result), Some(checksum))
+            }
+        };
+        let reason = Some(req.reason.as_str())
+            .filter(|reason| !reason.trim().is_empty())
+            .unwrap_or("Completed by an operator");
+        
+        let outcome = database::force_resolve_task(&self.db_pool, task_id, TaskState::Completed, result, result_checksum, reason)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to force-complete task {}", task_id)))?;
+        
+        self.finish_forced(task_id, outcome).await
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn requeue_failed_tasks(
+        &self,
+        request: Request<durable_engine::RequeueFailedTasksRequest>,
+    ) -> Result<Response<durable_engine::RequeueFailedTasksResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/RequeueFailedTasks");
+        self.admin.authorize(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        
+        let requeued = database::requeue_failed_tasks(&self.db_pool, workflow_id, req.keep_retry_count)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to requeue tasks of workflow {}", workflow_id)))?
+            .ok_or_else(|| Status::not_found(format!("Workflow {} not found", workflow_id)))?;
+        
+        warn!("Operator requeued {} failed tasks of workflow {}", requeued.task_ids.len(), workflow_id);
+        self.events.publish(requeued.events).await;
+        
+        // Reconciliation picks these up anyway if a publish is lost
+        for &task_id in &requeued.task_ids {
+            if let Err(e) = queue::publish_task(&self.producer, &self.task_topic, task_id, workflow_id).await {
+                warn!("Failed to publish requeued task {}: {:?}", task_id, e);
+            }
+        }
+        
+        Ok(Response::new(durable_engine::RequeueFailedTasksResponse {
+            requeued: requeued.task_ids.len() as i32,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn start_workflow(
+        &self,
+        request: Request<durable_engine::StartWorkflowRequest>,
+    ) -> Result<Response<durable_engine::StartWorkflowResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/StartWorkflow");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        
+        let outcome = database::start_workflow(&self.db_pool, principal.tenant_id, workflow_id)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to start workflow {}", workflow_id)))?;
+        
+        let root_task_ids = match outcome {
+            StartWorkflowOutcome::Started(root_task_ids) => root_task_ids,
+            StartWorkflowOutcome::AlreadyRunning => {
+                return Ok(Response::new(durable_engine::StartWorkflowResponse {
+                    workflow_id: workflow_id.to_string(),
+                    already_running: true,
+                    enqueued_tasks: 0,
+                }));
+            }
+            StartWorkflowOutcome::Finished(state) => {
+                return Err(Status::failed_precondition(format!(
+                    "Workflow {} is already {} and cannot be started again",
+                    workflow_id, state
+                )));
+            }
+            StartWorkflowOutcome::NotFound => {
+                return Err(Status::not_found(format!("Workflow {} not found", workflow_id)));
+            }
+        };
+        
+        info!("Started workflow {} with {} root tasks", workflow_id, root_task_ids.len());
+        // Reconciliation picks these up anyway if a publish is lost; the rest
+        // become ready as their dependencies complete
+        for &task_id in &root_task_ids {
+            if let Err(e) = queue::publish_task(&self.producer, &self.task_topic, task_id, workflow_id).await {
+                warn!("Failed to publish root task {}: {:?}", task_id, e);
+            }
+        }
+        
+        Ok(Response::new(durable_engine::StartWorkflowResponse {
+            workflow_id: workflow_id.to_string(),
+            already_running: false,
+            enqueued_tasks: root_task_ids.len() as i32,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn pause_workflow(
+        &self,
+        request: Request<durable_engine::PauseWorkflowRequest>,
+    ) -> Result<Response<durable_engine::PauseWorkflowResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/PauseWorkflow");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        
+        let outcome = database::set_workflow_paused(&self.db_pool, principal.tenant_id, workflow_id, true)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to pause workflow {}", workflow_id)))?;
+        
+        let already_paused = match outcome {
+            PauseWorkflowOutcome::Changed(_) => false,
+            PauseWorkflowOutcome::Unchanged => true,
+            PauseWorkflowOutcome::Draft => {
+                return Err(Status::failed_precondition(format!(
+                    "Workflow {} has not been started and cannot be paused",
+                    workflow_id
+                )));
+            }
+            PauseWorkflowOutcome::Finished(state) => {
+                return Err(Status::failed_precondition(format!(
+                    "Workflow {} is already {} and cannot be paused",
+                    workflow_id, state
+                )));
+            }
+            PauseWorkflowOutcome::NotFound => {
+                return Err(Status::not_found(format!("Workflow {} not found", workflow_id)));
+            }
+        };
+        
+        if !already_paused {
+            info!("Paused workflow {}", workflow_id);
+        }
+        
+        Ok(Response::new(durable_engine::PauseWorkflowResponse {
+            workflow_id: workflow_id.to_string(),
+            already_paused,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn resume_workflow(
+        &self,
+        request: Request<durable_engine::ResumeWorkflowRequest>,
+    ) -> Result<Response<durable_engine::ResumeWorkflowResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/ResumeWorkflow");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        
+        let outcome = database::set_workflow_paused(&self.db_pool, principal.tenant_id, workflow_id, false)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to resume workflow {}", workflow_id)))?;
+        
+        let ready_task_ids = match outcome {
+            PauseWorkflowOutcome::Changed(ready_task_ids) => ready_task_ids,
+            PauseWorkflowOutcome::Unchanged => {
+                return Ok(Response::new(durable_engine::ResumeWorkflowResponse {
+                    workflow_id: workflow_id.to_string(),
+                    already_running: true,
+                    enqueued_tasks: 0,
+                }));
+            }
+            PauseWorkflowOutcome::Draft => {
+                return Err(Status::failed_precondition(format!(
+                    "Workflow {} has not been started and cannot be resumed",
+                    workflow_id
+                )));
+            }
+            PauseWorkflowOutcome::Finished(state) => {
+                return Err(Status::failed_precondition(format!(
+                    "Workflow {} is already {} and cannot be resumed",
+                    workflow_id, state
+                )));
+            }
+            PauseWorkflowOutcome::NotFound => {
+                return Err(Status::not_found(format!("Workflow {} not found", workflow_id)));
+            }
+        };
+        
+        info!("Resumed workflow {} with {} ready tasks", workflow_id, ready_task_ids.len());
+        // Their messages were skipped while paused; reconciliation picks them
+        // up anyway if a publish is lost
+        for &task_id in &ready_task_ids {
+            if let Err(e) = queue::publish_task(&self.producer, &self.task_topic, task_id, workflow_id).await {
+                warn!("Failed to publish ready task {}: {:?}", task_id, e);
+            }
+        }
+        
+        Ok(Response::new(durable_engine::ResumeWorkflowResponse {
+            workflow_id: workflow_id.to_string(),
+            already_running: false,
+            enqueued_tasks: ready_task_ids.len() as i32,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn cancel_workflow(
+        &self,
+        request: Request<durable_engine::CancelWorkflowRequest>,
+    ) -> Result<Response<durable_engine::CancelWorkflowResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/CancelWorkflow");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        let reason = Some(req.reason.as_str()).filter(|reason| !reason.is_empty());
+        
+        let outcome = database::cancel_workflow(&self.db_pool, principal.tenant_id, workflow_id, reason)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to cancel workflow {}", workflow_id)))?;
+        
+        let (running_task_ids, events) = match outcome {
+            CancelWorkflowOutcome::Cancelled { running_task_ids, events } => (running_task_ids, events),
+            CancelWorkflowOutcome::Finished(state) => {
+                return Err(Status::failed_precondition(format!(
+                    "Workflow {} is already {}",
+                    workflow_id, state
+                )));
+            }
+            CancelWorkflowOutcome::NotFound => {
+                return Err(Status::not_found(format!("Workflow {} not found", workflow_id)));
+            }
+        };
+        let cancelled_tasks = events.len() as i32;
+        self.events.publish(events).await;
+        
+        // As with CancelTask, a worker that misses the message finds out when
+        // it tries to report on the task
+        for task_id in running_task_ids {
+            self.engine.remove_active_task(task_id).await;
+            let message = ControlMessage::CancelTask { task_id, workflow_id };
+            if let Err(e) = queue::publish_control(&self.producer, &self.control_topic, &message).await {
+                warn!("Task {} cancelled but workers were not notified: {:?}", task_id, e);
+            }
+        }
+        
+        info!("Cancelled workflow {} with {} unfinished tasks", workflow_id, cancelled_tasks);
+        
+        Ok(Response::new(durable_engine::CancelWorkflowResponse {
+            workflow_id: workflow_id.to_string(),
+            cancelled_tasks,
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn delete_workflow(
+        &self,
+        request: Request<durable_engine::DeleteWorkflowRequest>,
+    ) -> Result<Response<durable_engine::DeleteWorkflowResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/DeleteWorkflow");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        
+        let outcome = database::soft_delete_workflow(&self.db_pool, principal.tenant_id, workflow_id)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to delete workflow {}", workflow_id)))?;
+        
+        let already_deleted = match outcome {
+            DeleteWorkflowOutcome::Deleted => {
+                info!("Deleted workflow {}", workflow_id);
+                false
+            }
+            DeleteWorkflowOutcome::AlreadyDeleted => true,
+            DeleteWorkflowOutcome::Active => {
+                return Err(Status::failed_precondition(format!(
+                    "Workflow {} still has queued or running tasks; cancel them before deleting it",
+                    workflow_id
+                )));
+            }
+            DeleteWorkflowOutcome::NotFound => {
+                return Err(Status::not_found(format!("Workflow {} not found", workflow_id)));
+            }
+        };
+        
+        Ok(Response::new(durable_engine::DeleteWorkflowResponse {
+            workflow_id: workflow_id.to_string(),
+            already_deleted,
         }))
     }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn get_workflow_status(
+        &self,
+        request: Request<durable_engine::GetWorkflowStatusRequest>,
+    ) -> Result<Response<durable_engine::GetWorkflowStatusResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/GetWorkflowStatus");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        
+        let status = fetch_workflow_status(&self.db_pool, principal.tenant_id, workflow_id).await?;
+        
+        Ok(Response::new(workflow_status_response(&status)))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn validate_workflow(
+        &self,
+        request: Request<durable_engine::ValidateWorkflowRequest>,
+    ) -> Result<Response<durable_engine::ValidateWorkflowResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/ValidateWorkflow");
+        // Nothing is read or created, but like every client RPC it needs a tenant
+        Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let tasks = req
+            .tasks
+            .into_iter()
+            .map(|task| {
+                Ok(TaskDefinition {
+                    name: task.name,
+                    task_type: task.task_type,
+                    parameters: {
+                        let mut parameters = serde_json::to_value(task.parameters)
+                            .map_err(|e| invalid_argument("parameters", e.to_string()))?;
+                        references::decode_string_references(&mut parameters);
+                        parameters
+                    },
+                    depends_on: task.depends_on,
+                })
+            })
+            .collect::<Result<Vec<_>, Status>>()?;
+        
+        let issues = validation::validate_workflow(&req.name, &tasks, &self.schemas);
+        
+        Ok(Response::new(durable_engine::ValidateWorkflowResponse {
+            valid: !issues.iter().any(|issue| issue.severity == Severity::Error),
+            issues: issues
+                .into_iter()
+                .map(|issue| durable_engine::ValidationIssue {
+                    severity: issue.severity.to_string(),
+                    task_name: issue.task_name.unwrap_or_default(),
+                    field: issue.field,
+                    message: issue.message,
+                })
+                .collect(),
+        }))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn watch_workflow(
+        &self,
+        request: Request<durable_engine::WatchWorkflowRequest>,
+    ) -> Result<Response<Self::WatchWorkflowStream>, Status> {
+        let _span = server_span(&request, "DurableEngine/WatchWorkflow");
+        let tenant_id = Principal::from_request(&request)?.tenant_id;
+        let req = request.into_inner();
+        
+        let workflow_id = parse_uuid("workflow_id", &req.workflow_id)?;
+        let initial = fetch_workflow_status(&self.db_pool, tenant_id, workflow_id).await?;
+        
+        // The next snapshot is only read once the client pulls it, so a burst
+        // of task updates reaches a slow consumer as a single, latest snapshot
+        let pool = self.db_pool.clone();
+        let poll_interval = self.watch_poll_interval;
+        let snapshots = stream::try_unfold(WatchState::Initial(initial), move |state| {
+            let pool = pool.clone();
+            async move {
+                let status = match state {
+                    WatchState::Initial(status) => status,
+                    WatchState::Sent(last) => loop {
+                        tokio::time::sleep(poll_interval).await;
+                        let status = fetch_workflow_status(&pool, tenant_id, workflow_id).await?;
+                        if status != last {
+                            break status;
+                        }
+                    },
+                    WatchState::Finished => return Ok(None),
+                };
+                
+                let next = if status.is_finished() {
+                    WatchState::Finished
+                } else {
+                    WatchState::Sent(status.clone())
+                };
+                Ok(Some((workflow_status_response(&status), next)))
+            }
+        });
+        
+        Ok(Response::new(snapshots.boxed()))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn stream_task_result(
+        &self,
+        request: Request<durable_engine::StreamTaskResultRequest>,
+    ) -> Result<Response<Self::StreamTaskResultStream>, Status> {
+        let _span = server_span(&request, "DurableEngine/StreamTaskResult");
+        let principal = Principal::from_request(&request)?;
+        let req = request.into_inner();
+        
+        let task_id = parse_uuid("task_id", &req.task_id)?;
+        self.require_visible_task(&principal, task_id).await?;
+        
+        // Chunks are read one at a time as the client pulls them
+        let pool = self.db_pool.clone();
+        let chunks = stream::try_unfold(-1, move |after_seq| {
+            let pool = pool.clone();
+            async move {
+                let chunk = database::get_result_chunk_after(&pool, task_id, after_seq)
+                    .await
+                    .map_err(|e| db_status(e, format!("Failed to read result of task {}", task_id)))?;
+                
+                Ok(chunk.map(|(seq, data)| {
+                    let chunk = durable_engine::TaskResultChunk {
+                        task_id: task_id.to_string(),
+                        sequence: seq,
+                        data,
+                    };
+                    (chunk, seq)
+                }))
+            }
+        });
+        
+        Ok(Response::new(chunks.boxed()))
+    }
+    
+    #[instrument(skip_all, fields(request_id = %request_id(&request)))]
+    async fn stream_task_result_chunks(
+        &self,
+        request: Request<tonic::Streaming<durable_engine::TaskResultChunk>>,
+    ) -> Result<Response<durable_engine::StreamTaskResultChunksResponse>, Status> {
+        let _span = server_span(&request, "DurableEngine/StreamTaskResultChunks");
+        let principal = Principal::from_request(&request)?;
+        let mut chunks = request.into_inner();
+        
+        let inline_limit = self.payloads.max_inline_bytes();
+        let mut task_id = None;
+        let mut next_seq = 0;
+        let mut total_bytes = 0usize;
+        // Kept only while the result still fits in the result column
+        let mut assembled = Some(Vec::new());
+        
+        while let Some(chunk) = chunks.message().await? {
+            let chunk_task_id = parse_uuid("task_id", &chunk.task_id)?;
+            match task_id {
+                None => {
+                    self.require_visible_task(&principal, chunk_task_id).await?;
+                    task_id = Some(chunk_task_id);
+                }
+                Some(id) if id != chunk_task_id => {
+                    return Err(invalid_argument("task_id", "every chunk in a stream must be for the same task"));
+                }
+                Some(_) => {}
+            }
+            if chunk.sequence != next_seq {
+                return Err(invalid_argument(
+                    "sequence",
+                    format!("expected chunk {}, got {}", next_seq, chunk.sequence),
+                ));
+            }
+            
+            database::put_result_chunk(&self.db_pool, chunk_task_id, chunk.sequence, &chunk.data)
+                .await
+                .map_err(|e| db_status(e, format!("Failed to store result chunk for task {}", chunk_task_id)))?;
+            
+            next_seq += 1;
+            total_bytes += chunk.data.len();
+            assembled = assembled
+                .filter(|_| total_bytes <= inline_limit)
+                .map(|mut data| {
+                    data.extend_from_slice(&chunk.data);
+                    data
+                });
+        }
+        
+        let task_id = task_id.ok_or_else(|| invalid_argument("task_id", "stream contained no chunks"))?;
+        
+        let inlined = match assembled.as_deref().and_then(inline_result) {
+            Some(result) => {
+                database::set_task_result(&self.db_pool, task_id, &result)
+                    .await
+                    .map_err(|e| db_status(e, format!("Failed to store assembled result for task {}", task_id)))?;
+                true
+            }
+            None => false,
+        };
+        
+        info!("Stored {} result chunks ({} bytes) for task {}", next_seq, total_bytes, task_id);
+        
+        Ok(Response::new(durable_engine::StreamTaskResultChunksResponse {
+            task_id: task_id.to_string(),
+            chunks_received: next_seq,
+            total_bytes: total_bytes as i64,
+            inlined,
+        }))
+    }
+}
+
+impl DurableEngineService {
+    /// Publish a forced resolution's events and get the rest of the system to
+    /// catch up with it: a running task's slot is freed and its worker told to
+    /// stop, and dependents a forced completion unblocked are queued for processing
+    async fn finish_forced(
+        &self,
+        task_id: Uuid,
+        outcome: ForceOutcome,
+    ) -> Result<Response<durable_engine::ForceResolveTaskResponse>, Status> {
+        let (task, previous_state, events) = match outcome {
+            ForceOutcome::Resolved { task, previous_state, events } => (task, previous_state, events),
+            ForceOutcome::NotFound => return Err(Status::not_found(format!("Task {} not found", task_id))),
+        };
+        warn!("Operator forced task {} from {} to {}", task_id, previous_state, task.state);
+        self.events.publish(events).await;
+        
+        if previous_state == TaskState::Running {
+            self.engine.remove_active_task(task_id).await;
+            let message = ControlMessage::CancelTask {
+                task_id,
+                workflow_id: task.workflow_id,
+            };
+            if let Err(e) = queue::publish_control(&self.producer, &self.control_topic, &message).await {
+                warn!("Task {} was forced but its worker was not notified: {:?}", task_id, e);
+            }
+        }
+        
+        if task.state == TaskState::Completed {
+            self.queue_ready_dependents(task_id, task.workflow_id).await?;
+        }
+        
+        Ok(Response::new(durable_engine::ForceResolveTaskResponse {
+            task_id: task.id.to_string(),
+            state: task.state.to_string(),
+            previous_state: previous_state.to_string(),
+        }))
+    }
+    
+    /// Publish the dependents of a completed task that it was the last to unblock
+    async fn queue_ready_dependents(&self, task_id: Uuid, workflow_id: Uuid) -> Result<(), Status> {
+        let ready = database::get_ready_dependents(&self.db_pool, task_id)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to get dependents of task {}", task_id)))?;
+        // Reconciliation picks these up anyway if a publish is lost
+        for dependent_id in ready {
+            if let Err(e) = queue::publish_task(&self.producer, &self.task_topic, dependent_id, workflow_id).await {
+                warn!("Failed to publish dependent task {}: {:?}", dependent_id, e);
+            }
+        }
+        Ok(())
+    }
+    
+    /// The workflow, or NOT_FOUND unless it exists and belongs to the caller's
+    /// tenant, or FAILED_PRECONDITION if it was cancelled
+    async fn open_workflow(&self, principal: &Principal, workflow_id: Uuid) -> Result<database::WorkflowHeader, Status> {
+        let workflow = database::get_workflow_header(&self.db_pool, workflow_id)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to get workflow {}", workflow_id)))?
+            .filter(|workflow| workflow.tenant_id == principal.tenant_id)
+            .ok_or_else(|| Status::not_found(format!("Workflow {} not found", workflow_id)))?;
+        if workflow.state == WorkflowState::Cancelled {
+            return Err(Status::failed_precondition(format!(
+                "Workflow {} was cancelled and cannot take new tasks",
+                workflow_id
+            )));
+        }
+        Ok(workflow)
+    }
+    
+    /// Check a StartTask request and turn it into the task to insert,
+    /// offloading its parameters if they are too large to keep inline
+    async fn prepare_task(
+        &self,
+        principal: &Principal,
+        workflow_id: Uuid,
+        req: durable_engine::StartTaskRequest,
+    ) -> Result<NewTask, Status> {
+        let depends_on = req
+            .depends_on
+            .iter()
+            .map(|id| parse_uuid("depends_on", id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let replayed_from = match req.replayed_from.as_str() {
+            "" => None,
+            id => Some(parse_uuid("replayed_from", id)?),
+        };
+        
+        let scheduled_at = parse_timestamp("scheduled_at", &req.scheduled_at)?;
+        if req.tags.keys().any(|key| key.is_empty()) {
+            return Err(invalid_argument("tags", "keys must not be empty"));
+        }
+        if req.requirements.keys().any(|key| key.is_empty()) {
+            return Err(invalid_argument("requirements", "labels must not be empty"));
+        }
+        let max_retries = self.retry_limits.effective(req.max_retries)?;
+        if req.max_retries.is_some_and(|requested| requested > max_retries) {
+            info!(
+                "Lowered max_retries of new task {:?} from {:?} to the ceiling of {}",
+                req.task_name, req.max_retries, max_retries
+            );
+        }
+        if req.timeout_seconds < 0 {
+            return Err(invalid_argument("timeout_seconds", "must not be negative"));
+        }
+        let timeout_seconds = self.timeouts.resolve(&req.task_type, req.timeout_seconds);
+        let retry_backoff = match req.retry_backoff.as_str() {
+            "" => None,
+            json => Some(BackoffStrategy::parse(json).map_err(|e| invalid_argument("retry_backoff", e))?),
+        };
+        
+        if !self.schemas.is_allowed(&req.task_type) {
+            return Err(invalid_argument(
+                "task_type",
+                format!("task type {:?} is not allowed on this cluster", req.task_type),
+            ));
+        }
+        
+        let payload_format = match req.payload_format.as_str() {
+            "" => PayloadFormat::Json,
+            format => format
+                .parse::<PayloadFormat>()
+                .map_err(|e| invalid_argument("payload_format", e.to_string()))?,
+        };
+        let payload = match payload_format {
+            PayloadFormat::Json if !req.payload.is_empty() => {
+                return Err(invalid_argument("payload", "must be empty for JSON tasks; send parameters instead"));
+            }
+            PayloadFormat::Json => None,
+            _ if !req.parameters.is_empty() => {
+                return Err(invalid_argument(
+                    "parameters",
+                    format!("must be empty for {} payloads; send them in payload", payload_format),
+                ));
+            }
+            // The engine can't decode binary payloads, so it couldn't check them
+            _ if req.task_type == COLLECTOR_TASK_TYPE || self.schemas.knows(&req.task_type) => {
+                return Err(invalid_argument(
+                    "payload_format",
+                    format!("task type {} has its parameters checked, so they must be JSON", req.task_type),
+                ));
+            }
+            _ => Some(req.payload),
+        };
+        
+        let mut parameters = serde_json::to_value(req.parameters)
+            .map_err(|e| invalid_argument("parameters", e.to_string()))?;
+        references::decode_string_references(&mut parameters);
+        let refs = references::find(&parameters).map_err(|e| invalid_argument("parameters", e.to_string()))?;
+        
+        // Results flow from dependencies through $ref and collectors, so they
+        // must never reach outside the caller's workflow
+        let dependency_names = match depends_on.as_slice() {
+            [] => HashMap::new(),
+            ids => database::get_task_names(&self.db_pool, principal.tenant_id, workflow_id, ids)
+                .await
+                .map_err(|e| db_status(e, "Failed to get dependencies"))?,
+        };
+        if let Some(id) = depends_on.iter().find(|id| !dependency_names.contains_key(id)) {
+            return Err(invalid_argument(
+                "depends_on",
+                format!("task {} is not in workflow {}", id, workflow_id),
+            ));
+        }
+        
+        if refs.is_empty() {
+            self.schemas
+                .validate(&req.task_type, &parameters)
+                .map_err(|violations| schema_violations(&req.task_type, violations))?;
+        } else {
+            // The schema is checked once the references are resolved, when the task starts
+            if let Some(reference) = refs.iter().find(|r| !dependency_names.values().any(|name| *name == r.task_name)) {
+                return Err(invalid_argument(
+                    "parameters",
+                    format!("reference to {} must name a task in depends_on", reference.task_name),
+                ));
+            }
+        }
+        
+        Ok(NewTask {
+            workflow_id,
+            name: req.task_name,
+            max_retries,
+            timeout_seconds,
+            parameters: self
+                .payloads
+                .offload(&format!("workflows/{}/parameters/{}", workflow_id, Uuid::new_v4()), parameters)
+                .await
+                .map_err(|e| match e {
+                    OffloadError::TooLarge { .. } => invalid_argument("parameters", e.to_string()),
+                    OffloadError::Store(e) => {
+                        error!("Failed to offload task parameters: {:?}", e);
+                        Status::internal("Failed to store task parameters")
+                    }
+                })?,
+            depends_on,
+            idempotency_key: Some(req.idempotency_key).filter(|key| !key.is_empty()),
+            priority: req.priority,
+            task_type: req.task_type,
+            replayed_from,
+            tags: req.tags,
+            scheduled_at,
+            payload_format,
+            payload,
+            tenant_id: principal.tenant_id,
+            created_by: principal.subject.clone(),
+            parent_task_id: None,
+            requirements: req.requirements,
+            retry_backoff,
+        })
+    }
+    
+    /// Hand a newly created task to the engines through the task topic
+    async fn queue_new_task(&self, workflow: &database::WorkflowHeader, task: &Task) {
+        // The task is already persisted as QUEUED, so reconciliation picks it up
+        // even if this publish is lost. Tasks of a draft wait for StartWorkflow.
+        if workflow.state.is_started() {
+            if let Err(e) = queue::publish_task(&self.producer, &self.task_topic, task.id, task.workflow_id).await {
+                warn!("Failed to publish task {}: {:?}", task.id, e);
+            }
+        }
+    }
+    
+    /// The task, or NOT_FOUND unless it exists and belongs to the caller's tenant
+    async fn require_visible_task(&self, principal: &Principal, task_id: Uuid) -> Result<Task, Status> {
+        database::get_task_by_id(&self.db_pool, task_id)
+            .await
+            .map_err(|e| db_status(e, format!("Failed to get task {}", task_id)))?
+            .filter(|task| task.tenant_id == principal.tenant_id)
+            .ok_or_else(|| Status::not_found(format!("Task {} not found", task_id)))
+    }
+    
+    /// The task, as for `require_visible_task`, or FAILED_PRECONDITION unless
+    /// it is RUNNING, so a worker can report on it
+    async fn require_running_task(&self, principal: &Principal, task_id: Uuid) -> Result<Task, Status> {
+        let task = self.require_visible_task(principal, task_id).await?;
+        if task.state != TaskState::Running {
+            return Err(Status::failed_precondition(format!("Task {} is {}, not RUNNING", task_id, task.state)));
+        }
+        Ok(task)
+    }
+}
+
+/// Progress of a WatchWorkflow stream
+enum WatchState {
+    /// Read when the stream was opened and not yet sent
+    Initial(database::WorkflowStatus),
+    /// Last snapshot sent; the next is sent once something changes
+    Sent(database::WorkflowStatus),
+    /// The final snapshot was sent
+    Finished,
+}
+
+/// NOT_FOUND unless the workflow exists
+async fn fetch_workflow_status(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    workflow_id: Uuid,
+) -> Result<database::WorkflowStatus, Status> {
+    database::get_workflow_status(pool, tenant_id, workflow_id)
+        .await
+        .map_err(|e| db_status(e, format!("Failed to get status of workflow {}", workflow_id)))?
+        .ok_or_else(|| Status::not_found(format!("Workflow {} not found", workflow_id)))
+}
+
+fn workflow_status_response(status: &database::WorkflowStatus) -> durable_engine::GetWorkflowStatusResponse {
+    durable_engine::GetWorkflowStatusResponse {
+        workflow_id: status.workflow_id.to_string(),
+        state: status.workflow_state.to_string(),
+        task_counts: status
+            .counts()
+            .into_iter()
+            .map(|(state, count)| (state.to_string(), count))
+            .collect(),
+        total_tasks: status.total,
+        percent_complete: status.percent_complete(),
+        paused: status.workflow_state == WorkflowState::Paused,
+    }
+}
+
+/// A task as sent to clients. Parameters, tags and requirements are string
+/// maps as in StartTask, and the result is its JSON text.
+fn task_message(task: Task) -> durable_engine::Task {
+    durable_engine::Task {
+        id: task.id.to_string(),
+        workflow_id: task.workflow_id.to_string(),
+        execution_id: String::new(),
+        name: task.name,
+        state: task.state.to_string(),
+        retry_count: task.retry_count,
+        max_retries: task.max_retries,
+        created_at: Some(timestamp(task.created_at)),
+        updated_at: Some(timestamp(task.updated_at)),
+        started_at: task.started_at.map(timestamp),
+        completed_at: task.completed_at.map(timestamp),
+        timeout_seconds: task.timeout_seconds,
+        parameters: string_map(&task.parameters),
+        result: task.result.map(|result| result.to_string()).unwrap_or_default(),
+        error: task.error.unwrap_or_default(),
+        priority: task.priority,
+        task_type: task.task_type,
+        version: task.version,
+        tags: string_map(&task.tags),
+        payload_format: task.payload_format.to_string(),
+        payload: task.payload.unwrap_or_default(),
+        tenant_id: task.tenant_id.to_string(),
+        created_by: task.created_by,
+        requirements: string_map(&task.requirements),
+        waiting_reason: task.waiting_reason.unwrap_or_default(),
+        result_checksum: task.result_checksum.unwrap_or_default(),
+    }
+}
+
+/// The entries of a JSON object, with values that aren't strings (such as a
+/// resolved reference or an offloaded blob) given as their JSON text
+fn string_map(value: &serde_json::Value) -> HashMap<String, String> {
+    let Some(object) = value.as_object() else {
+        return HashMap::new();
+    };
+    object
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+fn timestamp(time: chrono::DateTime<chrono::Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// Answer to a list request whose version token still matches; the caller
+/// keeps the page, including its next offset, from when it got the token
+fn not_modified(version_token: String) -> durable_engine::ListTasksResponse {
+    durable_engine::ListTasksResponse {
+        tasks: Vec::new(),
+        next_offset: -1,
+        version_token,
+        not_modified: true,
+    }
+}
+
+/// A streamed result small enough for the `result` column: stored as JSON if
+/// it parses, otherwise as a string if it is UTF-8. Binary output stays chunk-only.
+fn inline_result(data: &[u8]) -> Option<serde_json::Value> {
+    serde_json::from_slice(data)
+        .ok()
+        .or_else(|| std::str::from_utf8(data).ok().map(|text| serde_json::Value::String(text.to_string())))
+}
+
+/// Map a database failure to its status code: NOT_FOUND, ABORTED for conflicts
+/// the caller may retry, UNAVAILABLE when the database can't be reached, and
+/// INTERNAL otherwise. Unexpected failures are logged with `context`.
+fn db_status(e: DbError, context: impl std::fmt::Display) -> Status {
+    match e {
+        DbError::NotFound(what) => Status::not_found(format!("{}: {} not found", context, what)),
+        DbError::Conflict(reason) => Status::aborted(reason),
+        DbError::Connection(e) => {
+            warn!("{}: {:?}", context, e);
+            Status::unavailable("Database unavailable")
+        }
+        e => {
+            error!("{}: {:?}", context, e);
+            Status::internal(context.to_string())
+        }
+    }
+}
+
+/// Map a failure to record a worker's report: INVALID_ARGUMENT for a result
+/// too large to store, FAILED_PRECONDITION if the task left RUNNING while the
+/// report was being recorded, and INTERNAL otherwise, logged with `context`
+fn report_status(e: anyhow::Error, context: impl std::fmt::Display) -> Status {
+    if let Some(e @ OffloadError::TooLarge { .. }) = e.downcast_ref::<OffloadError>() {
+        return invalid_argument("result", e.to_string());
+    }
+    if let Some(sqlx::Error::RowNotFound) = e.downcast_ref::<sqlx::Error>() {
+        return Status::failed_precondition(format!("{}: it is no longer RUNNING", context));
+    }
+    error!("{}: {:?}", context, e);
+    Status::internal(context.to_string())
+}
+
+/// The same status with its message prefixed by which task of a batch it is about
+fn at_index(index: usize, status: Status) -> Status {
+    Status::with_details(
+        status.code(),
+        format!("task at index {}: {}", index, status.message()),
+        status.details().to_vec().into(),
+    )
+}
+
+/// INVALID_ARGUMENT carrying a `google.rpc.ErrorInfo` that names the offending field
+fn invalid_argument(field: &str, reason: impl Into<String>) -> Status {
+    let reason = reason.into();
+    let details = ErrorDetails::with_error_info(
+        reason.clone(),
+        "chronos.durable_engine",
+        HashMap::from([("field".to_string(), field.to_string())]),
+    );
+
+    Status::with_error_details(Code::InvalidArgument, format!("{}: {}", field, reason), details)
+}
+
+/// INVALID_ARGUMENT carrying a `google.rpc.BadRequest` with one field violation per schema error
+fn schema_violations(task_type: &str, violations: Vec<SchemaViolation>) -> Status {
+    let field_violations = violations
+        .into_iter()
+        .map(|violation| FieldViolation::new(format!("parameters{}", violation.path), violation.message))
+        .collect::<Vec<_>>();
+    let details = ErrorDetails::with_bad_request(field_violations);
+
+    Status::with_error_details(
+        Code::InvalidArgument,
+        format!("parameters do not match the schema for task type {}", task_type),
+        details,
+    )
+}
+
+fn parse_uuid(field: &str, value: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(value)
+        .map_err(|_| invalid_argument(field, format!("not a valid UUID: {}", value)))
+}
+
+/// An optional RFC 3339 timestamp; empty means unset
+fn parse_timestamp(field: &str, value: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>, Status> {
+    match value {
+        "" => Ok(None),
+        time => chrono::DateTime::parse_from_rfc3339(time)
+            .map(|time| Some(time.with_timezone(&chrono::Utc)))
+            .map_err(|e| invalid_argument(field, format!("not an RFC 3339 timestamp: {}", e))),
+    }
 }
 
 /// Start the gRPC server
-pub async fn start_grpc_server(db_pool: PgPool) -> Result<()> {
+pub async fn start_grpc_server(
+    db_pool: PgPool,
+    payloads: PayloadStore,
+    schemas: Arc<SchemaRegistry>,
+    events: EventPublisher,
+    timeouts: TimeoutRegistry,
+    engine: TaskEngine,
+    config: ServerConfig,
+) -> Result<()> {
     let addr = "[::1]:50051".parse::<SocketAddr>()?;
-    let service = DurableEngineService { db_pool };
+    let service = DurableEngineService {
+        db_pool,
+        payloads,
+        schemas,
+        events,
+        retry_limits: config.retry_limits,
+        timeouts,
+        engine,
+        admin: config.admin,
+        producer: queue::init_kafka_producer(&config.kafka)?,
+        task_topic: config.kafka.task_topic().to_string(),
+        control_topic: config.kafka.control_topic.clone(),
+        watch_poll_interval: config.watch_poll_interval,
+    };
+    let limits = config.limits;
     
-    info!("Starting gRPC server on {}", addr);
+    info!(
+        "Starting gRPC server on {} (max message size {} bytes in, {} bytes out)",
+        addr, limits.max_decoding_message_size, limits.max_encoding_message_size
+    );
     
-    // In a real implementation, this would start the server
-    // For this sample, we'll just return Ok
+    let incoming = TcpIncoming::bind(addr)?;
+    tokio::spawn(async move {
+        if let Err(e) = serve(service, limits, incoming).await {
+            error!("gRPC server stopped: {:?}", e);
+        }
+    });
     
     Ok(())
 }
+
+/// Serve the API on `incoming` until the listener fails
+async fn serve(
+    service: DurableEngineService,
+    limits: MessageSizeLimits,
+    incoming: TcpIncoming,
+) -> Result<(), tonic::transport::Error> {
+    // Large payloads and results travel compressed when the client asks for it
+    let server = DurableEngineServiceServer::new(service)
+        .accept_compressed(CompressionEncoding::Gzip)
+        .send_compressed(CompressionEncoding::Gzip)
+        .max_decoding_message_size(limits.max_decoding_message_size)
+        .max_encoding_message_size(limits.max_encoding_message_size);
+    
+    // Handler spans join the caller's trace
+    let server = InterceptedService::new(server, propagation::extract_trace_context);
+    
+    Server::builder().add_service(server).serve_with_incoming(incoming).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::tests::{insert_workflow, new_task};
+    use crate::events::tests::unreachable_producer;
+    use crate::models::ResetReason;
+    use durable_engine::durable_engine_service_client::DurableEngineServiceClient;
+    use prost::Message;
+    use sqlx::postgres::PgPoolOptions;
+
+    /// A service for requests that fail before reaching the database or Kafka
+    fn offline_service() -> DurableEngineService {
+        let db_pool = PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy("postgres://localhost/chronos-test-unused")
+            .unwrap();
+        service_with(db_pool)
+    }
+
+    /// A service on `db_pool` whose Kafka messages go nowhere
+    fn service_with(db_pool: PgPool) -> DurableEngineService {
+        let kafka_config = KafkaConfig::from_env().unwrap();
+        let producer = unreachable_producer();
+        DurableEngineService {
+            db_pool: db_pool.clone(),
+            payloads: PayloadStore::from_env().unwrap(),
+            schemas: Arc::new(SchemaRegistry::new()),
+            events: EventPublisher::new(db_pool.clone(), producer.clone(), "chronos-events"),
+            retry_limits: RetryLimits::from_env().unwrap(),
+            timeouts: TimeoutRegistry::default(),
+            engine: crate::engine::tests::engine(db_pool),
+            admin: AdminAuth::from_env(),
+            producer,
+            task_topic: kafka_config.task_topic().to_string(),
+            control_topic: kafka_config.control_topic.clone(),
+            watch_poll_interval: std::time::Duration::from_millis(10),
+        }
+    }
+
+    /// Serve on a free local port and connect to it
+    async fn connect(limits: MessageSizeLimits) -> DurableEngineServiceClient<tonic::transport::Channel> {
+        let incoming = TcpIncoming::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = incoming.local_addr().unwrap();
+        tokio::spawn(serve(offline_service(), limits, incoming));
+        DurableEngineServiceClient::connect(format!("http://{}", addr)).await.unwrap()
+    }
+
+    /// A StartTask request from some tenant, with no workflow so it is
+    /// rejected as soon as it is decoded, whose encoding is exactly `size` bytes
+    fn start_task_of_size(size: usize) -> Request<durable_engine::StartTaskRequest> {
+        let mut message = durable_engine::StartTaskRequest {
+            payload: vec![0; size],
+            ..Default::default()
+        };
+        let overhead = message.encoded_len() - size;
+        message.payload.truncate(size - overhead);
+        assert_eq!(message.encoded_len(), size);
+        as_tenant(Uuid::new_v4(), message)
+    }
+
+    #[tokio::test]
+    async fn default_limits_reject_a_request_just_over_4_mib() {
+        let limits = MessageSizeLimits {
+            max_decoding_message_size: DEFAULT_MAX_DECODING_MESSAGE_SIZE,
+            max_encoding_message_size: usize::MAX,
+        };
+        let mut client = connect(limits).await;
+
+        let at_limit = client
+            .start_task(start_task_of_size(DEFAULT_MAX_DECODING_MESSAGE_SIZE))
+            .await
+            .unwrap_err();
+        assert_eq!(at_limit.code(), Code::InvalidArgument, "{:?}", at_limit);
+
+        let over_limit = client
+            .start_task(start_task_of_size(DEFAULT_MAX_DECODING_MESSAGE_SIZE + 1))
+            .await
+            .unwrap_err();
+        assert_eq!(over_limit.code(), Code::OutOfRange, "{:?}", over_limit);
+    }
+
+    #[tokio::test]
+    async fn raised_limit_accepts_a_request_over_4_mib() {
+        let limits = MessageSizeLimits {
+            max_decoding_message_size: 2 * DEFAULT_MAX_DECODING_MESSAGE_SIZE,
+            max_encoding_message_size: usize::MAX,
+        };
+        let mut client = connect(limits).await;
+
+        let status = client
+            .start_task(start_task_of_size(DEFAULT_MAX_DECODING_MESSAGE_SIZE + 1))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument, "{:?}", status);
+    }
+
+    #[tokio::test]
+    async fn accepts_gzip_requests() {
+        let limits = MessageSizeLimits {
+            max_decoding_message_size: DEFAULT_MAX_DECODING_MESSAGE_SIZE,
+            max_encoding_message_size: usize::MAX,
+        };
+        let mut client = connect(limits)
+            .await
+            .send_compressed(CompressionEncoding::Gzip)
+            .accept_compressed(CompressionEncoding::Gzip);
+
+        let status = client.start_task(start_task_of_size(1024)).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument, "{:?}", status);
+    }
+
+    #[test]
+    fn string_map_gives_non_strings_as_json() {
+        let value = serde_json::json!({"team": "data", "count": 3, "input": {"$ref": "fetch.result"}});
+        let map = string_map(&value);
+        assert_eq!(map["team"], "data");
+        assert_eq!(map["count"], "3");
+        assert_eq!(map["input"], r#"{"$ref":"fetch.result"}"#);
+        assert!(string_map(&serde_json::Value::Null).is_empty());
+    }
+
+    /// Insert a started workflow with one QUEUED task for `tenant_id`, returning the task's id
+    async fn insert_task(pool: &PgPool, tenant_id: Uuid) -> Uuid {
+        let workflow_id = Uuid::new_v4();
+        let task_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO workflows (id, name, state, tenant_id) VALUES ($1, 'wf', 'RUNNING', $2)")
+            .bind(workflow_id)
+            .bind(tenant_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO tasks (id, workflow_id, name, state, tenant_id, tags) VALUES ($1, $2, 'fetch', 'QUEUED', $3, $4)",
+        )
+        .bind(task_id)
+        .bind(workflow_id)
+        .bind(tenant_id)
+        .bind(serde_json::json!({"team": "data"}))
+        .execute(pool)
+        .await
+        .unwrap();
+        task_id
+    }
+
+    /// A request made as `tenant_id`
+    fn as_tenant<T>(tenant_id: Uuid, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert("x-chronos-tenant-id", tenant_id.to_string().parse().unwrap());
+        request
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn get_task_only_finds_the_callers_tasks(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let owner = Uuid::new_v4();
+        let task_id = insert_task(&pool, owner).await;
+        let get = |tenant_id| {
+            as_tenant(tenant_id, durable_engine::GetTaskRequest { task_id: task_id.to_string() })
+        };
+
+        let task = service.get_task(get(owner)).await.unwrap().into_inner().task.unwrap();
+        assert_eq!(task.id, task_id.to_string());
+        assert_eq!(task.name, "fetch");
+        assert_eq!(task.state, "QUEUED");
+        assert_eq!(task.tags["team"], "data");
+        assert_eq!(task.tenant_id, owner.to_string());
+
+        let status = service.get_task(get(Uuid::new_v4())).await.unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn other_tenants_tasks_are_missing_from_get_tasks_and_heartbeat(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let owner = Uuid::new_v4();
+        let stranger = Uuid::new_v4();
+        let task_id = insert_task(&pool, owner).await;
+        let get = |tenant_id| {
+            as_tenant(tenant_id, durable_engine::GetTasksRequest { task_ids: vec![task_id.to_string()] })
+        };
+
+        let found = service.get_tasks(get(owner)).await.unwrap().into_inner();
+        assert!(found.tasks.contains_key(&task_id.to_string()));
+        assert!(found.missing_ids.is_empty());
+        let hidden = service.get_tasks(get(stranger)).await.unwrap().into_inner();
+        assert!(hidden.tasks.is_empty());
+        assert_eq!(hidden.missing_ids, vec![task_id.to_string()]);
+
+        let heartbeat = |tenant_id| {
+            as_tenant(tenant_id, durable_engine::HeartbeatRequest { task_id: task_id.to_string() })
+        };
+        assert_eq!(service.heartbeat(heartbeat(owner)).await.unwrap().into_inner().state, "QUEUED");
+        let status = service.heartbeat(heartbeat(stranger)).await.unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn start_tasks_creates_all_or_none(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let tenant_id = Uuid::new_v4();
+        let workflow_id = insert_workflow(&pool, tenant_id).await;
+        let task = |name: &str| durable_engine::StartTaskRequest {
+            task_name: name.to_string(),
+            ..Default::default()
+        };
+        let start = |tasks| {
+            as_tenant(tenant_id, durable_engine::StartTasksRequest { workflow_id: workflow_id.to_string(), tasks })
+        };
+        let task_count = || {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tasks WHERE workflow_id = $1")
+                .bind(workflow_id)
+                .fetch_one(&pool)
+        };
+
+        let invalid = durable_engine::StartTaskRequest {
+            timeout_seconds: -1,
+            ..task("load")
+        };
+        let status = service.start_tasks(start(vec![task("fetch"), invalid])).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert!(status.message().starts_with("task at index 1: timeout_seconds"), "{}", status.message());
+        assert_eq!(task_count().await.unwrap(), 0);
+
+        let created = service
+            .start_tasks(start(vec![task("fetch"), task("load")]))
+            .await
+            .unwrap()
+            .into_inner()
+            .tasks;
+        let names: Vec<String> = futures::future::try_join_all(created.iter().map(|task| {
+            sqlx::query_scalar("SELECT name FROM tasks WHERE id = $1::uuid")
+                .bind(&task.task_id)
+                .fetch_one(&pool)
+        }))
+        .await
+        .unwrap();
+        assert_eq!(names, ["fetch", "load"]);
+        assert!(created.iter().all(|task| task.state == "QUEUED"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn start_task_refuses_dependencies_outside_the_workflow(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let tenant_id = Uuid::new_v4();
+        let workflow_id = insert_workflow(&pool, tenant_id).await;
+        let (sibling, _) = database::create_task(&pool, &new_task(workflow_id, tenant_id, "fetch")).await.unwrap();
+        let foreign_task = insert_task(&pool, Uuid::new_v4()).await;
+        let start = |depends_on: Uuid| {
+            as_tenant(
+                tenant_id,
+                durable_engine::StartTaskRequest {
+                    workflow_id: workflow_id.to_string(),
+                    task_name: "load".to_string(),
+                    parameters: HashMap::from([("input".to_string(), r#"{"$ref": "fetch.result"}"#.to_string())]),
+                    depends_on: vec![depends_on.to_string()],
+                    ..Default::default()
+                },
+            )
+        };
+
+        let status = service.start_task(start(foreign_task)).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument, "{:?}", status);
+        assert!(status.message().starts_with("depends_on:"), "{}", status.message());
+
+        service.start_task(start(sibling.id)).await.unwrap();
+    }
+
+    /// Move a task to RUNNING, as if a worker had claimed it
+    async fn set_running(pool: &PgPool, task_id: Uuid) {
+        sqlx::query("UPDATE tasks SET state = 'RUNNING', started_at = NOW() WHERE id = $1")
+            .bind(task_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn complete_task_fans_out_the_listed_subtasks(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let owner = Uuid::new_v4();
+        let task_id = insert_task(&pool, owner).await;
+        set_running(&pool, task_id).await;
+        let complete = |tenant_id| {
+            as_tenant(
+                tenant_id,
+                durable_engine::CompleteTaskRequest {
+                    task_id: task_id.to_string(),
+                    result: r#"{"$fanout": [{"name": "part", "task_type": "fetch", "parameters": {}}]}"#.to_string(),
+                },
+            )
+        };
+
+        let status = service.complete_task(complete(Uuid::new_v4())).await.unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+
+        service.complete_task(complete(owner)).await.unwrap();
+        let (state, children): (String, Vec<Uuid>) =
+            sqlx::query_as("SELECT state::TEXT, fan_out_children FROM tasks WHERE id = $1")
+                .bind(task_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(state, "QUEUED");
+        assert_eq!(children.len(), 1);
+        let child = database::get_task_by_id(&pool, children[0]).await.unwrap().unwrap();
+        assert_eq!(child.name, "part");
+        assert_eq!(child.tenant_id, owner);
+
+        // The parent is waiting on its subtasks, so it can't be reported on again
+        let status = service.complete_task(complete(owner)).await.unwrap_err();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn fail_task_retries_only_when_asked(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let owner = Uuid::new_v4();
+        let task_id = insert_task(&pool, owner).await;
+        let fail = |retry| {
+            as_tenant(
+                owner,
+                durable_engine::FailTaskRequest {
+                    task_id: task_id.to_string(),
+                    error: "connection reset".to_string(),
+                    retry,
+                    timed_out: false,
+                },
+            )
+        };
+
+        let status = service.fail_task(fail(true)).await.unwrap_err();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+
+        set_running(&pool, task_id).await;
+        assert!(service.fail_task(fail(true)).await.unwrap().into_inner().will_retry);
+        let task = database::get_task_by_id(&pool, task_id).await.unwrap().unwrap();
+        assert_eq!(task.state, TaskState::Queued);
+        assert_eq!(task.retry_count, 1);
+
+        set_running(&pool, task_id).await;
+        assert!(!service.fail_task(fail(false)).await.unwrap().into_inner().will_retry);
+        let task = database::get_task_by_id(&pool, task_id).await.unwrap().unwrap();
+        assert_eq!(task.state, TaskState::Failed);
+        assert_eq!(task.error.as_deref(), Some("connection reset"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn fail_task_records_a_worker_timeout(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let owner = Uuid::new_v4();
+        let task_id = insert_task(&pool, owner).await;
+        sqlx::query("UPDATE tasks SET max_retries = 0 WHERE id = $1")
+            .bind(task_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        set_running(&pool, task_id).await;
+
+        let request = as_tenant(
+            owner,
+            durable_engine::FailTaskRequest {
+                task_id: task_id.to_string(),
+                error: "exceeded its 30s timeout".to_string(),
+                retry: true,
+                timed_out: true,
+            },
+        );
+        assert!(!service.fail_task(request).await.unwrap().into_inner().will_retry);
+        let task = database::get_task_by_id(&pool, task_id).await.unwrap().unwrap();
+        assert_eq!(task.state, TaskState::TimedOut);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn cancelling_running_tasks_frees_their_slots(pool: PgPool) {
+        use crate::engine::tests::{free_slots, run};
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let owner = Uuid::new_v4();
+        let task_id = insert_task(&pool, owner).await;
+        let other_task_id = insert_task(&pool, owner).await;
+        run(&service.engine, task_id).await;
+        run(&service.engine, other_task_id).await;
+        assert_eq!(free_slots(&service.engine), 8);
+
+        let cancel = durable_engine::CancelTaskRequest {
+            task_id: task_id.to_string(),
+            reason: String::new(),
+        };
+        service.cancel_task(as_tenant(owner, cancel)).await.unwrap();
+        assert_eq!(free_slots(&service.engine), 9);
+
+        let other_task = database::get_task_by_id(&pool, other_task_id).await.unwrap().unwrap();
+        let cancel = durable_engine::CancelWorkflowRequest {
+            workflow_id: other_task.workflow_id.to_string(),
+            reason: String::new(),
+        };
+        service.cancel_workflow(as_tenant(owner, cancel)).await.unwrap();
+        assert_eq!(free_slots(&service.engine), 10);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn forcing_a_running_task_frees_its_slot(pool: PgPool) {
+        use crate::engine::tests::{free_slots, run};
+
+        let service = service_with(pool.clone());
+        let task_id = insert_task(&pool, Uuid::new_v4()).await;
+        run(&service.engine, task_id).await;
+        assert_eq!(free_slots(&service.engine), 9);
+
+        let outcome = database::force_resolve_task(&pool, task_id, TaskState::Failed, None, None, "stuck on a dead host")
+            .await
+            .unwrap();
+        let response = service.finish_forced(task_id, outcome).await.unwrap().into_inner();
+        assert_eq!(response.previous_state, "RUNNING");
+        assert_eq!(free_slots(&service.engine), 10);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn task_timeline_shows_why_the_engine_requeued(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let owner = Uuid::new_v4();
+        let task_id = insert_task(&pool, owner).await;
+        let workflow_id: Uuid = sqlx::query_scalar("SELECT workflow_id FROM tasks WHERE id = $1")
+            .bind(task_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        // Outside a transaction, so each event gets its own NOW() timestamp
+        let mut conn = pool.acquire().await.unwrap();
+        database::record_event(&mut conn, task_id, workflow_id, "STARTED", Some(TaskState::Queued), TaskState::Running, None)
+            .await
+            .unwrap();
+        database::record_event(
+            &mut conn,
+            task_id,
+            workflow_id,
+            "REQUEUED",
+            Some(TaskState::Running),
+            TaskState::Queued,
+            Some(ResetReason::GracefulShutdown.to_metadata()),
+        )
+        .await
+        .unwrap();
+        let timeline = |tenant_id| {
+            as_tenant(tenant_id, durable_engine::GetTaskTimelineRequest { task_id: task_id.to_string() })
+        };
+
+        let events = service.get_task_timeline(timeline(owner)).await.unwrap().into_inner().events;
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| (event.event_type.as_str(), event.new_state.as_str(), event.reset_reason.as_str()))
+            .collect();
+        assert_eq!(summary, [("STARTED", "RUNNING", ""), ("REQUEUED", "QUEUED", "GRACEFUL_SHUTDOWN")]);
+        assert_eq!(events[1].previous_state, "RUNNING");
+
+        let status = service.get_task_timeline(timeline(Uuid::new_v4())).await.unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn search_returns_the_callers_matches_with_their_creation_time(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let owner = Uuid::new_v4();
+        let task_id = insert_task(&pool, owner).await;
+        insert_task(&pool, Uuid::new_v4()).await;
+        let search = |tenant_id| {
+            as_tenant(
+                tenant_id,
+                durable_engine::SearchRequest { query: "FET".to_string(), kind: "TASK".to_string(), limit: 0 },
+            )
+        };
+
+        let results = service.search(search(owner)).await.unwrap().into_inner().results;
+        assert_eq!(results.len(), 1, "{:?}", results);
+        assert_eq!(results[0].kind, "TASK");
+        assert_eq!(results[0].id, task_id.to_string());
+        let created_at: chrono::DateTime<chrono::Utc> = results[0].created_at.parse().unwrap();
+        assert!(chrono::Utc::now() - created_at < chrono::Duration::minutes(1));
+    }
+
+    #[tokio::test]
+    async fn set_processing_enabled_requires_admin() {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = offline_service();
+        let request = Request::new(durable_engine::SetProcessingEnabledRequest { enabled: false });
+
+        let status = service.set_processing_enabled(request).await.unwrap_err();
+        assert_eq!(status.code(), Code::PermissionDenied, "{:?}", status);
+        assert!(service.engine.processing().is_enabled());
+    }
+
+    #[tokio::test]
+    async fn validate_workflow_requires_a_tenant() {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = offline_service();
+        let validate = || durable_engine::ValidateWorkflowRequest {
+            name: "etl".to_string(),
+            tasks: Vec::new(),
+        };
+
+        let status = service.validate_workflow(Request::new(validate())).await.unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated, "{:?}", status);
+        service.validate_workflow(as_tenant(Uuid::new_v4(), validate())).await.unwrap();
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn watching_an_empty_workflow_waits_for_its_tasks(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let tenant_id = Uuid::new_v4();
+        let workflow_id = insert_workflow(&pool, tenant_id).await;
+        let request = durable_engine::WatchWorkflowRequest {
+            workflow_id: workflow_id.to_string(),
+        };
+        let mut snapshots = service.watch_workflow(as_tenant(tenant_id, request)).await.unwrap().into_inner();
+
+        assert_eq!(snapshots.next().await.unwrap().unwrap().total_tasks, 0);
+        let pending = tokio::time::timeout(std::time::Duration::from_millis(100), snapshots.next()).await;
+        assert!(pending.is_err(), "the stream must stay open: {:?}", pending);
+
+        sqlx::query("INSERT INTO tasks (id, workflow_id, name, state, tenant_id) VALUES ($1, $2, 'fetch', 'COMPLETED', $3)")
+            .bind(Uuid::new_v4())
+            .bind(workflow_id)
+            .bind(tenant_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let last = snapshots.next().await.unwrap().unwrap();
+        assert_eq!((last.total_tasks, last.percent_complete), (1, 100.0));
+        assert!(snapshots.next().await.is_none());
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn start_workflow_from_each_state(pool: PgPool) {
+        use durable_engine::durable_engine_service_server::DurableEngineService as _;
+
+        let service = service_with(pool.clone());
+        let tenant_id = Uuid::new_v4();
+        let start = |workflow_id: Uuid| {
+            as_tenant(tenant_id, durable_engine::StartWorkflowRequest { workflow_id: workflow_id.to_string() })
+        };
+        let workflow_state = |workflow_id: Uuid| {
+            sqlx::query_scalar::<_, String>("SELECT state::text FROM workflows WHERE id = $1")
+                .bind(workflow_id)
+                .fetch_one(&pool)
+        };
+
+        for state in ["DRAFT", "RUNNING", "PAUSED", "COMPLETED", "FAILED", "CANCELLED"] {
+            // A root task and one that depends on it
+            let workflow_id = insert_workflow(&pool, tenant_id).await;
+            let (root, _) = database::create_task(&pool, &new_task(workflow_id, tenant_id, "root")).await.unwrap();
+            let dependent = NewTask {
+                depends_on: vec![root.id],
+                ..new_task(workflow_id, tenant_id, "dependent")
+            };
+            database::create_task(&pool, &dependent).await.unwrap();
+            sqlx::query("UPDATE workflows SET state = $2::workflow_state WHERE id = $1")
+                .bind(workflow_id)
+                .bind(state)
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let result = service.start_workflow(start(workflow_id)).await.map(Response::into_inner);
+            match state {
+                "DRAFT" => {
+                    let started = result.unwrap();
+                    assert!(!started.already_running);
+                    assert_eq!(started.enqueued_tasks, 1);
+                    assert_eq!(workflow_state(workflow_id).await.unwrap(), "RUNNING");
+
+                    // Starting again enqueues nothing
+                    let again = service.start_workflow(start(workflow_id)).await.unwrap().into_inner();
+                    assert!(again.already_running);
+                    assert_eq!(again.enqueued_tasks, 0);
+                }
+                "RUNNING" | "PAUSED" => {
+                    let started = result.unwrap();
+                    assert!(started.already_running, "{}", state);
+                    assert_eq!(started.enqueued_tasks, 0, "{}", state);
+                    assert_eq!(workflow_state(workflow_id).await.unwrap(), state);
+                }
+                _ => {
+                    assert_eq!(result.unwrap_err().code(), Code::FailedPrecondition, "{}", state);
+                    assert_eq!(workflow_state(workflow_id).await.unwrap(), state);
+                }
+            }
+        }
+
+        // Deleted workflows and those of other tenants aren't found
+        let workflow_id = insert_workflow(&pool, tenant_id).await;
+        let other_tenant = as_tenant(
+            Uuid::new_v4(),
+            durable_engine::StartWorkflowRequest { workflow_id: workflow_id.to_string() },
+        );
+        assert_eq!(service.start_workflow(other_tenant).await.unwrap_err().code(), Code::NotFound);
+        sqlx::query("UPDATE workflows SET deleted_at = NOW() WHERE id = $1")
+            .bind(workflow_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(service.start_workflow(start(workflow_id)).await.unwrap_err().code(), Code::NotFound);
+    }
+}
@@ -0,0 +1,109 @@
+use tonic::{Request, Status};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Who a request is made on behalf of.
+///
+/// The engine doesn't verify credentials itself. It runs behind the gateway
+/// that checks the caller's bearer token, rejects tenants the token doesn't
+/// grant, and forwards the verified identity as `x-chronos-tenant-id` and
+/// `x-chronos-principal`. A request without a tenant is refused rather than
+/// given a shared one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub tenant_id: Uuid,
+    /// The authenticated subject, empty if the gateway didn't name one
+    pub subject: String,
+}
+
+impl Principal {
+    /// UNAUTHENTICATED if the tenant header is missing or not a UUID
+    pub fn from_request<T>(request: &Request<T>) -> Result<Self, Status> {
+        let metadata = request.metadata();
+        let tenant_id = metadata
+            .get("x-chronos-tenant-id")
+            .ok_or_else(|| Status::unauthenticated("x-chronos-tenant-id is required"))?
+            .to_str()
+            .ok()
+            .and_then(|value| Uuid::parse_str(value).ok())
+            .ok_or_else(|| Status::unauthenticated("x-chronos-tenant-id is not a UUID"))?;
+        let subject = metadata
+            .get("x-chronos-principal")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(Self { tenant_id, subject })
+    }
+}
+
+/// Guards admin RPCs that override the task state machine.
+///
+/// Callers must send `authorization: Bearer <ADMIN_TOKEN>`. Without a
+/// configured token every admin call is refused, so the RPCs are off unless
+/// an operator opts in.
+#[derive(Clone)]
+pub struct AdminAuth {
+    token: Option<String>,
+}
+
+impl AdminAuth {
+    /// Read the admin token from `ADMIN_TOKEN`; unset or empty disables admin RPCs
+    pub fn from_env() -> Self {
+        let token = std::env::var("ADMIN_TOKEN").ok().filter(|token| !token.is_empty());
+        if token.is_none() {
+            warn!("ADMIN_TOKEN is not set; admin RPCs are disabled");
+        }
+        Self { token }
+    }
+
+    /// UNAUTHENTICATED without a bearer token, PERMISSION_DENIED if it isn't the admin token
+    pub fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let Some(expected) = &self.token else {
+            return Err(Status::permission_denied("Admin RPCs are disabled on this engine"));
+        };
+
+        let presented = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("Admin RPCs require a bearer token"))?;
+
+        if !constant_time_eq(presented.as_bytes(), expected.as_bytes()) {
+            return Err(Status::permission_denied("Token does not grant admin access"));
+        }
+
+        Ok(())
+    }
+}
+
+// Compare without returning early so response timing doesn't leak the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(tenant_id: Option<&str>) -> Request<()> {
+        let mut request = Request::new(());
+        if let Some(tenant_id) = tenant_id {
+            request.metadata_mut().insert("x-chronos-tenant-id", tenant_id.parse().unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn requests_must_name_a_tenant() {
+        let tenant_id = Uuid::new_v4();
+        let principal = Principal::from_request(&request(Some(&tenant_id.to_string()))).unwrap();
+        assert_eq!(principal.tenant_id, tenant_id);
+
+        for tenant_id in [None, Some("not-a-uuid")] {
+            let status = Principal::from_request(&request(tenant_id)).unwrap_err();
+            assert_eq!(status.code(), tonic::Code::Unauthenticated, "{:?}", tenant_id);
+        }
+    }
+}
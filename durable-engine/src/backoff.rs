@@ -0,0 +1,217 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+use uuid::Uuid;
+
+/// How long a failed task waits before its next attempt.
+///
+/// Set for every task with `TASK_RETRY_BACKOFF`, or for one task when it is
+/// created, as JSON such as
+/// `{"strategy": "exponential", "initial_ms": 1000, "multiplier": 2, "max_ms": 60000}`.
+/// Must match the client's `backoff` module, which sends the same JSON; both
+/// are tested against `proto/testdata/retry_backoff.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    /// The same delay before every retry; zero retries straight away
+    Fixed { delay_ms: u64 },
+    /// `initial_ms` before the first retry and `step_ms` more before each
+    /// one after it, up to `max_ms`
+    Linear {
+        initial_ms: u64,
+        step_ms: u64,
+        #[serde(default = "default_max_ms")]
+        max_ms: u64,
+    },
+    /// `initial_ms` before the first retry, multiplied by `multiplier` for
+    /// each one after it, up to `max_ms`
+    Exponential {
+        initial_ms: u64,
+        #[serde(default = "default_multiplier")]
+        multiplier: f64,
+        #[serde(default = "default_max_ms")]
+        max_ms: u64,
+    },
+    /// A random delay between zero and what `Exponential` would wait, so
+    /// tasks that failed together don't all retry together
+    ExponentialWithJitter {
+        initial_ms: u64,
+        #[serde(default = "default_multiplier")]
+        multiplier: f64,
+        #[serde(default = "default_max_ms")]
+        max_ms: u64,
+    },
+}
+
+fn default_multiplier() -> f64 {
+    2.0
+}
+
+/// One hour
+fn default_max_ms() -> u64 {
+    3_600_000
+}
+
+impl Default for BackoffStrategy {
+    /// Retry straight away, as tasks always have
+    fn default() -> Self {
+        BackoffStrategy::Fixed { delay_ms: 0 }
+    }
+}
+
+impl BackoffStrategy {
+    /// Read the strategy for tasks that don't set their own from the JSON in
+    /// `TASK_RETRY_BACKOFF`; unset retries straight away
+    pub fn from_env() -> Result<Self> {
+        let Ok(json) = std::env::var("TASK_RETRY_BACKOFF") else {
+            return Ok(Self::default());
+        };
+        let strategy = Self::parse(&json).map_err(anyhow::Error::msg).context("Invalid TASK_RETRY_BACKOFF")?;
+
+        info!("Retrying failed tasks with backoff {:?}", strategy);
+        Ok(strategy)
+    }
+
+    /// Parse and check a strategy given as JSON
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let strategy: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        strategy.validate()?;
+        Ok(strategy)
+    }
+
+    /// Check the parameters make sense, e.g. that `max_ms` isn't below `initial_ms`
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            BackoffStrategy::Fixed { .. } => Ok(()),
+            BackoffStrategy::Exponential { multiplier, .. } | BackoffStrategy::ExponentialWithJitter { multiplier, .. }
+                if !(multiplier.is_finite() && multiplier >= 1.0) =>
+            {
+                Err(format!("multiplier must be at least 1, got {}", multiplier))
+            }
+            BackoffStrategy::Linear { initial_ms, max_ms, .. }
+            | BackoffStrategy::Exponential { initial_ms, max_ms, .. }
+            | BackoffStrategy::ExponentialWithJitter { initial_ms, max_ms, .. }
+                if max_ms < initial_ms =>
+            {
+                Err(format!("max_ms ({}) is less than initial_ms ({})", max_ms, initial_ms))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// How long to wait before retry number `retry`, counting from 1
+    pub fn delay(&self, retry: u32) -> Duration {
+        let steps = retry.saturating_sub(1);
+        let millis = match *self {
+            BackoffStrategy::Fixed { delay_ms } => delay_ms,
+            BackoffStrategy::Linear { initial_ms, step_ms, max_ms } => initial_ms
+                .saturating_add(step_ms.saturating_mul(u64::from(steps)))
+                .min(max_ms),
+            BackoffStrategy::Exponential { initial_ms, multiplier, max_ms } => {
+                exponential(initial_ms, multiplier, max_ms, steps)
+            }
+            BackoffStrategy::ExponentialWithJitter { initial_ms, multiplier, max_ms } => {
+                let ceiling = exponential(initial_ms, multiplier, max_ms, steps);
+                (ceiling as f64 * unit_random()) as u64
+            }
+        };
+        Duration::from_millis(millis)
+    }
+}
+
+/// `initial_ms * multiplier^steps`, capped at `max_ms`
+fn exponential(initial_ms: u64, multiplier: f64, max_ms: u64, steps: u32) -> u64 {
+    let millis = initial_ms as f64 * multiplier.powf(f64::from(steps));
+    // Casting saturates, and the cap is applied in floating point first so an
+    // overflowing power still ends up at max_ms
+    millis.min(max_ms as f64) as u64
+}
+
+/// A number in `[0, 1)`, from the random bits of a v4 UUID so the engine
+/// doesn't need a separate random number generator for jitter
+fn unit_random() -> f64 {
+    // The low 53 bits are clear of the version and variant bits, and fill an f64 mantissa
+    let bits = Uuid::new_v4().as_u128() as u64 & ((1 << 53) - 1);
+    bits as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// Strategies and the delays they give, shared with the client's tests
+    const CASES: &str = include_str!("../../proto/testdata/retry_backoff.json");
+
+    fn cases(kind: &str) -> Vec<Value> {
+        let cases: Value = serde_json::from_str(CASES).unwrap();
+        cases[kind].as_array().unwrap().clone()
+    }
+
+    fn millis(values: &Value) -> Vec<u64> {
+        values.as_array().unwrap().iter().map(|ms| ms.as_u64().unwrap()).collect()
+    }
+
+    #[test]
+    fn delays_follow_the_strategy() {
+        for case in cases("valid").into_iter().filter(|case| case.get("delays_ms").is_some()) {
+            let strategy = BackoffStrategy::parse(&case["json"].to_string()).unwrap();
+            let delays: Vec<u64> = (1..=case["delays_ms"].as_array().unwrap().len() as u32)
+                .map(|retry| strategy.delay(retry).as_millis() as u64)
+                .collect();
+            assert_eq!(delays, millis(&case["delays_ms"]), "{}", case["json"]);
+        }
+    }
+
+    #[test]
+    fn jitter_stays_between_zero_and_the_exponential_delay() {
+        for case in cases("valid").into_iter().filter(|case| case.get("max_delays_ms").is_some()) {
+            let strategy = BackoffStrategy::parse(&case["json"].to_string()).unwrap();
+            for (retry, ceiling) in (1..).zip(millis(&case["max_delays_ms"])) {
+                let delays: Vec<u64> = (0..200).map(|_| strategy.delay(retry).as_millis() as u64).collect();
+                assert!(delays.iter().all(|&delay| delay <= ceiling), "retry {}: {:?}", retry, delays);
+                assert!(delays.iter().any(|&delay| delay != delays[0]), "retry {} never varied", retry);
+            }
+        }
+    }
+
+    #[test]
+    fn unit_random_is_in_the_unit_interval() {
+        assert!((0..10_000).map(|_| unit_random()).all(|n| (0.0..1.0).contains(&n)));
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        for json in cases("invalid") {
+            let error = BackoffStrategy::parse(&json.to_string()).unwrap_err();
+            assert!(error.contains("multiplier") || error.contains("max_ms"), "{}: {}", json, error);
+        }
+        for json in cases("malformed") {
+            assert!(BackoffStrategy::parse(&json.to_string()).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn serializes_to_json_it_parses_back() {
+        for case in cases("valid") {
+            let strategy = BackoffStrategy::parse(&case["json"].to_string()).unwrap();
+            let json = serde_json::to_string(&strategy).unwrap();
+            assert_eq!(BackoffStrategy::parse(&json), Ok(strategy));
+        }
+        assert_eq!(
+            serde_json::to_value(BackoffStrategy::default()).unwrap(),
+            serde_json::json!({"strategy": "fixed", "delay_ms": 0})
+        );
+    }
+
+    #[test]
+    fn long_exponential_sequences_saturate_at_max_ms() {
+        let strategy = BackoffStrategy::Exponential {
+            initial_ms: 1,
+            multiplier: 10.0,
+            max_ms: u64::MAX,
+        };
+        assert_eq!(strategy.delay(u32::MAX), Duration::from_millis(u64::MAX));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::database::env_or;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::ObjectStore;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::sync::Arc;
+use tracing::info;
+
+/// Key marking a JSON value as a reference to an offloaded blob.
+///
+/// Must match the client's `blob` module, which resolves these references.
+pub const BLOB_REF_KEY: &str = "$blob";
+
+/// Hex SHA-256 of a value's compact JSON encoding.
+///
+/// That encoding is both what clients receive as a task's result and what
+/// [`PayloadStore::offload`] uploads, so a client can check a result it read
+/// inline or from a blob against the same checksum.
+pub fn checksum(value: &Value) -> String {
+    let data = serde_json::to_vec(value).expect("JSON values always serialize");
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// External storage for task payloads and results too large to keep inline.
+///
+/// The engine only writes blobs; clients fetch them when resolving references.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()>;
+}
+
+/// S3-compatible blob store (AWS, MinIO, ...)
+pub struct S3BlobStore {
+    store: AmazonS3,
+}
+
+impl S3BlobStore {
+    /// Credentials and region come from the standard `AWS_*` variables;
+    /// `BLOB_ENDPOINT` points at a non-AWS service
+    pub fn from_env(bucket: &str) -> Result<Self> {
+        let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+        if let Ok(endpoint) = env::var("BLOB_ENDPOINT") {
+            builder = builder
+                .with_allow_http(endpoint.starts_with("http://"))
+                .with_endpoint(endpoint);
+        }
+
+        Ok(Self {
+            store: builder.build().context("Failed to configure S3 blob store")?,
+        })
+    }
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        self.store
+            .put(&Path::from(key), data.into())
+            .await
+            .with_context(|| format!("Failed to upload blob {}", key))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OffloadError {
+    #[error("value is {size} bytes, over the {limit} byte inline limit, and no blob store is configured")]
+    TooLarge { size: usize, limit: usize },
+
+    #[error(transparent)]
+    Store(#[from] anyhow::Error),
+}
+
+/// Decides whether a JSON value is stored inline or offloaded to the blob store
+#[derive(Clone)]
+pub struct PayloadStore {
+    blobs: Option<Arc<dyn BlobStore>>,
+    max_inline_bytes: usize,
+}
+
+impl PayloadStore {
+    /// Read from `BLOB_MAX_INLINE_BYTES` (default 262144) and `BLOB_BUCKET`.
+    ///
+    /// Without a bucket, values over the limit are rejected instead of offloaded.
+    pub fn from_env() -> Result<Self> {
+        let max_inline_bytes = env_or("BLOB_MAX_INLINE_BYTES", 256 * 1024)?;
+        let blobs: Option<Arc<dyn BlobStore>> = match env::var("BLOB_BUCKET") {
+            Ok(bucket) => {
+                info!("Offloading payloads over {} bytes to bucket {}", max_inline_bytes, bucket);
+                Some(Arc::new(S3BlobStore::from_env(&bucket)?))
+            }
+            Err(_) => None,
+        };
+
+        Ok(Self { blobs, max_inline_bytes })
+    }
+
+    /// Largest value, in bytes, kept inline in a row
+    pub fn max_inline_bytes(&self) -> usize {
+        self.max_inline_bytes
+    }
+
+    /// Return `value` unchanged if it fits inline, otherwise upload it under
+    /// `key` and return a reference to store in its place
+    pub async fn offload(&self, key: &str, value: Value) -> Result<Value, OffloadError> {
+        let data = serde_json::to_vec(&value).map_err(anyhow::Error::from)?;
+        if data.len() <= self.max_inline_bytes {
+            return Ok(value);
+        }
+
+        let Some(blobs) = &self.blobs else {
+            return Err(OffloadError::TooLarge {
+                size: data.len(),
+                limit: self.max_inline_bytes,
+            });
+        };
+
+        let size = data.len();
+        blobs.put(key, data).await?;
+
+        Ok(json!({ BLOB_REF_KEY: { "key": key, "size": size } }))
+    }
+}
@@ -1,87 +1,64 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::time::Duration;
-use tonic::transport::Channel;
+use tonic::transport::{Channel, Endpoint};
 use tracing::info;
 
-// In a real implementation, this would be generated from the proto files
-// For this sample, we'll define a simplified version manually
-pub mod durable_engine_client {
-    use tonic::{Request, Response, Status};
-    
-    // Since we don't have actual proto files yet, we'll define these manually
-    #[derive(Debug)]
-    pub struct Task {
-        pub id: String,
-        pub workflow_id: String,
-        pub name: String,
-        pub state: String,
-    }
-    
-    #[derive(Debug)]
-    pub struct GetTaskRequest {
-        pub task_id: String,
-    }
-    
-    #[derive(Debug)]
-    pub struct GetTaskResponse {
-        pub task: Option<Task>,
-    }
-    
-    #[derive(Debug)]
-    pub struct UpdateTaskStateRequest {
-        pub task_id: String,
-        pub new_state: String,
-    }
-    
-    #[derive(Debug)]
-    pub struct UpdateTaskStateResponse {
-        pub success: bool,
-    }
-    
-    #[derive(Debug, Clone)]
-    pub struct DurableEngineClient<T> {
-        inner: T,
-    }
-    
-    impl<T> DurableEngineClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            Self { inner }
-        }
-        
-        pub async fn get_task(
-            &mut self,
-            request: Request<GetTaskRequest>,
-        ) -> Result<Response<GetTaskResponse>, Status> {
-            // In a real implementation, this would make a gRPC call
-            unimplemented!()
-        }
-        
-        pub async fn update_task_state(
-            &mut self,
-            request: Request<UpdateTaskStateRequest>,
-        ) -> Result<Response<UpdateTaskStateResponse>, Status> {
-            // In a real implementation, this would make a gRPC call
-            unimplemented!()
+pub mod durable_engine {
+    tonic::include_proto!("durable_engine");
+}
+
+use durable_engine::durable_engine_service_client::DurableEngineServiceClient;
+
+/// Connection settings for [`create_client`]
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// How often to send HTTP/2 PING frames to keep the connection alive
+    pub keepalive_interval: Duration,
+    /// How long to wait for a PING ack before treating the peer as dead
+    pub keepalive_timeout: Duration,
+    /// Keep pinging while no calls are in flight, so idle connections through
+    /// load balancers are not silently dropped
+    pub keepalive_while_idle: bool,
+    pub connect_timeout: Duration,
+    pub tcp_nodelay: bool,
+    /// Deadline applied to every call that doesn't set its own
+    pub request_timeout: Option<Duration>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            keepalive_interval: Duration::from_secs(30),
+            keepalive_timeout: Duration::from_secs(10),
+            keepalive_while_idle: true,
+            connect_timeout: Duration::from_secs(5),
+            tcp_nodelay: true,
+            request_timeout: Some(Duration::from_secs(30)),
         }
     }
-    
-    // These imports would normally be provided by the generated code
-    use bytes::Bytes;
-    use std::error::StdError;
-    use tonic::body::Body;
 }
 
 /// Create a new client for the durable engine service
-pub async fn create_client(addr: &str) -> Result<durable_engine_client::DurableEngineClient<Channel>> {
+pub async fn create_client(
+    addr: &str,
+    config: &ClientConfig,
+) -> Result<DurableEngineServiceClient<Channel>> {
     info!("Connecting to durable engine at {}", addr);
     
-    // In a real implementation, this would create a gRPC client
-    // For this sample, we'll just return an error
-    Err(anyhow::anyhow!("Not implemented"))
+    let mut endpoint = Endpoint::from_shared(addr.to_string())?
+        .http2_keep_alive_interval(config.keepalive_interval)
+        .keep_alive_timeout(config.keepalive_timeout)
+        .keep_alive_while_idle(config.keepalive_while_idle)
+        .connect_timeout(config.connect_timeout)
+        .tcp_nodelay(config.tcp_nodelay);
+    if let Some(timeout) = config.request_timeout {
+        endpoint = endpoint.timeout(timeout);
+    }
+    
+    let channel = endpoint
+        .connect()
+        .await
+        .with_context(|| format!("Failed to connect to durable engine at {}", addr))?;
+    
+    Ok(DurableEngineServiceClient::new(channel))
 }
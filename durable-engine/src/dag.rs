@@ -0,0 +1,137 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Nodes that depend on each other in a loop, so none of them can ever run.
+///
+/// Each node depends on the next, and the last depends on the first; a
+/// self-dependency is a cycle of one. Displays as `a -> b -> a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<K>(pub Vec<K>);
+
+impl<K: std::fmt::Display> std::fmt::Display for Cycle<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for node in &self.0 {
+            write!(f, "{} -> ", node)?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Order `nodes` so each comes after everything it depends on, or return one
+/// of the cycles that makes that impossible.
+///
+/// `nodes` pairs each node with its dependencies. Dependencies that aren't
+/// themselves in `nodes` are ignored, and nodes without dependencies between
+/// them keep their input order.
+pub fn topological_sort<K: Eq + Hash + Clone>(nodes: &[(K, Vec<K>)]) -> Result<Vec<K>, Cycle<K>> {
+    let index: HashMap<&K, usize> = nodes.iter().enumerate().map(|(i, (node, _))| (node, i)).collect();
+    let dependencies: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|(_, depends_on)| depends_on.iter().filter_map(|d| index.get(d).copied()).collect())
+        .collect();
+
+    let mut waiting_on: Vec<usize> = dependencies.iter().map(Vec::len).collect();
+    let mut dependents = vec![Vec::new(); nodes.len()];
+    for (node, node_dependencies) in dependencies.iter().enumerate() {
+        for &dependency in node_dependencies {
+            dependents[dependency].push(node);
+        }
+    }
+
+    // Kahn's algorithm: repeatedly take a node whose dependencies are all placed
+    let mut ready: VecDeque<usize> = (0..nodes.len()).filter(|&i| waiting_on[i] == 0).collect();
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(node) = ready.pop_front() {
+        order.push(node);
+        for &dependent in &dependents[node] {
+            waiting_on[dependent] -= 1;
+            if waiting_on[dependent] == 0 {
+                ready.push_back(dependent);
+            }
+        }
+    }
+
+    let Some(mut current) = (0..nodes.len()).find(|&i| waiting_on[i] > 0) else {
+        return Ok(order.into_iter().map(|i| nodes[i].0.clone()).collect());
+    };
+
+    // Every unplaced node waits on another unplaced node, so following those
+    // dependencies must eventually revisit a node
+    let mut path = Vec::new();
+    let mut position = HashMap::new();
+    loop {
+        if let Some(&start) = position.get(&current) {
+            let cycle = path[start..].iter().map(|&i: &usize| nodes[i].0.clone()).collect();
+            return Err(Cycle(cycle));
+        }
+        position.insert(current, path.len());
+        path.push(current);
+        current = dependencies[current]
+            .iter()
+            .copied()
+            .find(|&dependency| waiting_on[dependency] > 0)
+            .expect("an unplaced node has an unplaced dependency");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&'static str, &[&'static str])]) -> Vec<(&'static str, Vec<&'static str>)> {
+        edges.iter().map(|(node, depends_on)| (*node, depends_on.to_vec())).collect()
+    }
+
+    /// Check `cycle` is a rotation of `expected`, since any node can start it
+    fn assert_cycle(cycle: Cycle<&str>, expected: &[&str]) {
+        assert_eq!(cycle.0.len(), expected.len(), "{}", cycle);
+        let start = expected.iter().position(|node| *node == cycle.0[0]).unwrap();
+        let rotated: Vec<&str> = expected[start..].iter().chain(&expected[..start]).copied().collect();
+        assert_eq!(cycle.0, rotated);
+    }
+
+    #[test]
+    fn orders_dependencies_first_and_keeps_input_order_otherwise() {
+        let nodes = graph(&[("report", &["clean", "fetch"]), ("clean", &["fetch"]), ("fetch", &[]), ("notify", &[])]);
+        assert_eq!(topological_sort(&nodes), Ok(vec!["fetch", "notify", "clean", "report"]));
+    }
+
+    #[test]
+    fn ignores_dependencies_outside_the_graph() {
+        let nodes = graph(&[("b", &["a", "elsewhere"]), ("a", &[])]);
+        assert_eq!(topological_sort(&nodes), Ok(vec!["a", "b"]));
+    }
+
+    #[test]
+    fn self_dependency_is_a_cycle_of_one() {
+        let nodes = graph(&[("a", &[]), ("b", &["a", "b"])]);
+        let cycle = topological_sort(&nodes).unwrap_err();
+        assert_eq!(cycle, Cycle(vec!["b"]));
+        assert_eq!(cycle.to_string(), "b -> b");
+    }
+
+    #[test]
+    fn finds_a_two_node_cycle() {
+        let nodes = graph(&[("a", &["b"]), ("b", &["a"])]);
+        let cycle = topological_sort(&nodes).unwrap_err();
+        assert_cycle(cycle, &["a", "b"]);
+    }
+
+    #[test]
+    fn names_only_the_cycle_in_a_larger_graph() {
+        // d is stuck behind the cycle without being part of it
+        let nodes = graph(&[
+            ("start", &[]),
+            ("d", &["c"]),
+            ("a", &["start", "b"]),
+            ("b", &["c"]),
+            ("c", &["a"]),
+            ("end", &["start"]),
+        ]);
+        let cycle = topological_sort(&nodes).unwrap_err();
+        assert_cycle(cycle, &["a", "b", "c"]);
+    }
+}
@@ -1,92 +1,637 @@
-use crate::models::{Task, TaskEvent, TaskState};
+use crate::backoff::BackoffStrategy;
+use crate::blob::{self, PayloadStore, BLOB_REF_KEY};
+use crate::database;
+use crate::events::EventPublisher;
+use crate::fan_out;
+use crate::metrics;
+use crate::models::{PayloadFormat, ResetReason, Task, TaskEvent, TaskState};
+use crate::queue::{self, DecodedTaskMessage, KafkaConfig, LoggingConsumer, TaskMessage};
+use crate::references;
+use crate::requirements::WorkerLabels;
+use crate::retry_budget::RetryBudget;
+use crate::schema::SchemaRegistry;
+use crate::timeouts::TimeoutRegistry;
 use anyhow::{Context, Result};
-use rdkafka::consumer::{Consumer, StreamConsumer};
-use sqlx::PgPool;
+use futures::StreamExt;
+use rdkafka::consumer::{CommitMode, Consumer};
+use rdkafka::message::{BorrowedMessage, Message};
+use rdkafka::Offset;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::{error, info, warn};
+use std::time::Duration;
+use tokio::sync::{watch, Mutex, Semaphore, SemaphorePermit};
+use tokio::task::JoinHandle;
+use tracing::{error, field, info, info_span, instrument, warn, Instrument, Span};
 use uuid::Uuid;
 
+/// Most queued tasks claimed from the database per reconciliation pass
+const QUEUED_BATCH_SIZE: i64 = 100;
+/// Consumer errors in a row before the Kafka consumer is rebuilt
+const MAX_CONSECUTIVE_CONSUMER_ERRORS: u32 = 5;
+/// How often the consumer loop re-checks whether processing is paused
+const PAUSE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// How long to wait for the broker when rewinding a message received while paused
+const SEEK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Wait before a message that failed to process is redelivered
+const FAILED_MESSAGE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Task type the engine runs itself: once its dependencies have completed it
+/// completes with their results merged, as chosen by its `merge` parameter
+pub const COLLECTOR_TASK_TYPE: &str = "collect";
+
+/// How a collector task combines its dependencies' results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeMode {
+    /// A JSON array, oldest dependency first
+    Array,
+    /// A JSON object keyed by dependency task name
+    Object,
+}
+
+impl MergeMode {
+    /// Read from the `merge` parameter, `"array"` (the default) or `"object"`
+    fn from_parameters(parameters: &serde_json::Value) -> Result<Self, String> {
+        match parameters.get("merge") {
+            None => Ok(MergeMode::Array),
+            Some(mode) => match mode.as_str() {
+                Some("array") => Ok(MergeMode::Array),
+                Some("object") => Ok(MergeMode::Object),
+                _ => Err(format!("merge must be \"array\" or \"object\", got: {}", mode)),
+            },
+        }
+    }
+}
+
+/// Timing of the reconciliation loop
+#[derive(Debug, Clone, Copy)]
+pub struct ReconciliationConfig {
+    pub interval: Duration,
+    /// How long a task that never heartbeats may stay RUNNING before it is
+    /// treated as stuck, when it has no `timeout_seconds` of its own
+    pub stuck_threshold: Duration,
+    /// How long a task that has heartbeated may go without another one
+    pub heartbeat_timeout: Duration,
+}
+
+impl ReconciliationConfig {
+    /// Read from `RECONCILIATION_INTERVAL_SECS` (default 60),
+    /// `STUCK_TASK_THRESHOLD_SECS` (default 3600) and `HEARTBEAT_TIMEOUT_SECS`
+    /// (default 120); all must be positive
+    pub fn from_env() -> Result<Self> {
+        let interval_secs: u64 = database::env_or("RECONCILIATION_INTERVAL_SECS", 60)?;
+        let stuck_threshold_secs: u64 = database::env_or("STUCK_TASK_THRESHOLD_SECS", 3600)?;
+        let heartbeat_timeout_secs: u64 = database::env_or("HEARTBEAT_TIMEOUT_SECS", 120)?;
+        
+        if interval_secs == 0 {
+            anyhow::bail!("RECONCILIATION_INTERVAL_SECS must be positive");
+        }
+        if stuck_threshold_secs == 0 {
+            anyhow::bail!("STUCK_TASK_THRESHOLD_SECS must be positive");
+        }
+        if heartbeat_timeout_secs == 0 {
+            anyhow::bail!("HEARTBEAT_TIMEOUT_SECS must be positive");
+        }
+        
+        Ok(Self {
+            interval: Duration::from_secs(interval_secs),
+            stuck_threshold: Duration::from_secs(stuck_threshold_secs),
+            heartbeat_timeout: Duration::from_secs(heartbeat_timeout_secs),
+        })
+    }
+}
+
+/// Operator switch for pausing task intake without restarting the engine.
+///
+/// While paused the engine takes no new work, from Kafka or the queued-task
+/// sweep, but running tasks finish and stuck-task reconciliation continues.
+#[derive(Clone)]
+pub struct ProcessingSwitch {
+    enabled: Arc<AtomicBool>,
+}
+
+impl ProcessingSwitch {
+    pub fn new() -> Self {
+        metrics::PROCESSING_PAUSED.set(0);
+        Self {
+            enabled: Arc::new(AtomicBool::new(true)),
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+    
+    /// Returns whether processing was enabled before the call
+    pub fn set_enabled(&self, enabled: bool) -> bool {
+        let previous = self.enabled.swap(enabled, Ordering::SeqCst);
+        metrics::PROCESSING_PAUSED.set(i64::from(!enabled));
+        if previous != enabled {
+            info!("Task processing {}", if enabled { "resumed" } else { "paused" });
+        }
+        previous
+    }
+}
+
+impl Default for ProcessingSwitch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A claimed task after its start was written, before the transaction commits
+enum Started {
+    Running(Task),
+    /// Fanned out earlier and its subtasks have completed, so it only
+    /// collects their results
+    Joining(Task),
+    /// Failed parameter validation without using a retry
+    Invalid { task_id: Uuid, reason: String },
+}
+
+/// Settings of a [`TaskEngine`], apart from the handles it shares with the API
+pub struct EngineConfig {
+    pub reconciliation: ReconciliationConfig,
+    /// Only tasks whose requirements these labels satisfy are claimed
+    pub worker_labels: WorkerLabels,
+    /// Delays retries once too many tasks are failing at once
+    pub retry_budget: RetryBudget,
+    /// Wait between retries of tasks that don't set their own backoff
+    pub retry_backoff: BackoffStrategy,
+    /// Most tasks the engine runs at once
+    pub max_concurrent_tasks: usize,
+}
+
+#[derive(Clone)]
 pub struct TaskEngine {
     db_pool: PgPool,
-    active_tasks: Arc<Mutex<Vec<Uuid>>>,
+    /// Offloads oversized task results to the blob store
+    payloads: PayloadStore,
+    /// Parameters are checked against these before a task runs
+    schemas: Arc<SchemaRegistry>,
+    reconciliation: ReconciliationConfig,
+    processing: ProcessingSwitch,
+    /// Every recorded task event is published here once its transaction commits
+    events: EventPublisher,
+    /// Timeouts for subtasks created by a fan-out that don't set their own
+    timeouts: TimeoutRegistry,
+    /// Only tasks whose requirements these labels satisfy are claimed
+    worker_labels: WorkerLabels,
+    /// Delays retries once too many tasks are failing at once
+    retry_budget: RetryBudget,
+    /// Wait between retries of tasks that don't set their own backoff
+    retry_backoff: BackoffStrategy,
+    active_tasks: Arc<Mutex<HashSet<Uuid>>>,
+    /// One permit per task this engine may run at once, held while a task is RUNNING
+    capacity: Arc<Semaphore>,
+    max_concurrent_tasks: usize,
+    /// Identifies this engine instance in task event metadata
+    worker_id: String,
+    /// Flipped to true to stop consuming new messages
+    shutdown_tx: Arc<watch::Sender<bool>>,
+    consumer_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
 impl TaskEngine {
-    pub fn new(db_pool: PgPool) -> Self {
+    pub fn new(
+        db_pool: PgPool,
+        payloads: PayloadStore,
+        schemas: Arc<SchemaRegistry>,
+        processing: ProcessingSwitch,
+        events: EventPublisher,
+        timeouts: TimeoutRegistry,
+        config: EngineConfig,
+    ) -> Self {
+        let worker_id = std::env::var("WORKER_ID")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_else(|_| "durable-engine".to_string());
+        
+        let (shutdown_tx, _) = watch::channel(false);
+        
         Self {
             db_pool,
-            active_tasks: Arc::new(Mutex::new(Vec::new())),
+            payloads,
+            schemas,
+            reconciliation: config.reconciliation,
+            processing,
+            events,
+            timeouts,
+            worker_labels: config.worker_labels,
+            retry_budget: config.retry_budget,
+            retry_backoff: config.retry_backoff,
+            active_tasks: Arc::new(Mutex::new(HashSet::new())),
+            capacity: Arc::new(Semaphore::new(config.max_concurrent_tasks)),
+            max_concurrent_tasks: config.max_concurrent_tasks,
+            worker_id,
+            shutdown_tx: Arc::new(shutdown_tx),
+            consumer_handle: Arc::new(Mutex::new(None)),
         }
     }
 
     /// Start processing tasks from the Kafka queue
-    pub async fn start_processing(&self, consumer: StreamConsumer) -> Result<()> {
+    pub async fn start_processing(&self, consumer: LoggingConsumer, kafka_config: KafkaConfig) -> Result<()> {
         info!("Starting task processing loop");
         
         // Start the reconciliation loop in a separate task
-        let db_pool_clone = self.db_pool.clone();
-        let active_tasks_clone = self.active_tasks.clone();
+        let engine = self.clone();
         tokio::spawn(async move {
-            if let Err(e) = Self::run_reconciliation_loop(db_pool_clone, active_tasks_clone).await {
-                error!("Reconciliation loop failed: {:?}", e);
-            }
+            engine.run_reconciliation_loop().await;
         });
         
         // Main processing loop
-        // In a real implementation, this would consume messages from Kafka
-        // and process them
+        let engine = self.clone();
+        let shutdown_rx = self.shutdown_tx.subscribe();
+        let handle = tokio::spawn(async move {
+            engine.run_consumer_loop(consumer, kafka_config, shutdown_rx).await;
+        });
+        *self.consumer_handle.lock().await = Some(handle);
+        
+        Ok(())
+    }
+    
+    /// Consume task messages until shutdown is signalled, then commit offsets.
+    ///
+    /// Messages are handled strictly one at a time, each finishing before the
+    /// next is read. Task messages are keyed by workflow id, so this is what
+    /// keeps tasks of one workflow from running out of order; don't spawn
+    /// message handling onto separate tasks.
+    ///
+    /// While processing is paused, or the engine is running as many tasks as
+    /// it may, the assigned partitions are paused too, so the consumer stays in
+    /// its group and resumes from the same offsets once work can be taken again.
+    ///
+    /// A message that fails to process is rewound and redelivered, so its
+    /// partition never commits past it. If the rewind fails the consumer is
+    /// rebuilt, resuming from the last committed offset.
+    ///
+    /// A run of consecutive consumer errors rebuilds the consumer instead of
+    /// ending the loop, so a Kafka restart doesn't stop task processing.
+    async fn run_consumer_loop(
+        &self,
+        mut consumer: LoggingConsumer,
+        kafka_config: KafkaConfig,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) {
+        loop {
+            let reconnect = {
+                let mut stream = consumer.stream();
+                let mut consecutive_errors = 0;
+                let mut paused = false;
+                let mut pause_check = tokio::time::interval(PAUSE_CHECK_INTERVAL);
+                
+                loop {
+                    tokio::select! {
+                        _ = shutdown_rx.changed() => break false,
+                        _ = pause_check.tick() => {
+                            // Re-applied while paused so partitions assigned by a rebalance are paused too
+                            let pause = !self.accepting_work();
+                            if pause || paused {
+                                match set_partitions_paused(&consumer, pause) {
+                                    Ok(()) => paused = pause,
+                                    Err(e) => warn!("Failed to {} consumer: {}", if pause { "pause" } else { "resume" }, e),
+                                }
+                            }
+                        }
+                        message = stream.next() => match message {
+                            Some(Ok(message)) if !self.accepting_work() => {
+                                // Fetched before the pause took effect; rewind so it
+                                // is redelivered on resume instead of processed now
+                                if let Err(e) = consumer.seek(
+                                    message.topic(),
+                                    message.partition(),
+                                    Offset::Offset(message.offset()),
+                                    SEEK_TIMEOUT,
+                                ) {
+                                    warn!("Failed to rewind offset {} while paused: {}", message.offset(), e);
+                                }
+                            }
+                            Some(Ok(message)) => {
+                                consecutive_errors = 0;
+                                if !self.handle_message(&message).await {
+                                    // Committing a later message of the partition would
+                                    // skip this one for good, so read it again instead
+                                    if let Err(e) = consumer.seek(
+                                        message.topic(),
+                                        message.partition(),
+                                        Offset::Offset(message.offset()),
+                                        SEEK_TIMEOUT,
+                                    ) {
+                                        warn!("Failed to rewind to failed offset {}: {}", message.offset(), e);
+                                        break true;
+                                    }
+                                    tokio::time::sleep(FAILED_MESSAGE_RETRY_DELAY).await;
+                                } else if !kafka_config.enable_auto_commit {
+                                    if let Err(e) = consumer.commit_message(&message, CommitMode::Async) {
+                                        warn!("Failed to commit offset {}: {}", message.offset(), e);
+                                    }
+                                }
+                            }
+                            Some(Err(e)) => {
+                                error!("Kafka consumer error: {}", e);
+                                consecutive_errors += 1;
+                                if consecutive_errors >= MAX_CONSECUTIVE_CONSUMER_ERRORS {
+                                    break true;
+                                }
+                            }
+                            None => break true,
+                        },
+                    }
+                }
+            };
+            
+            if !reconnect {
+                break;
+            }
+            
+            warn!("Kafka consumer unhealthy, reconnecting");
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+                result = queue::init_kafka_consumer(&kafka_config) => match result {
+                    Ok(new_consumer) => consumer = new_consumer,
+                    Err(e) => {
+                        error!("Giving up on Kafka, no longer consuming tasks: {:?}", e);
+                        return;
+                    }
+                },
+            }
+        }
+        
+        info!("Stopped consuming task messages");
+        
+        if let Err(e) = consumer.commit_consumer_state(CommitMode::Sync) {
+            warn!("Failed to commit consumer offsets on shutdown: {}", e);
+        }
+        consumer.unsubscribe();
+    }
+    
+    /// Handle a [`TaskMessage`] naming a task to process.
+    ///
+    /// Returns whether the message's offset may be committed: true once the
+    /// task's transition is persisted, or for messages this engine can never
+    /// handle, malformed or from a newer schema version. False means the
+    /// message should be handled again.
+    async fn handle_message(&self, message: &BorrowedMessage<'_>) -> bool {
+        let task_id = match TaskMessage::decode(message.payload().unwrap_or_default()) {
+            Ok(DecodedTaskMessage::Task(task)) => task.task_id,
+            Ok(DecodedTaskMessage::UnsupportedVersion(version)) => {
+                // The task is left to the queued-task sweep
+                warn!(
+                    "Skipping task message at offset {} with schema version {}, newer than the supported {}",
+                    message.offset(),
+                    version,
+                    queue::TASK_MESSAGE_VERSION
+                );
+                return true;
+            }
+            Err(e) => {
+                warn!("Skipping malformed task message at offset {}: {:#}", message.offset(), e);
+                return true;
+            }
+        };
+        
+        match self.process_task(task_id).await {
+            Ok(()) => true,
+            Err(e) => {
+                error!("Failed to process task {}: {:?}", task_id, e);
+                false
+            }
+        }
+    }
+    
+    /// The switch that pauses and resumes this engine's processing
+    pub fn processing(&self) -> &ProcessingSwitch {
+        &self.processing
+    }
+    
+    /// Whether new work may be taken: processing is enabled and a slot is free
+    fn accepting_work(&self) -> bool {
+        self.processing.is_enabled() && self.capacity.available_permits() > 0
+    }
+    
+    /// Stop consuming, wait up to `grace_period` for active tasks to finish,
+    /// then requeue whatever is still running so another engine picks it up
+    pub async fn shutdown(&self, grace_period: Duration) -> Result<()> {
+        info!("Draining engine, grace period {:?}", grace_period);
+        
+        let _ = self.shutdown_tx.send(true);
+        if let Some(handle) = self.consumer_handle.lock().await.take() {
+            if let Err(e) = handle.await {
+                warn!("Consumer loop ended abnormally: {:?}", e);
+            }
+        }
+        
+        let deadline = tokio::time::Instant::now() + grace_period;
+        loop {
+            let remaining = self.active_tasks.lock().await.len();
+            if remaining == 0 {
+                info!("All active tasks drained");
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                warn!("{} tasks still active after grace period, requeueing", remaining);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        
+        let unfinished: Vec<Uuid> = self.active_tasks.lock().await.iter().copied().collect();
+        for task_id in unfinished {
+            if let Err(e) = self.requeue_task(task_id, ResetReason::GracefulShutdown).await {
+                error!("Failed to requeue task {} on shutdown: {:?}", task_id, e);
+                continue;
+            }
+            self.remove_active_task(task_id).await;
+        }
         
         Ok(())
     }
     
     /// Process a single task
+    #[instrument(skip(self), fields(task_id = %task_id, workflow_id = field::Empty))]
     async fn process_task(&self, task_id: Uuid) -> Result<()> {
-        // 1. Lock the task in the database
-        // 2. Update its state to RUNNING
-        // 3. Execute the task logic
-        // 4. Update the state based on the result
-        // 5. Store any task events
+        // Tasks stay QUEUED until every dependency has completed
+        let unmet = database::get_unmet_dependencies(&self.db_pool, task_id).await?;
+        if !unmet.is_empty() {
+            info!("Task {} is waiting on {} unfinished dependencies", task_id, unmet.len());
+            return Ok(());
+        }
+        
+        // Deferred tasks are held QUEUED; the queued-task sweep picks them up once due
+        if let Some(scheduled_at) = database::get_pending_schedule(&self.db_pool, task_id).await? {
+            info!("Task {} is scheduled for {}, holding it until then", task_id, scheduled_at);
+            return Ok(());
+        }
+        
+        // Left QUEUED when at capacity; the queued-task sweep picks it up once a slot frees
+        let Ok(permit) = self.capacity.try_acquire() else {
+            info!("Running {} tasks already, leaving task {} queued", self.max_concurrent_tasks, task_id);
+            return Ok(());
+        };
         
-        // This is a simplified implementation
         let mut tx = self.db_pool.begin().await?;
         
-        // Update task state to RUNNING
+        // A row locked by another engine is being claimed by it; skip rather than wait
+        let Some(task) = database::claim_queued_task(&mut tx, task_id, self.worker_labels.as_json()).await? else {
+            drop(tx);
+            // Left QUEUED for an engine whose workers can run it, which picks it up in its queued-task sweep
+            match database::mark_unmatched_requirements(&self.db_pool, task_id, self.worker_labels.as_json()).await? {
+                Some(requirements) => info!(
+                    "Task {} requires {}, which this engine's workers don't offer; leaving it queued",
+                    task_id, requirements
+                ),
+                None => info!(
+                    "Task {} is no longer queued, was claimed by another engine, or its workflow is paused",
+                    task_id
+                ),
+            }
+            return Ok(());
+        };
+        Span::current().record("workflow_id", field::display(task.workflow_id));
+        
+        let (started, events) = self.start_claimed(&mut tx, task).await?;
+        
+        tx.commit().await?;
+        self.events.publish(events).await;
+        
+        self.after_start(started, permit).await
+    }
+    
+    /// Move a task claimed in `conn`'s transaction to RUNNING, or straight to
+    /// FAILED if its parameters are malformed, returning the events to publish
+    /// once the transaction commits
+    async fn start_claimed(&self, conn: &mut PgConnection, task: Task) -> Result<(Started, Vec<TaskEvent>)> {
+        // A task that fanned out already ran; its parameters were checked then
+        let joining = database::is_fanned_out(conn, task.id).await?;
+        
+        // Resolved parameters replace the references, so the worker and any
+        // retries see the same input
+        let resolved = if joining {
+            Ok(None)
+        } else {
+            self.resolve_references(conn, &task).await?
+        };
+        
+        // The row is locked by the claim, so no state guard is needed
         let task = sqlx::query_as!(
             Task,
-            "UPDATE tasks SET state = $1, updated_at = NOW(), started_at = NOW() 
-             WHERE id = $2 AND state = $3
-             RETURNING *",
-            TaskState::Running.to_string(),
-            task_id,
-            TaskState::Queued.to_string()
+            r#"UPDATE tasks SET state = $1, parameters = COALESCE($3, parameters), 
+             updated_at = NOW(), version = version + 1, started_at = NOW(), last_heartbeat_at = NULL, 
+             waiting_reason = NULL 
+             WHERE id = $2
+             RETURNING id, workflow_id, name, state AS "state: TaskState", retry_count, max_retries, 
+             created_at, updated_at, started_at, completed_at, timeout_seconds, 
+             parameters, result, error, priority, task_type, version, tags, 
+             payload_format AS "payload_format: PayloadFormat", payload, tenant_id, created_by, 
+             requirements, waiting_reason, result_checksum"#,
+            TaskState::Running as TaskState,
+            task.id,
+            resolved.as_ref().ok().cloned().flatten()
         )
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut *conn)
         .await
         .context("Failed to update task state to RUNNING")?;
         
+        // Malformed parameters and unresolvable references fail straight away
+        // without using a retry, since running them again can't succeed
+        let problem = match resolved {
+            Err(reason) => Some(reason),
+            Ok(_) if joining => None,
+            Ok(_) => self.check_parameters(&task).err(),
+        };
+        if let Some(reason) = problem {
+            sqlx::query!(
+                "UPDATE tasks SET state = $1, error = $2, updated_at = NOW(), version = version + 1, completed_at = NOW() 
+                 WHERE id = $3",
+                TaskState::Failed as TaskState,
+                reason,
+                task.id
+            )
+            .execute(&mut *conn)
+            .await
+            .context("Failed to update invalid task")?;
+            
+            let event = database::record_event(
+                conn,
+                task.id,
+                task.workflow_id,
+                "VALIDATION_FAILED",
+                Some(TaskState::Queued),
+                TaskState::Failed,
+                Some(serde_json::json!({ "error": reason })),
+            )
+            .await?;
+            
+            return Ok((Started::Invalid { task_id: task.id, reason }, vec![event]));
+        }
+        
+        // Collecting subtask results isn't another attempt at the task
+        if joining {
+            let event = database::record_event(
+                conn,
+                task.id,
+                task.workflow_id,
+                "STATE_CHANGE",
+                Some(TaskState::Queued),
+                TaskState::Running,
+                Some(serde_json::json!({
+                    "worker_id": self.worker_id,
+                    "joining_subtasks": true,
+                })),
+            )
+            .await?;
+            
+            return Ok((Started::Joining(task), vec![event]));
+        }
+        
+        database::start_attempt(conn, task.id, &self.worker_id).await?;
+        
         // Record the state change event
-        let event_id = Uuid::new_v4();
-        sqlx::query!(
-            "INSERT INTO task_events (id, task_id, workflow_id, event_type, previous_state, new_state, timestamp)
-             VALUES ($1, $2, $3, $4, $5, $6, NOW())",
-            event_id,
+        let event = database::record_event(
+            conn,
             task.id,
             task.workflow_id,
             "STATE_CHANGE",
-            Some(TaskState::Queued.to_string()),
-            TaskState::Running.to_string()
+            Some(TaskState::Queued),
+            TaskState::Running,
+            Some(serde_json::json!({
+                "worker_id": self.worker_id,
+                "attempt": task.retry_count + 1,
+            })),
         )
-        .execute(&mut *tx)
-        .await
-        .context("Failed to record task event")?;
+        .await?;
         
-        tx.commit().await?;
+        Ok((Started::Running(task), vec![event]))
+    }
+    
+    /// Finish starting a task once the transaction that claimed it committed;
+    /// `permit` is its capacity slot, kept while it runs
+    async fn after_start(&self, started: Started, permit: SemaphorePermit<'_>) -> Result<()> {
+        let (task, joining) = match started {
+            Started::Running(task) => (task, false),
+            Started::Joining(task) => (task, true),
+            Started::Invalid { task_id, reason } => {
+                warn!("Task {} failed validation: {}", task_id, reason);
+                metrics::TASKS_FAILED.inc();
+                return self.cancel_dependents(task_id).await;
+            }
+        };
         
-        // Add to active tasks
+        // Add to active tasks; the slot is returned by remove_active_task
         {
             let mut active_tasks = self.active_tasks.lock().await;
-            active_tasks.push(task_id);
+            if active_tasks.insert(task.id) {
+                permit.forget();
+            }
+            metrics::ACTIVE_TASKS.set(active_tasks.len() as i64);
+        }
+        
+        // Neither joins nor collectors reach a worker; what they wait on has all completed by now
+        if joining {
+            return self.join_subtasks(&task).await;
+        }
+        if task.task_type == COLLECTOR_TASK_TYPE {
+            return self.run_collector(&task).await;
         }
         
         // In a real implementation, this would communicate with the worker
@@ -95,32 +640,598 @@ impl TaskEngine {
         Ok(())
     }
     
-    /// Reconciliation loop to find and fix "stuck" tasks
-    async fn run_reconciliation_loop(
-        db_pool: PgPool,
-        active_tasks: Arc<Mutex<Vec<Uuid>>>
-    ) -> Result<()> {
-        let interval = tokio::time::Duration::from_secs(60); // Run every minute
+    /// Complete a collector task with its dependencies' results merged
+    async fn run_collector(&self, task: &Task) -> Result<()> {
+        // Already checked by check_parameters
+        let mode = MergeMode::from_parameters(&task.parameters).map_err(anyhow::Error::msg)?;
+        let upstream = database::get_dependency_results(&self.db_pool, task).await?;
+        
+        info!("Collecting results of {} tasks into task {}", upstream.len(), task.id);
+        let result = match mode {
+            MergeMode::Array => serde_json::Value::Array(
+                upstream.into_iter().map(|(_, result)| result.unwrap_or_default()).collect(),
+            ),
+            MergeMode::Object => serde_json::Value::Object(
+                upstream
+                    .into_iter()
+                    .map(|(name, result)| (name, result.unwrap_or_default()))
+                    .collect(),
+            ),
+        };
+        
+        // Boxed because completing a task processes its dependents, which may be collectors
+        Box::pin(self.complete_task(task.id, result)).await
+    }
+    
+    /// Complete a task that fanned out with its subtasks' results, as a JSON
+    /// array in the order it listed them
+    async fn join_subtasks(&self, task: &Task) -> Result<()> {
+        let results = database::get_fan_out_results(&self.db_pool, task.id).await?;
+        
+        info!("Joining the results of {} subtasks into task {}", results.len(), task.id);
+        let result = serde_json::Value::Array(results.into_iter().map(Option::unwrap_or_default).collect());
+        
+        // Boxed because completing a task processes its dependents, which may be joins
+        Box::pin(self.complete_task(task.id, result)).await
+    }
+    
+    /// The task's parameters with `$ref` values replaced from its dependencies'
+    /// results, `None` if it has no references, or why they can't be resolved
+    async fn resolve_references(&self, conn: &mut PgConnection, task: &Task) -> Result<Result<Option<serde_json::Value>, String>> {
+        // Binary and offloaded parameters can't be inspected
+        if task.payload_format != PayloadFormat::Json || task.parameters.get(BLOB_REF_KEY).is_some() {
+            return Ok(Ok(None));
+        }
+        match references::find(&task.parameters) {
+            Ok(found) if found.is_empty() => return Ok(Ok(None)),
+            Ok(_) => {}
+            Err(e) => return Ok(Err(e.to_string())),
+        }
+        
+        let outputs = database::get_dependency_outputs(conn, task).await?;
+        Ok(references::resolve(&task.parameters, &outputs).map(Some).map_err(|e| e.to_string()))
+    }
+    
+    /// Why a task can't run, if its parameters aren't a JSON object or don't
+    /// match the schema registered for its task type
+    fn check_parameters(&self, task: &Task) -> Result<(), String> {
+        // Binary payloads are opaque to the engine; StartTask only accepts
+        // them for task types without a schema
+        if task.payload_format != PayloadFormat::Json {
+            return Ok(());
+        }
+        // Offloaded parameters were validated before they were uploaded
+        if task.parameters.get(BLOB_REF_KEY).is_some() {
+            return Ok(());
+        }
+        if !task.parameters.is_object() {
+            return Err(format!("parameters must be a JSON object, got: {}", task.parameters));
+        }
+        if task.task_type == COLLECTOR_TASK_TYPE {
+            MergeMode::from_parameters(&task.parameters)?;
+        }
+        
+        self.schemas.validate(&task.task_type, &task.parameters).map_err(|violations| {
+            let details: Vec<String> = violations
+                .iter()
+                .map(|violation| format!("parameters{}: {}", violation.path, violation.message))
+                .collect();
+            format!(
+                "parameters do not match the schema for task type {}: {}",
+                task.task_type,
+                details.join("; ")
+            )
+        })
+    }
+    
+    /// Mark a running task as completed and process dependents it was blocking.
+    ///
+    /// A result of the form `{"$fanout": [...]}` instead creates the listed
+    /// subtasks; the task completes once they have, with their results.
+    #[instrument(skip(self, result), fields(task_id = %task_id, workflow_id = field::Empty))]
+    pub async fn complete_task(&self, task_id: Uuid, result: serde_json::Value) -> Result<()> {
+        if fan_out::is_fan_out(&result) {
+            return self.fan_out(task_id, result).await;
+        }
+        
+        // Upload oversized results before taking row locks
+        let checksum = blob::checksum(&result);
+        let result = self
+            .payloads
+            .offload(&format!("tasks/{}/result", task_id), result)
+            .await
+            .context("Failed to store task result")?;
+        
+        let mut tx = self.db_pool.begin().await?;
+        
+        let task = sqlx::query!(
+            "UPDATE tasks SET state = $1, result = $2, result_checksum = $5, updated_at = NOW(), version = version + 1, 
+             completed_at = NOW() 
+             WHERE id = $3 AND state = $4
+             RETURNING workflow_id, retry_count, started_at, completed_at",
+            TaskState::Completed as TaskState,
+            result,
+            task_id,
+            TaskState::Running as TaskState,
+            checksum
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to update task state to COMPLETED")?;
+        Span::current().record("workflow_id", field::display(task.workflow_id));
+        
+        database::finish_attempt(&mut tx, task_id, TaskState::Completed, None).await?;
+        
+        let event = database::record_event(
+            &mut tx,
+            task_id,
+            task.workflow_id,
+            "STATE_CHANGE",
+            Some(TaskState::Running),
+            TaskState::Completed,
+            Some(serde_json::json!({
+                "worker_id": self.worker_id,
+                "attempt": task.retry_count + 1,
+            })),
+        )
+        .await?;
+        
+        tx.commit().await?;
+        self.events.publish([event]).await;
+        
+        metrics::TASKS_PROCESSED.inc();
+        metrics::observe_task_duration(task.started_at, task.completed_at);
+        self.remove_active_task(task_id).await;
+        
+        for dependent_id in database::get_ready_dependents(&self.db_pool, task_id).await? {
+            if let Err(e) = self.process_task(dependent_id).await {
+                error!("Failed to process dependent task {}: {:?}", dependent_id, e);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Create the subtasks a running task's result asks for and requeue the
+    /// task behind them. A malformed list fails the attempt like any other error.
+    async fn fan_out(&self, task_id: Uuid, result: serde_json::Value) -> Result<()> {
+        let mut tx = self.db_pool.begin().await?;
+        
+        let parent = sqlx::query_as!(
+            Task,
+            r#"SELECT id, workflow_id, name, state AS "state: TaskState", retry_count, max_retries, 
+             created_at, updated_at, started_at, completed_at, timeout_seconds, 
+             parameters, result, error, priority, task_type, version, tags, 
+             payload_format AS "payload_format: PayloadFormat", payload, tenant_id, created_by, 
+             requirements, waiting_reason, result_checksum 
+             FROM tasks WHERE id = $1 AND state = $2 
+             FOR UPDATE"#,
+            task_id,
+            TaskState::Running as TaskState
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to load running task")?;
+        Span::current().record("workflow_id", field::display(parent.workflow_id));
+        
+        let children = match fan_out::children(&result, &parent, &self.timeouts) {
+            Ok(children) if !children.is_empty() => children,
+            // Nothing to wait for, so the task is done
+            Ok(_) => {
+                drop(tx);
+                return Box::pin(self.complete_task(task_id, serde_json::Value::Array(Vec::new()))).await;
+            }
+            Err(reason) => {
+                drop(tx);
+                return self.fail_task(task_id, &format!("Invalid fan-out: {}", reason), true).await.map(drop);
+            }
+        };
+        
+        let fanned_out = database::fan_out(&mut tx, &parent, children, &self.worker_id).await?;
+        tx.commit().await?;
+        self.events.publish([fanned_out.event]).await;
+        
+        info!("Task {} fanned out to {} subtasks", task_id, fanned_out.child_ids.len());
+        self.remove_active_task(task_id).await;
+        
+        for child_id in fanned_out.child_ids {
+            if let Err(e) = Box::pin(self.process_task(child_id)).await {
+                error!("Failed to process subtask {}: {:?}", child_id, e);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Record a task failure, requeueing it if `retry` allows and it has
+    /// retries left, and return whether it was requeued. A task that won't be
+    /// retried has its dependents cancelled.
+    #[instrument(skip(self, error_message), fields(task_id = %task_id, workflow_id = field::Empty))]
+    pub async fn fail_task(&self, task_id: Uuid, error_message: &str, retry: bool) -> Result<bool> {
+        self.record_failed_attempt(task_id, error_message, retry, TaskState::Failed).await
+    }
+    
+    /// Record that a worker stopped a task for exceeding its timeout, as soon as
+    /// it happens rather than when reconciliation notices. Retries like any
+    /// other failure and ends TIMED_OUT once it won't be retried.
+    #[instrument(skip(self, error_message), fields(task_id = %task_id, workflow_id = field::Empty))]
+    pub async fn time_out_task(&self, task_id: Uuid, error_message: &str, retry: bool) -> Result<bool> {
+        self.record_failed_attempt(task_id, error_message, retry, TaskState::TimedOut).await
+    }
+    
+    /// Requeue a failed attempt, or move the task to `exhausted_state` and
+    /// cancel its dependents if it won't be retried
+    async fn record_failed_attempt(
+        &self,
+        task_id: Uuid,
+        error_message: &str,
+        retry: bool,
+        exhausted_state: TaskState,
+    ) -> Result<bool> {
+        let mut tx = self.db_pool.begin().await?;
+        
+        let task = sqlx::query!(
+            "SELECT workflow_id, retry_count, max_retries, started_at, retry_backoff FROM tasks 
+             WHERE id = $1 AND state = $2 
+             FOR UPDATE",
+            task_id,
+            TaskState::Running as TaskState
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to load running task")?;
+        Span::current().record("workflow_id", field::display(task.workflow_id));
+        
+        let will_retry = retry && task.retry_count < task.max_retries;
+        let new_state = if will_retry { TaskState::Queued } else { exhausted_state };
+        
+        // Held back by the task's backoff, and by the retry budget when many
+        // tasks are failing at once; the queued-task sweep picks the retry up
+        // once it is due
+        let (backoff_delay, budget_delay) = if will_retry {
+            let backoff = self.backoff_for(task_id, task.retry_backoff);
+            let retry = u32::try_from(task.retry_count + 1).unwrap_or(1);
+            (backoff.delay(retry), self.retry_budget.acquire())
+        } else {
+            (Duration::ZERO, Duration::ZERO)
+        };
+        let retry_at = match backoff_delay.max(budget_delay) {
+            delay if delay.is_zero() => None,
+            delay => chrono::Duration::from_std(delay).ok().map(|delay| chrono::Utc::now() + delay),
+        };
+        
+        sqlx::query!(
+            "UPDATE tasks SET state = $1, error = $2, 
+             retry_count = retry_count + $3, updated_at = NOW(), version = version + 1, 
+             completed_at = CASE WHEN $4 THEN NULL ELSE NOW() END, 
+             scheduled_at = COALESCE($5, scheduled_at) 
+             WHERE id = $6",
+            new_state as TaskState,
+            error_message,
+            if will_retry { 1 } else { 0 },
+            will_retry,
+            retry_at,
+            task_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context("Failed to update failed task")?;
+        
+        database::finish_attempt(&mut tx, task_id, new_state, Some(error_message)).await?;
+        
+        let event = database::record_event(
+            &mut tx,
+            task_id,
+            task.workflow_id,
+            if will_retry { "RETRY" } else { "STATE_CHANGE" },
+            Some(TaskState::Running),
+            new_state,
+            Some(serde_json::json!({
+                "worker_id": self.worker_id,
+                "attempt": task.retry_count + 1,
+                "max_retries": task.max_retries,
+                "error": error_message,
+                "retry_at": retry_at,
+            })),
+        )
+        .await?;
+        
+        tx.commit().await?;
+        self.events.publish([event]).await;
+        
+        if will_retry {
+            metrics::TASKS_RETRIED.inc();
+            if let Some(retry_at) = retry_at.filter(|_| budget_delay > backoff_delay) {
+                metrics::RETRIES_DELAYED.inc();
+                info!("Retry budget exhausted, holding retry of task {} until {}", task_id, retry_at);
+            }
+        } else {
+            metrics::TASKS_FAILED.inc();
+            metrics::observe_task_duration(task.started_at, Some(chrono::Utc::now()));
+        }
+        self.remove_active_task(task_id).await;
+        
+        if !will_retry {
+            self.cancel_dependents(task_id).await?;
+        }
+        
+        Ok(will_retry)
+    }
+    
+    /// The backoff a task was created with, or the engine's default
+    fn backoff_for(&self, task_id: Uuid, retry_backoff: Option<serde_json::Value>) -> BackoffStrategy {
+        let Some(retry_backoff) = retry_backoff else {
+            return self.retry_backoff;
+        };
+        serde_json::from_value(retry_backoff).unwrap_or_else(|e| {
+            warn!("Task {} has an unreadable retry_backoff, using the default: {}", task_id, e);
+            self.retry_backoff
+        })
+    }
+    
+    /// Stop tracking a task that has left RUNNING and free its slot; a no-op
+    /// for tasks this engine isn't running
+    pub async fn remove_active_task(&self, task_id: Uuid) {
+        let mut active_tasks = self.active_tasks.lock().await;
+        if active_tasks.remove(&task_id) {
+            self.capacity.add_permits(1);
+        }
+        metrics::ACTIVE_TASKS.set(active_tasks.len() as i64);
+    }
+    
+    /// Cancel every queued task that transitively depends on `blocking_task_id`
+    async fn cancel_dependents(&self, blocking_task_id: Uuid) -> Result<()> {
+        let mut tx = self.db_pool.begin().await?;
+        let events = database::cancel_dependents(&mut tx, blocking_task_id, "DEPENDENCY_FAILED").await?;
+        tx.commit().await?;
+        
+        for event in &events {
+            warn!("Cancelled task {} blocked by failed task {}", event.task_id, blocking_task_id);
+        }
+        self.events.publish(events).await;
+        
+        Ok(())
+    }
+    
+    /// Reconciliation loop to find and fix "stuck" tasks and pick up queued work.
+    ///
+    /// Runs for the life of the engine; a pass that fails is logged and the
+    /// work is retried on the next one.
+    async fn run_reconciliation_loop(&self) {
+        let ReconciliationConfig { interval, stuck_threshold, heartbeat_timeout } = self.reconciliation;
+        info!(
+            "Reconciling every {:?}, tasks are considered stuck after {:?} without a heartbeat \
+             or, if they never heartbeat, after RUNNING past their timeout or {:?} without one",
+            interval, heartbeat_timeout, stuck_threshold
+        );
         
         loop {
             tokio::time::sleep(interval).await;
             
-            // Find tasks that have been in RUNNING state for too long
-            let stuck_tasks = sqlx::query!(
-                "SELECT id FROM tasks 
-                 WHERE state = $1 
-                 AND started_at < NOW() - INTERVAL '1 hour'",
-                TaskState::Running.to_string()
-            )
-            .fetch_all(&db_pool)
-            .await?;
+            // A task whose worker heartbeats is only stuck once the heartbeats
+            // stop; long-running but live tasks are left alone
+            let stuck_tasks = match database::get_stuck_tasks(&self.db_pool, stuck_threshold, heartbeat_timeout).await {
+                Ok(stuck_tasks) => stuck_tasks,
+                Err(e) => {
+                    error!("Failed to find stuck tasks, retrying next pass: {:?}", e);
+                    Vec::new()
+                }
+            };
             
             for task in stuck_tasks {
-                warn!("Found stuck task: {}", task.id);
+                let span = info_span!("reconcile_stuck_task", task_id = %task.id, workflow_id = %task.workflow_id);
                 
-                // In a real implementation, this would reset the task
-                // and potentially retry it
+                async {
+                    warn!("Found stuck task: {}", task.id);
+                    
+                    if let Err(e) = self.requeue_task(task.id, ResetReason::StuckTask).await {
+                        error!("Failed to requeue stuck task {}: {:?}", task.id, e);
+                        return;
+                    }
+                    
+                    metrics::TASKS_TIMED_OUT.inc();
+                    self.remove_active_task(task.id).await;
+                }
+                .instrument(span)
+                .await;
+            }
+            
+            if self.processing.is_enabled() {
+                if let Err(e) = self.process_queued_tasks().await {
+                    error!("Failed to process queued tasks, retrying next pass: {:?}", e);
+                }
             }
         }
     }
+    
+    /// Claim and start QUEUED tasks highest priority first.
+    ///
+    /// Kafka only preserves order within a partition and knows nothing about
+    /// priority, so priority is applied here when the engine pulls queued work
+    /// from the database rather than by reordering the topic. A high-priority
+    /// task that arrives via Kafka behind lower-priority messages is still
+    /// picked up on the next pass.
+    ///
+    /// The batch is claimed with `FOR UPDATE SKIP LOCKED`, so engines sweeping
+    /// at the same time each get different tasks instead of queueing up on the
+    /// same rows. If any task in the batch fails to start, the whole batch is
+    /// left QUEUED for the next pass.
+    async fn process_queued_tasks(&self) -> Result<()> {
+        // Reserve capacity first so every claimed task can start
+        let mut permits: Vec<_> = std::iter::from_fn(|| self.capacity.try_acquire().ok())
+            .take(QUEUED_BATCH_SIZE as usize)
+            .collect();
+        if permits.is_empty() {
+            return Ok(());
+        }
+        
+        let mut tx = self.db_pool.begin().await?;
+        let claimed = database::claim_queued_tasks(&mut tx, permits.len() as i64, self.worker_labels.as_json()).await?;
+        // Unused slots go back to the semaphore
+        permits.truncate(claimed.len());
+        
+        let mut started = Vec::with_capacity(claimed.len());
+        let mut events = Vec::new();
+        for task in claimed {
+            let (outcome, task_events) = self.start_claimed(&mut tx, task).await?;
+            started.push(outcome);
+            events.extend(task_events);
+        }
+        
+        tx.commit().await?;
+        self.events.publish(events).await;
+        
+        for (outcome, permit) in started.into_iter().zip(permits) {
+            if let Err(e) = self.after_start(outcome, permit).await {
+                warn!("Failed to process queued task: {:?}", e);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Move a RUNNING task back to QUEUED, recording why the engine did it
+    #[instrument(skip(self), fields(task_id = %task_id, reason = %reason))]
+    async fn requeue_task(&self, task_id: Uuid, reason: ResetReason) -> Result<()> {
+        let mut tx = self.db_pool.begin().await?;
+        
+        let task = sqlx::query!(
+            "UPDATE tasks SET state = $1, updated_at = NOW(), version = version + 1, started_at = NULL, last_heartbeat_at = NULL 
+             WHERE id = $2 AND state = $3
+             RETURNING workflow_id",
+            TaskState::Queued as TaskState,
+            task_id,
+            TaskState::Running as TaskState
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to reset task state to QUEUED")?;
+        
+        // The task already left RUNNING, nothing to requeue
+        let Some(task) = task else {
+            return Ok(());
+        };
+        
+        database::finish_attempt(&mut tx, task_id, TaskState::Queued, Some(&format!("Requeued: {}", reason))).await?;
+        
+        let event = database::record_event(
+            &mut tx,
+            task_id,
+            task.workflow_id,
+            "REQUEUED",
+            Some(TaskState::Running),
+            TaskState::Queued,
+            Some(reason.to_metadata()),
+        )
+        .await?;
+        
+        tx.commit().await?;
+        self.events.publish([event]).await;
+        
+        info!("Requeued task {} ({})", task_id, reason);
+        
+        Ok(())
+    }
+}
+
+/// Pause or resume every partition currently assigned to the consumer
+fn set_partitions_paused(consumer: &LoggingConsumer, paused: bool) -> rdkafka::error::KafkaResult<()> {
+    let assignment = consumer.assignment()?;
+    if paused {
+        consumer.pause(&assignment)
+    } else {
+        consumer.resume(&assignment)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::database::tests::{insert_workflow, new_task};
+    use crate::events::tests::unreachable_producer;
+
+    /// Claim and start a QUEUED task on `engine`, as the consumer loop would
+    pub(crate) async fn run(engine: &TaskEngine, task_id: Uuid) {
+        engine.process_task(task_id).await.unwrap();
+    }
+
+    /// How many more tasks `engine` may start
+    pub(crate) fn free_slots(engine: &TaskEngine) -> usize {
+        engine.capacity.available_permits()
+    }
+
+    /// An engine on `pool` whose events are left to the outbox
+    pub(crate) fn engine(pool: PgPool) -> TaskEngine {
+        let config = EngineConfig {
+            reconciliation: ReconciliationConfig::from_env().unwrap(),
+            worker_labels: WorkerLabels::default(),
+            retry_budget: RetryBudget::from_env().unwrap(),
+            retry_backoff: BackoffStrategy::default(),
+            max_concurrent_tasks: 10,
+        };
+        TaskEngine::new(
+            pool.clone(),
+            PayloadStore::from_env().unwrap(),
+            Arc::new(SchemaRegistry::new()),
+            ProcessingSwitch::new(),
+            EventPublisher::new(pool, unreachable_producer(), "chronos-events"),
+            TimeoutRegistry::default(),
+            config,
+        )
+    }
+
+    /// Tasks of the workflow in the order they started running
+    async fn start_order(pool: &PgPool, workflow_id: Uuid) -> Vec<Uuid> {
+        sqlx::query_scalar(
+            "SELECT task_id FROM task_events WHERE workflow_id = $1 AND new_state = 'RUNNING' ORDER BY timestamp",
+        )
+        .bind(workflow_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn reconciliation_outlives_database_errors(pool: PgPool) {
+        let mut engine = engine(pool.clone());
+        engine.reconciliation.interval = Duration::from_millis(10);
+        pool.close().await;
+
+        let reconciliation = tokio::spawn(async move { engine.run_reconciliation_loop().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!reconciliation.is_finished());
+        reconciliation.abort();
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn tasks_of_a_workflow_start_in_message_order(pool: PgPool) {
+        let engine = engine(pool.clone());
+        let tenant_id = Uuid::new_v4();
+        let workflow_id = insert_workflow(&pool, tenant_id).await;
+        let mut task_ids = Vec::new();
+        for name in ["extract", "transform", "load"] {
+            let (task, _) = database::create_task(&pool, &new_task(workflow_id, tenant_id, name))
+                .await
+                .unwrap();
+            task_ids.push(task.id);
+        }
+
+        // Published in a different order than created. Messages of one workflow
+        // share a partition, which the consumer loop handles one at a time.
+        task_ids.swap(0, 1);
+        let messages: Vec<Vec<u8>> = task_ids
+            .iter()
+            .map(|&task_id| serde_json::to_vec(&TaskMessage::new(task_id, workflow_id)).unwrap())
+            .collect();
+        for payload in &messages {
+            let Ok(DecodedTaskMessage::Task(message)) = TaskMessage::decode(payload) else {
+                panic!("undecodable task message");
+            };
+            engine.process_task(message.task_id).await.unwrap();
+        }
+
+        assert_eq!(start_order(&pool, workflow_id).await, task_ids);
+    }
 }
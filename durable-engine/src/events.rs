@@ -0,0 +1,240 @@
+use crate::database;
+use crate::metrics;
+use crate::models::TaskEvent;
+use crate::queue;
+use anyhow::Result;
+use rdkafka::producer::FutureProducer;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// How long an outbox event is left for the publisher that recorded it before
+/// the relay takes over
+const RELAY_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// How long published outbox rows are kept before being deleted
+const OUTBOX_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Timing of the outbox relay
+#[derive(Debug, Clone, Copy)]
+pub struct RelayConfig {
+    pub interval: Duration,
+    pub batch_size: i64,
+}
+
+impl RelayConfig {
+    /// Read from `OUTBOX_RELAY_INTERVAL_MS` (default 1000) and
+    /// `OUTBOX_RELAY_BATCH_SIZE` (default 100); both must be positive
+    pub fn from_env() -> Result<Self> {
+        let interval_ms: u64 = database::env_or("OUTBOX_RELAY_INTERVAL_MS", 1000)?;
+        let batch_size: i64 = database::env_or("OUTBOX_RELAY_BATCH_SIZE", 100)?;
+
+        if interval_ms == 0 {
+            anyhow::bail!("OUTBOX_RELAY_INTERVAL_MS must be positive");
+        }
+        if batch_size <= 0 {
+            anyhow::bail!("OUTBOX_RELAY_BATCH_SIZE must be positive");
+        }
+
+        Ok(Self {
+            interval: Duration::from_millis(interval_ms),
+            batch_size,
+        })
+    }
+}
+
+/// Publishes task events to the events topic so other services can react to
+/// task lifecycle changes without polling the database.
+///
+/// Delivery is at least once. `database::record_event` queues every event in
+/// the outbox within the state change's transaction; `publish` sends events
+/// right after that commits and marks them published, and the relay sends any
+/// that are still unpublished after a short grace period, e.g. because the
+/// publish failed or the process died first. An event can therefore be
+/// delivered twice, and a relayed event may arrive after later events of the
+/// same workflow, so subscribers should deduplicate by event id.
+#[derive(Clone)]
+pub struct EventPublisher {
+    db_pool: PgPool,
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl EventPublisher {
+    pub fn new(db_pool: PgPool, producer: FutureProducer, topic: impl Into<String>) -> Self {
+        Self {
+            db_pool,
+            producer,
+            topic: topic.into(),
+        }
+    }
+
+    /// Publish events whose transaction has committed, in order.
+    ///
+    /// Stops at the first failure and leaves that event and the rest to the relay.
+    pub async fn publish(&self, events: impl IntoIterator<Item = TaskEvent>) {
+        let mut published = Vec::new();
+        for event in events {
+            if let Err(e) = queue::publish_event(&self.producer, &self.topic, &event).await {
+                warn!(
+                    "Failed to publish {} event {} for task {}, leaving it to the outbox relay: {:?}",
+                    event.event_type, event.id, event.task_id, e
+                );
+                metrics::EVENTS_PUBLISH_FAILED.inc();
+                break;
+            }
+            published.push(event.id);
+        }
+
+        if published.is_empty() {
+            return;
+        }
+        // If this fails the relay publishes the events again, which at-least-once allows
+        if let Err(e) = self.mark_published(&published).await {
+            warn!("Failed to mark {} events published: {:?}", published.len(), e);
+        }
+    }
+
+    /// Publish outbox events nobody else has, every `config.interval`, until the process exits
+    pub async fn run_relay(&self, config: RelayConfig) {
+        info!(
+            "Relaying unpublished task events every {:?}, up to {} at a time",
+            config.interval, config.batch_size
+        );
+
+        loop {
+            tokio::time::sleep(config.interval).await;
+
+            match self.relay_batch(config.batch_size).await {
+                Ok(0) => {}
+                Ok(relayed) => info!("Relayed {} unpublished task events", relayed),
+                Err(e) => warn!("Outbox relay pass failed: {:?}", e),
+            }
+        }
+    }
+
+    /// Publish one batch of overdue outbox events, holding their row locks so
+    /// other engines' relays skip them. Returns how many were published.
+    async fn relay_batch(&self, batch_size: i64) -> Result<usize> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let events = database::claim_unpublished_events(&mut tx, RELAY_GRACE_PERIOD, batch_size).await?;
+        let mut published = Vec::with_capacity(events.len());
+        for event in &events {
+            // Stop at the first failure so later events aren't published ahead of it
+            if let Err(e) = queue::publish_event(&self.producer, &self.topic, event).await {
+                warn!("Failed to relay event {} for task {}: {:?}", event.id, event.task_id, e);
+                metrics::EVENTS_PUBLISH_FAILED.inc();
+                break;
+            }
+            published.push(event.id);
+        }
+
+        database::mark_events_published(&mut tx, &published).await?;
+        let pruned = database::prune_published_events(&mut tx, OUTBOX_RETENTION).await?;
+        if pruned > 0 {
+            debug!("Pruned {} published outbox rows", pruned);
+        }
+
+        tx.commit().await?;
+
+        Ok(published.len())
+    }
+
+    async fn mark_published(&self, event_ids: &[uuid::Uuid]) -> Result<()> {
+        let mut conn = self.db_pool.acquire().await?;
+        database::mark_events_published(&mut conn, event_ids).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::database::tests::{insert_workflow, new_task};
+    use crate::models::TaskState;
+
+    /// A producer whose messages quickly fail to send
+    pub(crate) fn unreachable_producer() -> FutureProducer {
+        rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", "localhost:1")
+            .set("message.timeout.ms", "10")
+            .create()
+            .unwrap()
+    }
+
+    /// Start a task and record its event in one transaction, as the engine does
+    async fn start_task(pool: &PgPool, commit: bool) -> TaskEvent {
+        let tenant_id = uuid::Uuid::new_v4();
+        let workflow_id = insert_workflow(pool, tenant_id).await;
+        let (task, _) = database::create_task(pool, &new_task(workflow_id, tenant_id, "fetch"))
+            .await
+            .unwrap();
+
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("UPDATE tasks SET state = 'RUNNING' WHERE id = $1")
+            .bind(task.id)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let event = database::record_event(
+            &mut tx,
+            task.id,
+            workflow_id,
+            "STARTED",
+            Some(TaskState::Queued),
+            TaskState::Running,
+            None,
+        )
+        .await
+        .unwrap();
+        if commit {
+            tx.commit().await.unwrap();
+        } else {
+            tx.rollback().await.unwrap();
+        }
+        event
+    }
+
+    /// Ids of events the relay would send now
+    async fn unpublished(pool: &PgPool) -> Vec<uuid::Uuid> {
+        let mut tx = pool.begin().await.unwrap();
+        let events = database::claim_unpublished_events(&mut tx, Duration::ZERO, 100).await.unwrap();
+        events.into_iter().map(|event| event.id).collect()
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn events_committed_but_never_published_are_relayed(pool: PgPool) {
+        // The process dies after the commit, before publishing the event
+        let event = start_task(&pool, true).await;
+        assert_eq!(unpublished(&pool).await, vec![event.id]);
+
+        // A relay that can't reach Kafka, once the grace period is over,
+        // leaves the event for the next pass
+        sqlx::query("UPDATE event_outbox SET created_at = NOW() - INTERVAL '1 minute'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let publisher = EventPublisher::new(pool.clone(), unreachable_producer(), "chronos-events");
+        assert_eq!(publisher.relay_batch(100).await.unwrap(), 0);
+        assert_eq!(unpublished(&pool).await, vec![event.id]);
+
+        // Once sent, by the relay or the original publisher, it isn't sent again
+        publisher.mark_published(&[event.id]).await.unwrap();
+        assert!(unpublished(&pool).await.is_empty());
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn rolled_back_state_changes_leave_no_event(pool: PgPool) {
+        let event = start_task(&pool, false).await;
+        assert!(unpublished(&pool).await.is_empty());
+
+        let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task_events WHERE id = $1")
+            .bind(event.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(recorded, 0);
+    }
+}
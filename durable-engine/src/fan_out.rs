@@ -0,0 +1,124 @@
+use crate::backoff::BackoffStrategy;
+use crate::database::NewTask;
+use crate::models::{PayloadFormat, Task};
+use crate::timeouts::TimeoutRegistry;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Key of a task result that asks for subtasks instead of completing, e.g.
+/// `{"$fanout": [{"task_type": "resize", "parameters": {"file": "a.png"}}]}`
+pub const FAN_OUT_KEY: &str = "$fanout";
+
+/// Most subtasks a single result may ask for
+pub const MAX_FAN_OUT: usize = 1000;
+
+/// A subtask as listed in a fan-out result
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChildSpec {
+    /// Defaults to `<parent name>[<index>]`
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    task_type: String,
+    #[serde(default = "empty_object")]
+    parameters: Value,
+    /// Names of subtasks earlier in the same list that must complete first
+    #[serde(default)]
+    depends_on: Vec<String>,
+    /// Defaults to the parent's
+    max_retries: Option<i32>,
+    /// 0 takes the default for the task type
+    #[serde(default)]
+    timeout_seconds: i32,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    /// Worker labels the subtask needs; not inherited from the parent
+    #[serde(default)]
+    requirements: HashMap<String, String>,
+    /// Defaults to the engine's; not inherited from the parent
+    retry_backoff: Option<BackoffStrategy>,
+}
+
+fn empty_object() -> Value {
+    Value::Object(serde_json::Map::new())
+}
+
+/// Whether a task result asks for subtasks rather than completing the task
+pub fn is_fan_out(result: &Value) -> bool {
+    matches!(result, Value::Object(object) if object.len() == 1 && object.contains_key(FAN_OUT_KEY))
+}
+
+/// The subtasks a fan-out `result` asks `parent` to create, each with the
+/// indexes of the earlier subtasks it depends on.
+///
+/// Subtasks belong to the parent's workflow and tenant and inherit its
+/// priority; fails if the list is malformed.
+pub fn children(result: &Value, parent: &Task, timeouts: &TimeoutRegistry) -> Result<Vec<(NewTask, Vec<usize>)>, String> {
+    let specs = result.get(FAN_OUT_KEY).cloned().unwrap_or_default();
+    let specs: Vec<ChildSpec> = serde_json::from_value(specs)
+        .map_err(|e| format!("{} must be a list of subtasks: {}", FAN_OUT_KEY, e))?;
+    if specs.len() > MAX_FAN_OUT {
+        return Err(format!("{} subtasks requested, at most {} are allowed", specs.len(), MAX_FAN_OUT));
+    }
+
+    let mut indexes: HashMap<String, usize> = HashMap::new();
+    let mut children = Vec::with_capacity(specs.len());
+    for (index, spec) in specs.into_iter().enumerate() {
+        let name = match spec.name.as_str() {
+            "" => format!("{}[{}]", parent.name, index),
+            name => name.to_string(),
+        };
+        if !spec.parameters.is_object() {
+            return Err(format!("subtask {}: parameters must be a JSON object", name));
+        }
+        if spec.max_retries.is_some_and(|retries| retries < 0) {
+            return Err(format!("subtask {}: max_retries must not be negative", name));
+        }
+        if spec.timeout_seconds < 0 {
+            return Err(format!("subtask {}: timeout_seconds must not be negative", name));
+        }
+        if let Some(Err(e)) = spec.retry_backoff.as_ref().map(BackoffStrategy::validate) {
+            return Err(format!("subtask {}: retry_backoff: {}", name, e));
+        }
+        let siblings = spec
+            .depends_on
+            .iter()
+            .map(|dependency| {
+                indexes
+                    .get(dependency)
+                    .copied()
+                    .ok_or_else(|| format!("subtask {}: {} is not listed before it", name, dependency))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if indexes.insert(name.clone(), index).is_some() {
+            return Err(format!("subtask name {} is used more than once", name));
+        }
+
+        let child = NewTask {
+            workflow_id: parent.workflow_id,
+            timeout_seconds: timeouts.resolve(&spec.task_type, spec.timeout_seconds),
+            name,
+            max_retries: spec.max_retries.unwrap_or(parent.max_retries),
+            parameters: spec.parameters,
+            depends_on: Vec::new(),
+            idempotency_key: None,
+            priority: parent.priority,
+            task_type: spec.task_type,
+            replayed_from: None,
+            tags: spec.tags,
+            scheduled_at: None,
+            payload_format: PayloadFormat::Json,
+            payload: None,
+            tenant_id: parent.tenant_id,
+            created_by: parent.created_by.clone(),
+            parent_task_id: Some(parent.id),
+            requirements: spec.requirements,
+            retry_backoff: spec.retry_backoff,
+        };
+        children.push((child, siblings));
+    }
+
+    Ok(children)
+}
@@ -1,21 +1,44 @@
 mod api;
+mod auth;
+mod backoff;
+mod blob;
 mod engine;
+mod events;
+mod fan_out;
 mod models;
 mod database;
 mod queue;
-mod client;
+mod dag;
+mod metrics;
+mod propagation;
+mod references;
+mod requirements;
+mod retention;
+mod retry_budget;
+mod schema;
+mod slow_queries;
+mod timeouts;
+mod validation;
 
 use std::error::Error;
-use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::info;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     // Initialize tracing
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)?;
+    init_tracing()?;
+    
+    // Migrations can run as a separate deployment step before rolling out the service
+    if migrate_only() {
+        info!("Running database migrations only...");
+        let db_pool = database::connect_db_pool().await?;
+        database::run_migrations(&db_pool).await?;
+        db_pool.close().await;
+        info!("Migrations complete, exiting");
+        return Ok(());
+    }
     
     info!("Starting Durable Engine service...");
     
@@ -23,14 +46,79 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let db_pool = database::init_db_pool().await?;
     
     // Initialize Kafka consumer
-    let kafka_consumer = queue::init_kafka_consumer()?;
+    let mut kafka_config = queue::KafkaConfig::from_env()?;
+    if let Some(group_id) = group_id_override() {
+        kafka_config = kafka_config.with_group_id(group_id);
+    }
+    let kafka_consumer = queue::init_kafka_consumer(&kafka_config).await?;
+    
+    // Expose Prometheus metrics
+    metrics::start_metrics_server().await?;
+    
+    // Large payloads and results go to the blob store
+    let payloads = blob::PayloadStore::from_env()?;
+    
+    // Parameters are validated when tasks are created and again before they run
+    let schemas = std::sync::Arc::new(schema::SchemaRegistry::from_env()?);
+    
+    // Shared with the admin RPC that pauses and resumes processing
+    let processing = engine::ProcessingSwitch::new();
+    
+    // Task events are published for external subscribers as they are recorded
+    let events = events::EventPublisher::new(
+        db_pool.clone(),
+        queue::init_kafka_producer(&kafka_config)?,
+        kafka_config.events_topic.clone(),
+    );
+    
+    // Publishes events whose publish failed or never happened, e.g. after a crash
+    let relay = events.clone();
+    let relay_config = events::RelayConfig::from_env()?;
+    tokio::spawn(async move { relay.run_relay(relay_config).await });
+    
+    // Removes deleted and finished workflows once they are past the retention period
+    let retention = retention::RetentionConfig::from_env()?;
+    tokio::spawn(retention::run_purge_loop(db_pool.clone(), retention));
+    
+    // Default timeouts by task type, for tasks created by clients and by fan-outs
+    let timeouts = timeouts::TimeoutRegistry::from_env()?;
+    
+    // Runs tasks, and records what workers report about them through the API
+    let engine = engine::TaskEngine::new(
+        db_pool.clone(),
+        payloads.clone(),
+        schemas.clone(),
+        processing.clone(),
+        events.clone(),
+        timeouts.clone(),
+        engine::EngineConfig {
+            reconciliation: engine::ReconciliationConfig::from_env()?,
+            worker_labels: requirements::WorkerLabels::from_env()?,
+            retry_budget: retry_budget::RetryBudget::from_env()?,
+            retry_backoff: backoff::BackoffStrategy::from_env()?,
+            max_concurrent_tasks: max_concurrent_tasks()?,
+        },
+    );
     
     // Start the gRPC server
-    let grpc_server = api::start_grpc_server(db_pool.clone()).await?;
+    api::start_grpc_server(
+        db_pool.clone(),
+        payloads,
+        schemas,
+        events,
+        timeouts,
+        engine.clone(),
+        api::ServerConfig {
+            retry_limits: api::RetryLimits::from_env()?,
+            admin: auth::AdminAuth::from_env(),
+            limits: api::MessageSizeLimits::from_env()?,
+            watch_poll_interval: api::watch_poll_interval_from_env()?,
+            kafka: kafka_config.clone(),
+        },
+    ).await?;
     
     // Start the task processor
-    let engine = engine::TaskEngine::new(db_pool);
-    engine.start_processing(kafka_consumer).await?;
+    engine.start_processing(kafka_consumer, kafka_config).await?;
     
     info!("Durable Engine service started successfully");
     
@@ -38,5 +126,77 @@ async fn main() -> Result<(), Box<dyn Error>> {
     tokio::signal::ctrl_c().await?;
     info!("Shutting down Durable Engine service...");
     
+    // Drain in-flight tasks before closing the database
+    engine.shutdown(shutdown_grace_period()?).await?;
+    db_pool.close().await;
+    
+    info!("Durable Engine service stopped");
+    
     Ok(())
 }
+
+/// Install the global subscriber.
+///
+/// `LOG_FORMAT=json` emits one JSON object per line, including the fields of
+/// the enclosing spans (`task_id`, `workflow_id`, `request_id`); anything else
+/// keeps the human-readable output. Filtering follows `RUST_LOG` directives,
+/// falling back to `LOG_LEVEL` (default `info`).
+fn init_tracing() -> Result<(), Box<dyn Error>> {
+    let filter = match std::env::var("RUST_LOG") {
+        Ok(directives) => EnvFilter::try_new(directives)?,
+        Err(_) => EnvFilter::try_new(std::env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()))?,
+    };
+    let builder = FmtSubscriber::builder().with_env_filter(filter);
+    // Database queries run in `db.*` spans; this warns about slow ones
+    let slow_queries = slow_queries::SlowQueryLayer::from_env()?;
+    
+    match std::env::var("LOG_FORMAT").as_deref() {
+        Ok("json") => {
+            let subscriber = builder.json().with_current_span(true).with_span_list(true).finish();
+            tracing::subscriber::set_global_default(subscriber.with(slow_queries))?;
+        }
+        _ => tracing::subscriber::set_global_default(builder.finish().with(slow_queries))?,
+    }
+    
+    Ok(())
+}
+
+/// How long to wait for active tasks on shutdown, from `SHUTDOWN_GRACE_PERIOD_SECS` (default 30)
+fn shutdown_grace_period() -> Result<std::time::Duration, Box<dyn Error>> {
+    let secs: u64 = std::env::var("SHUTDOWN_GRACE_PERIOD_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse()?;
+    Ok(std::time::Duration::from_secs(secs))
+}
+
+/// Most tasks the engine runs at once, from `MAX_CONCURRENT_TASKS` (default 100)
+fn max_concurrent_tasks() -> Result<usize, Box<dyn Error>> {
+    let limit: usize = std::env::var("MAX_CONCURRENT_TASKS")
+        .unwrap_or_else(|_| "100".to_string())
+        .parse()?;
+    if limit == 0 {
+        return Err("MAX_CONCURRENT_TASKS must be positive".into());
+    }
+    Ok(limit)
+}
+
+/// True when started with `--migrate-only` or `CHRONOS_MIGRATE_ONLY=1`
+fn migrate_only() -> bool {
+    std::env::args().any(|arg| arg == "--migrate-only")
+        || std::env::var("CHRONOS_MIGRATE_ONLY").is_ok_and(|v| v == "1" || v == "true")
+}
+
+/// Consumer group from `--kafka-group-id <id>` or `--kafka-group-id=<id>`, taking
+/// precedence over `KAFKA_GROUP_ID`
+fn group_id_override() -> Option<String> {
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        if arg == "--kafka-group-id" {
+            return args.next();
+        }
+        if let Some(group_id) = arg.strip_prefix("--kafka-group-id=") {
+            return Some(group_id.to_string());
+        }
+    }
+    None
+}
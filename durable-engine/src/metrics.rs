@@ -0,0 +1,126 @@
+use anyhow::Result;
+use axum::{http::header, response::IntoResponse, routing::get, Router};
+use prometheus::{
+    register_histogram, register_int_counter, register_int_gauge, Encoder, Histogram, IntCounter,
+    IntGauge, TextEncoder,
+};
+use std::env;
+use std::net::SocketAddr;
+use std::sync::LazyLock;
+use tracing::{error, info};
+
+pub static TASKS_PROCESSED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "chronos_engine_tasks_processed_total",
+        "Total number of tasks that completed successfully"
+    )
+    .expect("metric can be registered")
+});
+
+pub static TASKS_FAILED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "chronos_engine_tasks_failed_total",
+        "Total number of tasks that failed after exhausting their retries"
+    )
+    .expect("metric can be registered")
+});
+
+pub static TASKS_RETRIED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "chronos_engine_tasks_retried_total",
+        "Total number of task attempts that failed and were requeued for retry"
+    )
+    .expect("metric can be registered")
+});
+
+pub static RETRIES_DELAYED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "chronos_engine_retries_delayed_total",
+        "Total number of retries held back because the retry budget was exhausted"
+    )
+    .expect("metric can be registered")
+});
+
+pub static TASKS_TIMED_OUT: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "chronos_engine_tasks_timed_out_total",
+        "Total number of running tasks reset by the reconciliation loop"
+    )
+    .expect("metric can be registered")
+});
+
+pub static EVENTS_PUBLISH_FAILED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "chronos_engine_events_publish_failed_total",
+        "Total number of task events that could not be published to the events topic"
+    )
+    .expect("metric can be registered")
+});
+
+pub static TASK_DURATION: LazyLock<Histogram> = LazyLock::new(|| {
+    register_histogram!(
+        "chronos_engine_task_duration_seconds",
+        "Task execution time from started_at to completed_at in seconds",
+        vec![0.1, 0.5, 1.0, 5.0, 15.0, 60.0, 300.0, 900.0, 3600.0]
+    )
+    .expect("metric can be registered")
+});
+
+pub static ACTIVE_TASKS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "chronos_engine_active_tasks",
+        "Number of tasks currently running on this engine"
+    )
+    .expect("metric can be registered")
+});
+
+pub static PROCESSING_PAUSED: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "chronos_engine_processing_paused",
+        "1 while an operator has paused task processing on this engine, 0 otherwise"
+    )
+    .expect("metric can be registered")
+});
+
+/// Record how long a finished task ran
+pub fn observe_task_duration(
+    started_at: Option<chrono::DateTime<chrono::Utc>>,
+    completed_at: Option<chrono::DateTime<chrono::Utc>>,
+) {
+    if let (Some(started_at), Some(completed_at)) = (started_at, completed_at) {
+        let seconds = (completed_at - started_at).num_milliseconds() as f64 / 1000.0;
+        TASK_DURATION.observe(seconds.max(0.0));
+    }
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+
+    if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        error!("Failed to encode metrics: {}", e);
+    }
+
+    ([(header::CONTENT_TYPE, encoder.format_type().to_string())], buffer)
+}
+
+/// Serve the `/metrics` endpoint on `METRICS_PORT` (default 9100)
+pub async fn start_metrics_server() -> Result<()> {
+    let port: u16 = env::var("METRICS_PORT")
+        .unwrap_or_else(|_| "9100".to_string())
+        .parse()?;
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+
+    let app = Router::new().route("/metrics", get(metrics_handler));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    info!("Starting metrics server on {}", addr);
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            error!("Metrics server failed: {:?}", e);
+        }
+    });
+
+    Ok(())
+}
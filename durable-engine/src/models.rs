@@ -2,7 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Stored in the `task_state` Postgres enum. Variants map to the same
+/// SCREAMING_SNAKE_CASE labels as `Display`, so `TimedOut` is `TIMED_OUT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "task_state", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TaskState {
     Queued,
     Running,
@@ -27,6 +30,239 @@ impl std::fmt::Display for TaskState {
     }
 }
 
+impl TaskState {
+    /// Whether the task has finished and will not run again
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            TaskState::Completed | TaskState::Failed | TaskState::Cancelled | TaskState::TimedOut
+        )
+    }
+    
+    /// Whether a task may move from this state to `next`: it is claimed or
+    /// fails validation once queued, an attempt ends or is requeued, a
+    /// failure is requeued for another try, and anything unfinished can be
+    /// cancelled
+    pub fn can_transition_to(self, next: TaskState) -> bool {
+        use TaskState::*;
+        matches!(
+            (self, next),
+            (Queued, Running | Failed | Cancelled)
+                | (Running, Queued | Completed | Failed | Cancelled | TimedOut)
+                | (Retrying, Queued | Cancelled)
+                | (Failed | TimedOut, Queued)
+        )
+    }
+}
+
+/// Stored in the `workflow_state` Postgres enum, with the same labels as `Display`.
+///
+/// A workflow starts as a `Draft`, whose tasks can be added but none runs
+/// until StartWorkflow moves it to `Running`. It settles in `Completed`,
+/// `Failed` or `Cancelled` once every task has finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "workflow_state", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WorkflowState {
+    Draft,
+    Running,
+    /// Started, but none of its QUEUED tasks are claimed until it is resumed
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl std::fmt::Display for WorkflowState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkflowState::Draft => write!(f, "DRAFT"),
+            WorkflowState::Running => write!(f, "RUNNING"),
+            WorkflowState::Paused => write!(f, "PAUSED"),
+            WorkflowState::Completed => write!(f, "COMPLETED"),
+            WorkflowState::Failed => write!(f, "FAILED"),
+            WorkflowState::Cancelled => write!(f, "CANCELLED"),
+        }
+    }
+}
+
+impl WorkflowState {
+    /// Whether every task has finished and the workflow has settled
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            WorkflowState::Completed | WorkflowState::Failed | WorkflowState::Cancelled
+        )
+    }
+
+    /// Whether StartWorkflow has run, so its ready tasks may be claimed unless paused
+    pub fn is_started(self) -> bool {
+        self != WorkflowState::Draft
+    }
+
+    /// The state a started workflow settles in once its tasks are in `states`,
+    /// or `None` while any of them may still run, or if there are none.
+    ///
+    /// Any failed or timed out task fails the workflow; otherwise any
+    /// cancelled task cancels it.
+    pub fn settled(states: impl IntoIterator<Item = TaskState>) -> Option<Self> {
+        let mut settled = None;
+        for state in states {
+            let outcome = WorkflowState::from(state);
+            if !outcome.is_terminal() {
+                return None;
+            }
+            settled = match (settled, outcome) {
+                (Some(WorkflowState::Failed), _) | (_, WorkflowState::Failed) => Some(WorkflowState::Failed),
+                (Some(WorkflowState::Cancelled), _) | (_, WorkflowState::Cancelled) => Some(WorkflowState::Cancelled),
+                _ => Some(WorkflowState::Completed),
+            };
+        }
+        settled
+    }
+}
+
+/// The workflow state a single task's state amounts to
+impl From<TaskState> for WorkflowState {
+    fn from(state: TaskState) -> Self {
+        match state {
+            TaskState::Queued | TaskState::Running | TaskState::Retrying => WorkflowState::Running,
+            TaskState::Completed => WorkflowState::Completed,
+            TaskState::Failed | TaskState::TimedOut => WorkflowState::Failed,
+            TaskState::Cancelled => WorkflowState::Cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown workflow state: {0:?}")]
+pub struct ParseWorkflowStateError(pub String);
+
+impl std::str::FromStr for WorkflowState {
+    type Err = ParseWorkflowStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "DRAFT" => Ok(WorkflowState::Draft),
+            "RUNNING" => Ok(WorkflowState::Running),
+            "PAUSED" => Ok(WorkflowState::Paused),
+            "COMPLETED" => Ok(WorkflowState::Completed),
+            "FAILED" => Ok(WorkflowState::Failed),
+            "CANCELLED" => Ok(WorkflowState::Cancelled),
+            other => Err(ParseWorkflowStateError(other.to_string())),
+        }
+    }
+}
+
+/// Why the engine moved a task back to `Queued` on its own initiative.
+///
+/// Stored under the `reset_reason` key of `task_events.metadata` so a task's
+/// timeline distinguishes a deploy-driven requeue from a crashed worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ResetReason {
+    GracefulShutdown,
+    LeaderLoss,
+    RebalanceRevoked,
+    ForcedTimeout,
+    StuckTask,
+}
+
+impl std::fmt::Display for ResetReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResetReason::GracefulShutdown => write!(f, "GRACEFUL_SHUTDOWN"),
+            ResetReason::LeaderLoss => write!(f, "LEADER_LOSS"),
+            ResetReason::RebalanceRevoked => write!(f, "REBALANCE_REVOKED"),
+            ResetReason::ForcedTimeout => write!(f, "FORCED_TIMEOUT"),
+            ResetReason::StuckTask => write!(f, "STUCK_TASK"),
+        }
+    }
+}
+
+impl ResetReason {
+    /// Metadata payload recorded on the requeue event
+    pub fn to_metadata(self) -> serde_json::Value {
+        serde_json::json!({ "reset_reason": self })
+    }
+
+    /// Extract the reset reason from an event's metadata, if present
+    pub fn from_metadata(metadata: &serde_json::Value) -> Option<Self> {
+        metadata
+            .get("reset_reason")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown task state: {0:?}")]
+pub struct ParseTaskStateError(pub String);
+
+impl std::str::FromStr for TaskState {
+    type Err = ParseTaskStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "QUEUED" => Ok(TaskState::Queued),
+            "RUNNING" => Ok(TaskState::Running),
+            "COMPLETED" => Ok(TaskState::Completed),
+            "FAILED" => Ok(TaskState::Failed),
+            "RETRYING" => Ok(TaskState::Retrying),
+            "CANCELLED" => Ok(TaskState::Cancelled),
+            "TIMED_OUT" => Ok(TaskState::TimedOut),
+            other => Err(ParseTaskStateError(other.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for TaskState {
+    type Error = ParseTaskStateError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// How a task's parameters are encoded, stored in the `payload_format`
+/// Postgres enum. The engine only reads JSON parameters; MessagePack and
+/// protobuf payloads are passed through to workers as opaque bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "payload_format", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PayloadFormat {
+    #[default]
+    Json,
+    Msgpack,
+    Protobuf,
+}
+
+impl std::fmt::Display for PayloadFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayloadFormat::Json => write!(f, "JSON"),
+            PayloadFormat::Msgpack => write!(f, "MSGPACK"),
+            PayloadFormat::Protobuf => write!(f, "PROTOBUF"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown payload format: {0:?}")]
+pub struct ParsePayloadFormatError(pub String);
+
+impl std::str::FromStr for PayloadFormat {
+    type Err = ParsePayloadFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "JSON" => Ok(PayloadFormat::Json),
+            "MSGPACK" => Ok(PayloadFormat::Msgpack),
+            "PROTOBUF" => Ok(PayloadFormat::Protobuf),
+            other => Err(ParsePayloadFormatError(other.to_string())),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: Uuid,
@@ -43,18 +279,24 @@ pub struct Task {
     pub parameters: serde_json::Value,
     pub result: Option<serde_json::Value>,
     pub error: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Workflow {
-    pub id: Uuid,
-    pub name: String,
-    pub state: TaskState,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-    pub started_at: Option<DateTime<Utc>>,
-    pub completed_at: Option<DateTime<Utc>>,
-    pub tasks: Vec<Task>,
+    pub priority: i32,
+    pub task_type: String,
+    /// Optimistic lock, incremented on every update
+    pub version: i32,
+    /// String labels as a JSON object
+    pub tags: serde_json::Value,
+    pub payload_format: PayloadFormat,
+    /// The encoded parameters of MessagePack and protobuf tasks; `None` for JSON
+    pub payload: Option<Vec<u8>>,
+    pub tenant_id: Uuid,
+    /// Principal that created the task, empty if unknown
+    pub created_by: String,
+    /// Worker labels the task needs, as a JSON object of strings
+    pub requirements: serde_json::Value,
+    /// Why the task is still QUEUED, e.g. no worker matches its requirements
+    pub waiting_reason: Option<String>,
+    /// Hex SHA-256 of the full result, see [`crate::blob::checksum`]
+    pub result_checksum: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,3 +310,46 @@ pub struct TaskEvent {
     pub timestamp: DateTime<Utc>,
     pub metadata: Option<serde_json::Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASK_STATES: [TaskState; 7] = [
+        TaskState::Queued,
+        TaskState::Running,
+        TaskState::Completed,
+        TaskState::Failed,
+        TaskState::Retrying,
+        TaskState::Cancelled,
+        TaskState::TimedOut,
+    ];
+
+    #[test]
+    fn task_states_parse_from_their_labels() {
+        for state in TASK_STATES {
+            assert_eq!(state.to_string().parse(), Ok(state));
+            assert_eq!(TaskState::try_from(state.to_string()), Ok(state));
+        }
+    }
+
+    #[test]
+    fn finished_tasks_only_move_back_to_queued() {
+        for next in TASK_STATES {
+            assert!(!TaskState::Completed.can_transition_to(next));
+            assert!(!TaskState::Cancelled.can_transition_to(next));
+            assert_eq!(TaskState::Failed.can_transition_to(next), next == TaskState::Queued);
+        }
+        assert!(TaskState::Queued.can_transition_to(TaskState::Running));
+        assert!(!TaskState::Queued.can_transition_to(TaskState::Completed));
+    }
+
+    #[test]
+    fn unknown_task_states_are_rejected() {
+        for label in ["BOGUS", "queued", "TimedOut", ""] {
+            let error = label.parse::<TaskState>().unwrap_err();
+            assert_eq!(error, ParseTaskStateError(label.to_string()));
+            assert_eq!(error.to_string(), format!("Unknown task state: {:?}", label));
+        }
+    }
+}
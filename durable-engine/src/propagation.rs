@@ -0,0 +1,87 @@
+use opentelemetry::global::{self, BoxedSpan};
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::Tracer;
+use opentelemetry::Context;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use tonic::metadata::{KeyRef, MetadataMap};
+use tonic::{Request, Status};
+use uuid::Uuid;
+
+// Reads W3C trace headers (`traceparent`, `tracestate`) out of gRPC metadata
+struct MetadataExtractor<'a>(&'a MetadataMap);
+
+impl Extractor for MetadataExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0
+            .keys()
+            .filter_map(|key| match key {
+                KeyRef::Ascii(key) => Some(key.as_str()),
+                KeyRef::Binary(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Server interceptor that continues the caller's trace.
+///
+/// The extracted parent context is stored in the request extensions and
+/// picked up by [`server_span`].
+pub fn extract_trace_context(mut request: Request<()>) -> Result<Request<()>, Status> {
+    let parent = TraceContextPropagator::new().extract(&MetadataExtractor(request.metadata()));
+    request.extensions_mut().insert(parent);
+    Ok(request)
+}
+
+/// Start a server span that is a child of the caller's span when one was propagated
+pub fn server_span<T>(request: &Request<T>, name: &'static str) -> BoxedSpan {
+    let parent = request
+        .extensions()
+        .get::<Context>()
+        .cloned()
+        .unwrap_or_default();
+
+    global::tracer("durable-engine").start_with_context(name, &parent)
+}
+
+/// Correlation id for a request: the caller's `x-request-id` if sent, otherwise a fresh one
+pub fn request_id<T>(request: &Request<T>) -> String {
+    request
+        .metadata()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TraceContextExt;
+
+    #[test]
+    fn interceptor_keeps_the_callers_trace() {
+        let mut request = Request::new(());
+        request.metadata_mut().insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".parse().unwrap(),
+        );
+
+        let request = extract_trace_context(request).unwrap();
+        let parent = request.extensions().get::<Context>().unwrap();
+        let span_context = parent.span().span_context().clone();
+        assert!(span_context.is_remote());
+        assert_eq!(span_context.trace_id().to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(span_context.span_id().to_string(), "00f067aa0ba902b7");
+    }
+
+    #[test]
+    fn interceptor_without_trace_headers_starts_a_new_trace() {
+        let request = extract_trace_context(Request::new(())).unwrap();
+        let parent = request.extensions().get::<Context>().unwrap();
+        assert!(!parent.span().span_context().is_valid());
+    }
+}
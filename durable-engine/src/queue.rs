@@ -1,24 +1,34 @@
-use anyhow::Result;
+use crate::database::env_or;
+use crate::models::TaskEvent;
+use anyhow::{Context, Result};
 use rdkafka::client::ClientContext;
 use rdkafka::config::{ClientConfig, RDKafkaLogLevel};
-use rdkafka::consumer::{ConsumerContext, Rebalance, StreamConsumer};
+use rdkafka::consumer::{BaseConsumer, Consumer, ConsumerContext, Rebalance, StreamConsumer};
 use rdkafka::error::KafkaResult;
+use rdkafka::producer::{FutureProducer, FutureRecord};
 use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::util::Timeout;
+use serde::{Deserialize, Serialize};
 use std::env;
+use std::time::Duration;
 use tracing::{info, warn};
+use uuid::Uuid;
+
+const METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(5);
 
 // A context can be used to change the behavior of producers and consumers by adding callbacks
 // that will be executed by librdkafka.
-struct CustomContext;
+pub struct CustomContext;
 
 impl ClientContext for CustomContext {}
 
 impl ConsumerContext for CustomContext {
-    fn pre_rebalance(&self, rebalance: &Rebalance) {
+    fn pre_rebalance(&self, _consumer: &BaseConsumer<Self>, rebalance: &Rebalance) {
         info!("Pre rebalance: {:?}", rebalance);
     }
 
-    fn post_rebalance(&self, rebalance: &Rebalance) {
+    fn post_rebalance(&self, _consumer: &BaseConsumer<Self>, rebalance: &Rebalance) {
         info!("Post rebalance: {:?}", rebalance);
     }
 
@@ -30,32 +40,396 @@ impl ConsumerContext for CustomContext {
     }
 }
 
-type LoggingConsumer = StreamConsumer<CustomContext>;
+pub type LoggingConsumer = StreamConsumer<CustomContext>;
+
+/// How hard to try reaching Kafka before giving up
+struct RetryConfig {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryConfig {
+    /// Read from `KAFKA_CONNECT_MAX_ATTEMPTS` (default 10), `KAFKA_CONNECT_INITIAL_BACKOFF_MS`
+    /// (default 500) and `KAFKA_CONNECT_MAX_BACKOFF_MS` (default 30000)
+    fn from_env() -> Result<Self> {
+        let max_attempts: u32 = env_or("KAFKA_CONNECT_MAX_ATTEMPTS", 10)?;
+        if max_attempts == 0 {
+            anyhow::bail!("KAFKA_CONNECT_MAX_ATTEMPTS must be at least 1");
+        }
+
+        Ok(Self {
+            max_attempts,
+            initial_backoff: Duration::from_millis(env_or("KAFKA_CONNECT_INITIAL_BACKOFF_MS", 500)?),
+            max_backoff: Duration::from_millis(env_or("KAFKA_CONNECT_MAX_BACKOFF_MS", 30_000)?),
+        })
+    }
+}
+
+/// Consumer settings, read from the environment
+#[derive(Debug, Clone)]
+pub struct KafkaConfig {
+    pub brokers: String,
+    pub group_id: String,
+    /// Task topics consumed together; new tasks are published to the first
+    pub topics: Vec<String>,
+    /// Topic workers watch for cancellations of tasks they are running
+    pub control_topic: String,
+    /// Topic every task event is published to for external subscribers
+    pub events_topic: String,
+    /// When false, the engine commits each offset itself once the task's state
+    /// transition has been written, giving at-least-once delivery
+    pub enable_auto_commit: bool,
+    pub auto_offset_reset: String,
+    pub max_poll_interval_ms: u32,
+    pub session_timeout_ms: u32,
+    pub fetch_max_bytes: u32,
+}
+
+impl KafkaConfig {
+    /// Read consumer settings from the environment:
+    ///
+    /// | Variable | Default |
+    /// |---|---|
+    /// | `KAFKA_BROKERS` | `localhost:9092` |
+    /// | `KAFKA_GROUP_ID` | `chronos-durable-engine` |
+    /// | `KAFKA_TOPIC` | `chronos-tasks` (comma-separated for several, e.g. `chronos-tasks,chronos-priority-tasks`) |
+    /// | `KAFKA_CONTROL_TOPIC` | `chronos-control` |
+    /// | `KAFKA_EVENTS_TOPIC` | `chronos-events` |
+    /// | `KAFKA_ENABLE_AUTO_COMMIT` | `false` (manual commit after each task transition) |
+    /// | `KAFKA_AUTO_OFFSET_RESET` | `earliest` |
+    /// | `KAFKA_MAX_POLL_INTERVAL_MS` | `300000` |
+    /// | `KAFKA_SESSION_TIMEOUT_MS` | `45000` |
+    /// | `KAFKA_FETCH_MAX_BYTES` | `52428800` |
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            brokers: env::var("KAFKA_BROKERS").unwrap_or_else(|_| "localhost:9092".to_string()),
+            group_id: env::var("KAFKA_GROUP_ID").unwrap_or_else(|_| "chronos-durable-engine".to_string()),
+            topics: parse_topics(&env::var("KAFKA_TOPIC").unwrap_or_else(|_| "chronos-tasks".to_string()))?,
+            control_topic: env::var("KAFKA_CONTROL_TOPIC").unwrap_or_else(|_| "chronos-control".to_string()),
+            events_topic: env::var("KAFKA_EVENTS_TOPIC").unwrap_or_else(|_| "chronos-events".to_string()),
+            enable_auto_commit: env_or("KAFKA_ENABLE_AUTO_COMMIT", false)?,
+            auto_offset_reset: env::var("KAFKA_AUTO_OFFSET_RESET").unwrap_or_else(|_| "earliest".to_string()),
+            max_poll_interval_ms: env_or("KAFKA_MAX_POLL_INTERVAL_MS", 300_000)?,
+            session_timeout_ms: env_or("KAFKA_SESSION_TIMEOUT_MS", 45_000)?,
+            fetch_max_bytes: env_or("KAFKA_FETCH_MAX_BYTES", 52_428_800)?,
+        })
+    }
+
+    /// Replace the consumer group read from `KAFKA_GROUP_ID`
+    pub fn with_group_id(mut self, group_id: impl Into<String>) -> Self {
+        self.group_id = group_id.into();
+        self
+    }
+
+    /// Topic new tasks are published to
+    pub fn task_topic(&self) -> &str {
+        &self.topics[0]
+    }
+}
+
+/// Split a comma-separated topic list, requiring at least one topic
+fn parse_topics(raw: &str) -> Result<Vec<String>> {
+    let topics: Vec<String> = raw
+        .split(',')
+        .map(str::trim)
+        .filter(|topic| !topic.is_empty())
+        .map(str::to_string)
+        .collect();
+    if topics.is_empty() {
+        anyhow::bail!("KAFKA_TOPIC must name at least one topic");
+    }
+    Ok(topics)
+}
+
+/// Initialize the Kafka consumer, retrying with exponential backoff while Kafka is unreachable
+pub async fn init_kafka_consumer(config: &KafkaConfig) -> Result<LoggingConsumer> {
+    let retry = RetryConfig::from_env()?;
+    let mut backoff = retry.initial_backoff;
+    let mut attempt = 1;
 
-/// Initialize the Kafka consumer
-pub fn init_kafka_consumer() -> Result<LoggingConsumer> {
-    let brokers = env::var("KAFKA_BROKERS").unwrap_or_else(|_| "localhost:9092".to_string());
-    let group_id = env::var("KAFKA_GROUP_ID").unwrap_or_else(|_| "chronos-durable-engine".to_string());
-    let topic = env::var("KAFKA_TOPIC").unwrap_or_else(|_| "chronos-tasks".to_string());
+    loop {
+        let config = config.clone();
+        let result = tokio::task::spawn_blocking(move || connect_consumer(&config))
+            .await
+            .context("Kafka connect task panicked")?;
 
-    info!("Connecting to Kafka at {}", brokers);
+        match result {
+            Ok(consumer) => return Ok(consumer),
+            Err(e) if attempt < retry.max_attempts => {
+                warn!(
+                    "Kafka connection attempt {}/{} failed, retrying in {:?}: {:?}",
+                    attempt, retry.max_attempts, backoff, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(retry.max_backoff);
+                attempt += 1;
+            }
+            Err(e) => {
+                return Err(e).context(format!(
+                    "Kafka unavailable after {} connection attempts",
+                    attempt
+                ))
+            }
+        }
+    }
+}
+
+/// Create the consumer, subscribe, and confirm the brokers are reachable.
+///
+/// Blocks on a metadata request, so run it off the async runtime.
+fn connect_consumer(config: &KafkaConfig) -> Result<LoggingConsumer> {
+    info!("Connecting to Kafka at {}", config.brokers);
 
     // Create the consumer
     let consumer: LoggingConsumer = ClientConfig::new()
-        .set("group.id", &group_id)
-        .set("bootstrap.servers", &brokers)
-        .set("enable.auto.commit", "true")
-        .set("auto.offset.reset", "earliest")
+        .set("group.id", &config.group_id)
+        .set("bootstrap.servers", &config.brokers)
+        .set("enable.auto.commit", config.enable_auto_commit.to_string())
+        .set("auto.offset.reset", &config.auto_offset_reset)
+        .set("max.poll.interval.ms", config.max_poll_interval_ms.to_string())
+        .set("session.timeout.ms", config.session_timeout_ms.to_string())
+        .set("fetch.max.bytes", config.fetch_max_bytes.to_string())
         .set_log_level(RDKafkaLogLevel::Debug)
         .create_with_context(CustomContext)
-        .expect("Consumer creation failed");
+        .context("Consumer creation failed")?;
 
-    // Subscribe to the topic
+    // Creation and subscription are lazy, so ask for metadata to find out whether Kafka is up
+    for topic in &config.topics {
+        consumer
+            .fetch_metadata(Some(topic), METADATA_TIMEOUT)
+            .with_context(|| format!("Failed to reach Kafka at {}", config.brokers))?;
+    }
+
+    // Subscribe to every task topic
+    let topics: Vec<&str> = config.topics.iter().map(String::as_str).collect();
     consumer
-        .subscribe(&[&topic])
-        .expect("Can't subscribe to specified topic");
+        .subscribe(&topics)
+        .context("Can't subscribe to specified topics")?;
 
-    info!("Kafka consumer initialized and subscribed to {}", topic);
+    info!(
+        "Kafka consumer initialized in group {} and subscribed to {} (auto commit: {})",
+        config.group_id,
+        topics.join(", "),
+        config.enable_auto_commit
+    );
 
     Ok(consumer)
 }
+
+/// Messages published on the control topic, keyed by task id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ControlMessage {
+    /// Workers running this task should abort it and report back
+    CancelTask { task_id: Uuid, workflow_id: Uuid },
+}
+
+impl ControlMessage {
+    fn key(&self) -> String {
+        match self {
+            ControlMessage::CancelTask { task_id, .. } => task_id.to_string(),
+        }
+    }
+}
+
+/// Initialize the producer used for task, control and event messages.
+///
+/// Idempotence keeps retried sends from being reordered within a partition.
+pub fn init_kafka_producer(config: &KafkaConfig) -> Result<FutureProducer> {
+    ClientConfig::new()
+        .set("bootstrap.servers", &config.brokers)
+        .set("message.timeout.ms", "5000")
+        .set("enable.idempotence", "true")
+        .create()
+        .context("Producer creation failed")
+}
+
+/// Newest [`TaskMessage`] schema this engine reads, and the one it writes
+pub const TASK_MESSAGE_VERSION: u32 = 1;
+
+/// Body of a message on the task topic, as JSON:
+///
+/// ```json
+/// {"schema_version": 1, "task_id": "<uuid>", "workflow_id": "<uuid>"}
+/// ```
+///
+/// Compatibility rules, so engines of different versions can share the topic
+/// during a rolling deploy:
+///
+/// - Within a version, fields may only be added, and must default when
+///   missing. Readers ignore fields they don't know.
+/// - Removing or renaming a field, or changing its meaning, needs a new
+///   `schema_version`. An engine skips messages newer than
+///   [`TASK_MESSAGE_VERSION`] without failing; the task is still QUEUED in the
+///   database, so the queued-task sweep runs it.
+/// - Version 0 is the bare task id payload written before this schema
+///   existed. It is still read, with a nil `workflow_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskMessage {
+    pub schema_version: u32,
+    pub task_id: Uuid,
+    pub workflow_id: Uuid,
+}
+
+/// A task topic message as understood by this engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodedTaskMessage {
+    Task(TaskMessage),
+    /// Written by a newer engine in a format this one can't read
+    UnsupportedVersion(u32),
+}
+
+impl TaskMessage {
+    pub fn new(task_id: Uuid, workflow_id: Uuid) -> Self {
+        Self {
+            schema_version: TASK_MESSAGE_VERSION,
+            task_id,
+            workflow_id,
+        }
+    }
+
+    /// Read a task topic payload of any version; errors only for payloads no
+    /// version could have written
+    pub fn decode(payload: &[u8]) -> Result<DecodedTaskMessage> {
+        let text = std::str::from_utf8(payload).context("task message is not UTF-8")?.trim();
+        if let Ok(task_id) = Uuid::parse_str(text) {
+            return Ok(DecodedTaskMessage::Task(Self {
+                schema_version: 0,
+                task_id,
+                workflow_id: Uuid::nil(),
+            }));
+        }
+
+        #[derive(Deserialize)]
+        struct Versioned {
+            schema_version: u32,
+        }
+        let Versioned { schema_version } =
+            serde_json::from_str(text).with_context(|| format!("task message has no schema_version: {}", text))?;
+        if schema_version > TASK_MESSAGE_VERSION {
+            return Ok(DecodedTaskMessage::UnsupportedVersion(schema_version));
+        }
+
+        let message = serde_json::from_str(text)
+            .with_context(|| format!("invalid version {} task message: {}", schema_version, text))?;
+        Ok(DecodedTaskMessage::Task(message))
+    }
+}
+
+/// Publish a task for the engine to process.
+///
+/// Messages are keyed by workflow id, so every task of a workflow lands on the
+/// same partition. A partition is consumed by one engine at a time and its
+/// messages are handled one after another, so tasks of the same workflow are
+/// picked up in the order they were published.
+pub async fn publish_task(producer: &FutureProducer, topic: &str, task_id: Uuid, workflow_id: Uuid) -> Result<()> {
+    let key = workflow_id.to_string();
+    let payload = serde_json::to_vec(&TaskMessage::new(task_id, workflow_id))?;
+
+    producer
+        .send(
+            FutureRecord::to(topic).key(&key).payload(&payload),
+            Timeout::After(PUBLISH_TIMEOUT),
+        )
+        .await
+        .map_err(|(e, _)| e)
+        .with_context(|| format!("Failed to publish task {} to {}", task_id, topic))?;
+
+    Ok(())
+}
+
+/// Publish a control message and wait for the broker to acknowledge it
+pub async fn publish_control(
+    producer: &FutureProducer,
+    topic: &str,
+    message: &ControlMessage,
+) -> Result<()> {
+    let payload = serde_json::to_vec(message)?;
+    let key = message.key();
+
+    producer
+        .send(
+            FutureRecord::to(topic).key(&key).payload(&payload),
+            Timeout::After(PUBLISH_TIMEOUT),
+        )
+        .await
+        .map_err(|(e, _)| e)
+        .with_context(|| format!("Failed to publish control message to {}", topic))?;
+
+    Ok(())
+}
+
+/// Publish a task event as JSON, keyed by workflow id so each workflow's
+/// events stay in order on one partition
+pub async fn publish_event(producer: &FutureProducer, topic: &str, event: &TaskEvent) -> Result<()> {
+    let payload = serde_json::to_vec(event)?;
+    let key = event.workflow_id.to_string();
+
+    producer
+        .send(
+            FutureRecord::to(topic).key(&key).payload(&payload),
+            Timeout::After(PUBLISH_TIMEOUT),
+        )
+        .await
+        .map_err(|(e, _)| e)
+        .with_context(|| format!("Failed to publish event {} to {}", event.id, topic))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASK_ID: &str = "3f1c2a9e-7b4d-4e8a-9c61-0d2f5b8e4a17";
+    const WORKFLOW_ID: &str = "b6e0d4c2-1a3f-4f5e-8d7c-9a0b1c2d3e4f";
+
+    fn decode(fixture: &str) -> Result<DecodedTaskMessage> {
+        let path = format!("{}/testdata/task_messages/{}", env!("CARGO_MANIFEST_DIR"), fixture);
+        TaskMessage::decode(&std::fs::read(path).unwrap())
+    }
+
+    fn task(schema_version: u32, workflow_id: &str) -> DecodedTaskMessage {
+        DecodedTaskMessage::Task(TaskMessage {
+            schema_version,
+            task_id: TASK_ID.parse().unwrap(),
+            workflow_id: workflow_id.parse().unwrap(),
+        })
+    }
+
+    #[test]
+    fn reads_bare_task_ids_as_version_0() {
+        assert_eq!(decode("v0.txt").unwrap(), task(0, &Uuid::nil().to_string()));
+    }
+
+    #[test]
+    fn reads_version_1() {
+        assert_eq!(decode("v1.json").unwrap(), task(1, WORKFLOW_ID));
+    }
+
+    #[test]
+    fn ignores_fields_added_within_a_version() {
+        assert_eq!(decode("v1_with_added_field.json").unwrap(), task(1, WORKFLOW_ID));
+    }
+
+    #[test]
+    fn skips_newer_versions() {
+        assert_eq!(decode("v2.json").unwrap(), DecodedTaskMessage::UnsupportedVersion(2));
+    }
+
+    #[test]
+    fn rejects_messages_no_version_could_have_written() {
+        assert!(decode("v1_missing_workflow_id.json").is_err());
+        assert!(decode("unversioned.json").is_err());
+        assert!(TaskMessage::decode(&[0xff, 0xfe]).is_err());
+    }
+
+    #[test]
+    fn writes_the_current_version() {
+        let message = TaskMessage::new(TASK_ID.parse().unwrap(), WORKFLOW_ID.parse().unwrap());
+        assert_eq!(message.schema_version, TASK_MESSAGE_VERSION);
+        let payload = serde_json::to_vec(&message).unwrap();
+        assert_eq!(TaskMessage::decode(&payload).unwrap(), DecodedTaskMessage::Task(message));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::blob::BLOB_REF_KEY;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Key of a parameter value that stands for part of a dependency's result,
+/// e.g. `{"$ref": "fetch.result.items.0.url"}`
+pub const REF_KEY: &str = "$ref";
+
+/// A parsed `$ref`: the dependency it reads and the path into its result.
+///
+/// Written `<task_name>.result`, optionally followed by `.<field>` segments;
+/// a segment indexes an array when it is a number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub task_name: String,
+    pub path: Vec<String>,
+}
+
+/// Why a task's parameter references can't be resolved
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReferenceError {
+    #[error("invalid reference {0:?}: expected {{\"$ref\": \"<task_name>.result[.<field>...]\"}}")]
+    Malformed(String),
+
+    #[error("reference {reference:?} names {task_name}, which is not a dependency of this task")]
+    NotADependency { reference: String, task_name: String },
+
+    #[error("reference {reference:?} reads {task_name}, which has not completed")]
+    Unfinished { reference: String, task_name: String },
+
+    #[error("reference {reference:?}: the result of {task_name} has no {field:?}")]
+    MissingField {
+        reference: String,
+        task_name: String,
+        field: String,
+    },
+
+    #[error("reference {reference:?}: the result of {task_name} is offloaded, so only the whole result can be referenced")]
+    Offloaded { reference: String, task_name: String },
+}
+
+impl Reference {
+    pub fn parse(reference: &str) -> Result<Self, ReferenceError> {
+        let malformed = || ReferenceError::Malformed(reference.to_string());
+        let mut segments = reference.split('.');
+        let task_name = segments.next().filter(|name| !name.is_empty()).ok_or_else(malformed)?;
+        if segments.next() != Some("result") {
+            return Err(malformed());
+        }
+        let path: Vec<String> = segments.map(str::to_string).collect();
+        if path.iter().any(String::is_empty) {
+            return Err(malformed());
+        }
+
+        Ok(Self {
+            task_name: task_name.to_string(),
+            path,
+        })
+    }
+}
+
+/// The `$ref` value if `value` is a `{"$ref": ...}` object
+fn as_reference(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Object(object) if object.len() == 1 => object.get(REF_KEY),
+        _ => None,
+    }
+}
+
+/// Every reference in `parameters`, in document order.
+///
+/// Fails on the first one that isn't a well-formed reference string.
+pub fn find(parameters: &Value) -> Result<Vec<Reference>, ReferenceError> {
+    let mut found = Vec::new();
+    collect(parameters, &mut found)?;
+    Ok(found)
+}
+
+fn collect(value: &Value, found: &mut Vec<Reference>) -> Result<(), ReferenceError> {
+    if let Some(reference) = as_reference(value) {
+        let reference = reference
+            .as_str()
+            .ok_or_else(|| ReferenceError::Malformed(reference.to_string()))?;
+        found.push(Reference::parse(reference)?);
+        return Ok(());
+    }
+    match value {
+        Value::Object(object) => object.values().try_for_each(|value| collect(value, found)),
+        Value::Array(items) => items.iter().try_for_each(|value| collect(value, found)),
+        _ => Ok(()),
+    }
+}
+
+/// `parameters` with every reference replaced by the value it points to.
+///
+/// `results` holds the task's dependencies by name, with `None` for one that
+/// hasn't completed.
+pub fn resolve(parameters: &Value, results: &HashMap<String, Option<Value>>) -> Result<Value, ReferenceError> {
+    if let Some(reference) = as_reference(parameters) {
+        let text = reference
+            .as_str()
+            .ok_or_else(|| ReferenceError::Malformed(reference.to_string()))?;
+        return lookup(text, &Reference::parse(text)?, results);
+    }
+    match parameters {
+        Value::Object(object) => object
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), resolve(value, results)?)))
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        Value::Array(items) => items
+            .iter()
+            .map(|value| resolve(value, results))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        value => Ok(value.clone()),
+    }
+}
+
+fn lookup(text: &str, reference: &Reference, results: &HashMap<String, Option<Value>>) -> Result<Value, ReferenceError> {
+    let result = results
+        .get(&reference.task_name)
+        .ok_or_else(|| ReferenceError::NotADependency {
+            reference: text.to_string(),
+            task_name: reference.task_name.clone(),
+        })?
+        .as_ref()
+        .ok_or_else(|| ReferenceError::Unfinished {
+            reference: text.to_string(),
+            task_name: reference.task_name.clone(),
+        })?;
+
+    if !reference.path.is_empty() && result.get(BLOB_REF_KEY).is_some() {
+        return Err(ReferenceError::Offloaded {
+            reference: text.to_string(),
+            task_name: reference.task_name.clone(),
+        });
+    }
+
+    let mut current = result;
+    for field in &reference.path {
+        let next = match current {
+            Value::Object(object) => object.get(field),
+            Value::Array(items) => field.parse::<usize>().ok().and_then(|index| items.get(index)),
+            _ => None,
+        };
+        current = next.ok_or_else(|| ReferenceError::MissingField {
+            reference: text.to_string(),
+            task_name: reference.task_name.clone(),
+            field: field.clone(),
+        })?;
+    }
+
+    Ok(current.clone())
+}
+
+/// Turn string parameters holding the JSON text of a reference, as sent in
+/// the string-valued proto parameter map, back into reference objects
+pub fn decode_string_references(parameters: &mut Value) {
+    let Value::Object(object) = parameters else {
+        return;
+    };
+    for value in object.values_mut() {
+        let Value::String(text) = value else {
+            continue;
+        };
+        if !text.trim_start().starts_with('{') {
+            continue;
+        }
+        if let Ok(decoded) = serde_json::from_str::<Value>(text) {
+            if as_reference(&decoded).is_some() {
+                *value = decoded;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn results(entries: &[(&str, Option<Value>)]) -> HashMap<String, Option<Value>> {
+        entries.iter().map(|(name, result)| (name.to_string(), result.clone())).collect()
+    }
+
+    #[test]
+    fn parses_references() {
+        assert_eq!(
+            Reference::parse("fetch.result.items.0.url"),
+            Ok(Reference {
+                task_name: "fetch".to_string(),
+                path: vec!["items".to_string(), "0".to_string(), "url".to_string()],
+            })
+        );
+        assert_eq!(Reference::parse("fetch.result").unwrap().path, Vec::<String>::new());
+        for malformed in ["", "fetch", ".result", "fetch.output.url", "fetch.result.", "fetch.result..url"] {
+            assert!(matches!(Reference::parse(malformed), Err(ReferenceError::Malformed(_))), "{:?}", malformed);
+        }
+    }
+
+    #[test]
+    fn finds_references_in_document_order() {
+        let parameters = json!({
+            "a": {"$ref": "fetch.result"},
+            "list": [1, {"$ref": "clean.result.rows"}],
+            "plain": {"$ref": "fetch.result", "other": 1},
+        });
+        let names: Vec<String> = find(&parameters).unwrap().into_iter().map(|r| r.task_name).collect();
+        assert_eq!(names, ["fetch", "clean"]);
+        assert!(matches!(find(&json!({"a": {"$ref": 42}})), Err(ReferenceError::Malformed(_))));
+    }
+
+    #[test]
+    fn resolves_whole_results_fields_and_array_items() {
+        let results = results(&[
+            ("fetch", Some(json!({"items": [{"url": "https://a"}, {"url": "https://b"}]}))),
+            ("count", Some(json!(7))),
+        ]);
+        let parameters = json!({
+            "url": {"$ref": "fetch.result.items.1.url"},
+            "nested": {"total": {"$ref": "count.result"}, "keep": "as is"},
+            "all": [{"$ref": "fetch.result.items.0"}],
+        });
+        assert_eq!(
+            resolve(&parameters, &results).unwrap(),
+            json!({
+                "url": "https://b",
+                "nested": {"total": 7, "keep": "as is"},
+                "all": [{"url": "https://a"}],
+            })
+        );
+    }
+
+    #[test]
+    fn reports_why_a_reference_cannot_be_resolved() {
+        let results = results(&[("fetch", Some(json!({"items": []}))), ("slow", None)]);
+        let resolve_one = |reference: &str| resolve(&json!({"x": {"$ref": reference}}), &results).unwrap_err();
+
+        assert!(matches!(
+            resolve_one("other.result"),
+            ReferenceError::NotADependency { task_name, .. } if task_name == "other"
+        ));
+        assert!(matches!(
+            resolve_one("slow.result"),
+            ReferenceError::Unfinished { task_name, .. } if task_name == "slow"
+        ));
+        assert!(matches!(
+            resolve_one("fetch.result.rows"),
+            ReferenceError::MissingField { field, .. } if field == "rows"
+        ));
+        assert!(matches!(
+            resolve_one("fetch.result.items.0"),
+            ReferenceError::MissingField { field, .. } if field == "0"
+        ));
+        assert!(matches!(
+            resolve_one("fetch.result.items.x"),
+            ReferenceError::MissingField { field, .. } if field == "x"
+        ));
+    }
+
+    #[test]
+    fn only_whole_offloaded_results_can_be_referenced() {
+        let offloaded = json!({BLOB_REF_KEY: "workflows/w/results/t"});
+        let results = results(&[("fetch", Some(offloaded.clone()))]);
+        assert_eq!(resolve(&json!({"$ref": "fetch.result"}), &results), Ok(offloaded));
+        assert!(matches!(
+            resolve(&json!({"$ref": "fetch.result.items"}), &results),
+            Err(ReferenceError::Offloaded { .. })
+        ));
+    }
+
+    #[test]
+    fn decodes_references_sent_as_strings() {
+        let mut parameters = json!({
+            "input": r#"{"$ref": "fetch.result.url"}"#,
+            "object": r#"{"key": "value"}"#,
+            "text": "{not json",
+            "plain": "fetch.result",
+        });
+        decode_string_references(&mut parameters);
+        assert_eq!(
+            parameters,
+            json!({
+                "input": {"$ref": "fetch.result.url"},
+                "object": r#"{"key": "value"}"#,
+                "text": "{not json",
+                "plain": "fetch.result",
+            })
+        );
+    }
+}
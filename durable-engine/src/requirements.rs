@@ -0,0 +1,59 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Labels advertised by the workers this engine runs tasks on, e.g.
+/// `gpu=true` or `pool=batch`.
+///
+/// A task only starts here if every one of its requirements is among these
+/// labels, so a heterogeneous fleet runs one engine per kind of worker and
+/// each claims just the tasks its workers can run. Tasks without
+/// requirements run anywhere.
+#[derive(Debug, Clone)]
+pub struct WorkerLabels {
+    /// The labels as a JSON object, for the `requirements <@ labels` check
+    labels: Value,
+}
+
+impl Default for WorkerLabels {
+    fn default() -> Self {
+        Self {
+            labels: Value::Object(serde_json::Map::new()),
+        }
+    }
+}
+
+impl WorkerLabels {
+    /// Read from `WORKER_LABELS`, e.g. `gpu=true,pool=batch`; unset means no
+    /// labels, so only tasks without requirements are claimed
+    pub fn from_env() -> Result<Self> {
+        let Ok(entries) = std::env::var("WORKER_LABELS") else {
+            return Ok(Self::default());
+        };
+
+        let mut labels = HashMap::new();
+        for entry in entries.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (key, value) = entry
+                .split_once('=')
+                .with_context(|| format!("WORKER_LABELS entry {} is not <label>=<value>", entry))?;
+            let key = key.trim();
+            if key.is_empty() {
+                anyhow::bail!("WORKER_LABELS entry {} has an empty label", entry);
+            }
+            labels.insert(key.to_string(), value.trim().to_string());
+        }
+
+        if !labels.is_empty() {
+            info!("Claiming tasks whose requirements match worker labels {:?}", labels);
+        }
+
+        Ok(Self {
+            labels: serde_json::to_value(labels)?,
+        })
+    }
+
+    pub fn as_json(&self) -> &Value {
+        &self.labels
+    }
+}
@@ -0,0 +1,73 @@
+use crate::database;
+use anyhow::Result;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How long deleted and finished workflows are kept before being purged
+#[derive(Debug, Clone, Copy)]
+pub struct RetentionConfig {
+    /// `None` keeps workflows forever
+    pub retention: Option<Duration>,
+    pub interval: Duration,
+    pub batch_size: i64,
+}
+
+impl RetentionConfig {
+    /// Read from `WORKFLOW_RETENTION_DAYS` (default 0, which never purges),
+    /// `RETENTION_PURGE_INTERVAL_SECS` (default 3600) and
+    /// `RETENTION_PURGE_BATCH_SIZE` (default 100); the last two must be positive
+    pub fn from_env() -> Result<Self> {
+        let retention_days: u64 = database::env_or("WORKFLOW_RETENTION_DAYS", 0)?;
+        let interval_secs: u64 = database::env_or("RETENTION_PURGE_INTERVAL_SECS", 3600)?;
+        let batch_size: i64 = database::env_or("RETENTION_PURGE_BATCH_SIZE", 100)?;
+
+        if interval_secs == 0 {
+            anyhow::bail!("RETENTION_PURGE_INTERVAL_SECS must be positive");
+        }
+        if batch_size <= 0 {
+            anyhow::bail!("RETENTION_PURGE_BATCH_SIZE must be positive");
+        }
+
+        Ok(Self {
+            retention: (retention_days > 0).then(|| Duration::from_secs(retention_days * 24 * 60 * 60)),
+            interval: Duration::from_secs(interval_secs),
+            batch_size,
+        })
+    }
+}
+
+/// Purge workflows past the retention period every `config.interval`, until
+/// the process exits. Returns straight away when retention is off.
+///
+/// Each pass removes batches until none are left, so a backlog built up
+/// while retention was off is worked through in one pass.
+pub async fn run_purge_loop(pool: PgPool, config: RetentionConfig) {
+    let Some(retention) = config.retention else {
+        info!("WORKFLOW_RETENTION_DAYS is unset, keeping deleted and finished workflows forever");
+        return;
+    };
+    info!(
+        "Purging workflows deleted or finished more than {:?} ago, every {:?}",
+        retention, config.interval
+    );
+
+    loop {
+        tokio::time::sleep(config.interval).await;
+
+        let mut purged = 0;
+        loop {
+            match database::purge_expired_workflows(&pool, retention, config.batch_size).await {
+                Ok(0) => break,
+                Ok(count) => purged += count,
+                Err(e) => {
+                    warn!("Workflow retention pass failed: {:?}", e);
+                    break;
+                }
+            }
+        }
+        if purged > 0 {
+            info!("Purged {} workflows past their retention period", purged);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::database;
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Caps how fast this engine retries failed tasks, across all workflows.
+///
+/// Every retry takes a token from a bucket that refills at a steady rate.
+/// While tokens remain a retry is requeued straight away, as it always has
+/// been. Once they run out each further retry is delayed until the bucket
+/// would have refilled a token for it, so a burst of failures against a
+/// struggling dependency is spread out at the refill rate instead of
+/// retrying all at once. Each engine keeps its own bucket.
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    /// `None` when the budget is turned off
+    bucket: Option<Arc<Mutex<Bucket>>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    /// Negative once retries have been handed out ahead of the refill, which
+    /// is how much later the next one has to wait
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RetryBudget {
+    /// Read the bucket size from `RETRY_BUDGET` (default 100; 0 turns the
+    /// budget off) and its refill rate from `RETRY_BUDGET_REFILL_PER_SEC`
+    /// (default 10), which must be positive
+    pub fn from_env() -> Result<Self> {
+        let capacity: u32 = database::env_or("RETRY_BUDGET", 100)?;
+        let refill_per_sec: f64 = database::env_or("RETRY_BUDGET_REFILL_PER_SEC", 10.0)?;
+        if capacity == 0 {
+            return Ok(Self::unlimited());
+        }
+        if !(refill_per_sec.is_finite() && refill_per_sec > 0.0) {
+            anyhow::bail!("RETRY_BUDGET_REFILL_PER_SEC must be positive");
+        }
+
+        info!(
+            "Retrying up to {} tasks at once, then {} per second",
+            capacity, refill_per_sec
+        );
+        Ok(Self {
+            bucket: Some(Arc::new(Mutex::new(Bucket {
+                capacity: f64::from(capacity),
+                refill_per_sec,
+                tokens: f64::from(capacity),
+                refilled_at: Instant::now(),
+            }))),
+        })
+    }
+
+    /// A budget that never delays a retry
+    pub fn unlimited() -> Self {
+        Self { bucket: None }
+    }
+
+    /// Take a token for one retry, returning how long it should wait before
+    /// running; zero while the budget has tokens left
+    pub fn acquire(&self) -> Duration {
+        let Some(bucket) = &self.bucket else {
+            return Duration::ZERO;
+        };
+        let mut bucket = bucket.lock().unwrap();
+
+        let now = Instant::now();
+        let refilled = now.duration_since(bucket.refilled_at).as_secs_f64() * bucket.refill_per_sec;
+        bucket.tokens = (bucket.tokens + refilled).min(bucket.capacity);
+        bucket.refilled_at = now;
+
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / bucket.refill_per_sec)
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use jsonschema::Validator;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::info;
+
+/// One way a task's parameters failed its type's schema
+#[derive(Debug, Clone)]
+pub struct SchemaViolation {
+    /// JSON pointer into the parameters, e.g. `/url`; empty for the root
+    pub path: String,
+    pub message: String,
+}
+
+/// JSON Schemas for task parameters, keyed by task type, and the task types
+/// tasks may be created with.
+///
+/// Task types without a registered schema accept any parameters. Without an
+/// allowlist every task type is accepted.
+#[derive(Default)]
+pub struct SchemaRegistry {
+    validators: HashMap<String, Validator>,
+    allowed_types: Option<HashSet<String>>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every `<task_type>.json` file in the directory named by
+    /// `TASK_SCHEMA_DIR`, and restrict task types to the comma-separated
+    /// `ALLOWED_TASK_TYPES`, e.g. `http,shell`. Either may be unset: no
+    /// schemas, or any task type allowed.
+    pub fn from_env() -> Result<Self> {
+        let mut registry = match std::env::var("TASK_SCHEMA_DIR") {
+            Ok(dir) => Self::from_dir(Path::new(&dir))?,
+            Err(_) => Self::new(),
+        };
+
+        if let Ok(types) = std::env::var("ALLOWED_TASK_TYPES") {
+            let allowed: HashSet<String> = types
+                .split(',')
+                .map(str::trim)
+                .filter(|task_type| !task_type.is_empty())
+                .map(str::to_string)
+                .collect();
+            if !allowed.is_empty() {
+                info!("Accepting only task types: {}", types);
+                registry.allowed_types = Some(allowed);
+            }
+        }
+
+        Ok(registry)
+    }
+
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let mut registry = Self::new();
+
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read schema directory {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(task_type) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read schema {}", path.display()))?;
+            let schema: Value = serde_json::from_str(&contents)
+                .with_context(|| format!("Schema {} is not valid JSON", path.display()))?;
+            registry.register(task_type, &schema)?;
+        }
+
+        info!("Loaded parameter schemas for {} task types", registry.validators.len());
+
+        Ok(registry)
+    }
+
+    /// Register a schema, replacing any existing one for the same task type
+    pub fn register(&mut self, task_type: &str, schema: &Value) -> Result<()> {
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|e| anyhow::anyhow!("Invalid schema for task type {}: {}", task_type, e))?;
+        self.validators.insert(task_type.to_string(), validator);
+        Ok(())
+    }
+
+    /// Whether tasks of this type may be created; always true without an allowlist
+    pub fn is_allowed(&self, task_type: &str) -> bool {
+        self.allowed_types
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(task_type))
+    }
+
+    /// Whether a schema is registered for `task_type`
+    pub fn knows(&self, task_type: &str) -> bool {
+        self.validators.contains_key(task_type)
+    }
+
+    /// Check parameters against the schema registered for `task_type`
+    pub fn validate(&self, task_type: &str, parameters: &Value) -> Result<(), Vec<SchemaViolation>> {
+        let Some(validator) = self.validators.get(task_type) else {
+            return Ok(());
+        };
+
+        let violations: Vec<SchemaViolation> = validator
+            .iter_errors(parameters)
+            .map(|error| SchemaViolation {
+                path: error.instance_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
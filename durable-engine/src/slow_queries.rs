@@ -0,0 +1,119 @@
+use crate::database;
+use anyhow::Result;
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{warn, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Prefix of the span names given to database queries, e.g. `db.get_task_by_id`
+pub const QUERY_SPAN_PREFIX: &str = "db.";
+
+/// Warns about database queries that take longer than a threshold.
+///
+/// Every query function in [`database`] runs in a `db.<operation>` span that
+/// records the rows it returned or changed as `db.rows`. This layer times
+/// those spans from creation to close, so the time includes waiting for a
+/// pool connection as well as the query itself.
+pub struct SlowQueryLayer {
+    threshold: Duration,
+}
+
+/// When a query span was created and the rows it has recorded so far
+struct QueryTiming {
+    started: Instant,
+    rows: Option<u64>,
+}
+
+impl SlowQueryLayer {
+    /// Read the threshold from `SLOW_QUERY_THRESHOLD_MS` (default 500); 0
+    /// turns the warnings off
+    pub fn from_env() -> Result<Self> {
+        let threshold_ms: u64 = database::env_or("SLOW_QUERY_THRESHOLD_MS", 500)?;
+        Ok(Self {
+            threshold: Duration::from_millis(threshold_ms),
+        })
+    }
+}
+
+impl<S> Layer<S> for SlowQueryLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if self.threshold.is_zero() || !attrs.metadata().name().starts_with(QUERY_SPAN_PREFIX) {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut timing = QueryTiming {
+            started: Instant::now(),
+            rows: None,
+        };
+        attrs.record(&mut timing);
+        span.extensions_mut().insert(timing);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<QueryTiming>() {
+            values.record(timing);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(timing) = extensions.get::<QueryTiming>() else {
+            return;
+        };
+
+        let elapsed = timing.started.elapsed();
+        if elapsed < self.threshold {
+            return;
+        }
+        let operation = span.name().trim_start_matches(QUERY_SPAN_PREFIX);
+        match timing.rows {
+            Some(rows) => warn!(
+                operation,
+                rows,
+                elapsed_ms = elapsed.as_millis() as u64,
+                "Slow database query: {} took {:?} for {} rows",
+                operation,
+                elapsed,
+                rows
+            ),
+            None => warn!(
+                operation,
+                elapsed_ms = elapsed.as_millis() as u64,
+                "Slow database query: {} took {:?}",
+                operation,
+                elapsed
+            ),
+        }
+    }
+}
+
+impl Visit for QueryTiming {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "db.rows" {
+            self.rows = Some(value);
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if field.name() == "db.rows" {
+            self.rows = u64::try_from(value).ok();
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
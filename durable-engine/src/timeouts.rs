@@ -0,0 +1,104 @@
+use crate::database;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+/// Timeout for tasks whose type has no configured default, matching the
+/// column default
+const DEFAULT_TIMEOUT_SECS: i32 = 3600;
+
+/// Default `timeout_seconds` per task type, for tasks created without one.
+///
+/// A quick HTTP call and a long batch job shouldn't share a timeout: one
+/// large enough for the batch job leaves a hung HTTP call running for hours.
+#[derive(Debug, Clone)]
+pub struct TimeoutRegistry {
+    default_secs: i32,
+    by_type: HashMap<String, i32>,
+}
+
+impl Default for TimeoutRegistry {
+    fn default() -> Self {
+        Self {
+            default_secs: DEFAULT_TIMEOUT_SECS,
+            by_type: HashMap::new(),
+        }
+    }
+}
+
+impl TimeoutRegistry {
+    /// Read the fallback from `DEFAULT_TASK_TIMEOUT_SECS` (default 3600), then
+    /// per-type defaults from the JSON object in the file named by
+    /// `TASK_TIMEOUTS_FILE`, e.g. `{"http": 30, "batch": 86400}`, then from
+    /// `TASK_TIMEOUTS`, e.g. `http=30,batch=86400`. Entries in `TASK_TIMEOUTS`
+    /// override the file's. Every timeout must be positive.
+    pub fn from_env() -> Result<Self> {
+        let default_secs: i32 = database::env_or("DEFAULT_TASK_TIMEOUT_SECS", DEFAULT_TIMEOUT_SECS)?;
+        if default_secs <= 0 {
+            anyhow::bail!("DEFAULT_TASK_TIMEOUT_SECS must be positive");
+        }
+        let mut registry = Self {
+            default_secs,
+            by_type: HashMap::new(),
+        };
+
+        if let Ok(path) = std::env::var("TASK_TIMEOUTS_FILE") {
+            registry.load_file(Path::new(&path))?;
+        }
+        if let Ok(entries) = std::env::var("TASK_TIMEOUTS") {
+            registry.load_list(&entries)?;
+        }
+
+        if !registry.by_type.is_empty() {
+            info!("Default timeouts by task type: {:?}", registry.by_type);
+        }
+
+        Ok(registry)
+    }
+
+    /// The timeout a new task of `task_type` gets when it asks for
+    /// `requested` seconds, 0 meaning unset
+    pub fn resolve(&self, task_type: &str, requested: i32) -> i32 {
+        if requested > 0 {
+            return requested;
+        }
+        self.by_type.get(task_type).copied().unwrap_or(self.default_secs)
+    }
+
+    fn load_file(&mut self, path: &Path) -> Result<()> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read task timeouts from {}", path.display()))?;
+        let timeouts: HashMap<String, i32> = serde_json::from_str(&contents)
+            .with_context(|| format!("{} is not a JSON object of task types to seconds", path.display()))?;
+
+        for (task_type, secs) in timeouts {
+            self.insert(task_type, secs)?;
+        }
+
+        Ok(())
+    }
+
+    fn load_list(&mut self, entries: &str) -> Result<()> {
+        for entry in entries.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (task_type, secs) = entry
+                .split_once('=')
+                .with_context(|| format!("TASK_TIMEOUTS entry {} is not <task_type>=<seconds>", entry))?;
+            let secs: i32 = secs
+                .trim()
+                .parse()
+                .with_context(|| format!("TASK_TIMEOUTS entry {} has an invalid number of seconds", entry))?;
+            self.insert(task_type.trim().to_string(), secs)?;
+        }
+
+        Ok(())
+    }
+
+    fn insert(&mut self, task_type: String, secs: i32) -> Result<()> {
+        if secs <= 0 {
+            anyhow::bail!("Timeout for task type {} must be positive, got {}", task_type, secs);
+        }
+        self.by_type.insert(task_type, secs);
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use crate::dag;
+use crate::engine::COLLECTOR_TASK_TYPE;
+use crate::references;
+use crate::schema::SchemaRegistry;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// How serious a validation issue is; only errors make a workflow invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "ERROR"),
+            Severity::Warning => write!(f, "WARNING"),
+        }
+    }
+}
+
+/// One problem found in a workflow definition
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// The task the issue is about; `None` for the workflow as a whole
+    pub task_name: Option<String>,
+    /// The offending field, e.g. `depends_on` or `parameters/url`
+    pub field: String,
+    pub message: String,
+}
+
+/// A task in a workflow that has not been created yet, referring to its
+/// dependencies by name
+#[derive(Debug, Clone)]
+pub struct TaskDefinition {
+    pub name: String,
+    pub task_type: String,
+    pub parameters: Value,
+    pub depends_on: Vec<String>,
+}
+
+/// Check a workflow definition without persisting anything.
+///
+/// Every problem found is reported rather than stopping at the first, in the
+/// order tasks were given.
+pub fn validate_workflow(name: &str, tasks: &[TaskDefinition], schemas: &SchemaRegistry) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    if name.trim().is_empty() {
+        issues.push(workflow_issue(Severity::Error, "name", "must not be empty"));
+    }
+    if tasks.is_empty() {
+        issues.push(workflow_issue(Severity::Warning, "tasks", "workflow has no tasks"));
+    }
+
+    let mut names = HashSet::new();
+    for task in tasks {
+        if task.name.trim().is_empty() {
+            issues.push(task_issue(task, Severity::Error, "name", "must not be empty"));
+        } else if !names.insert(task.name.as_str()) {
+            issues.push(task_issue(task, Severity::Error, "name", "duplicate task name"));
+        }
+    }
+
+    for task in tasks {
+        let refs = match references::find(&task.parameters) {
+            Ok(refs) => refs,
+            Err(e) => {
+                issues.push(task_issue(task, Severity::Error, "parameters", e.to_string()));
+                Vec::new()
+            }
+        };
+        for reference in &refs {
+            if !task.depends_on.contains(&reference.task_name) {
+                issues.push(task_issue(
+                    task,
+                    Severity::Error,
+                    "parameters",
+                    format!("reference to {} must name a task in depends_on", reference.task_name),
+                ));
+            }
+        }
+
+        if task.task_type.is_empty() {
+            issues.push(task_issue(task, Severity::Error, "task_type", "must not be empty"));
+        } else if !schemas.is_allowed(&task.task_type) {
+            issues.push(task_issue(
+                task,
+                Severity::Error,
+                "task_type",
+                format!("task type {} is not allowed on this cluster", task.task_type),
+            ));
+        } else if task.task_type == COLLECTOR_TASK_TYPE {
+            // Built in, so there is no schema; its parameters are checked when it runs
+        } else if !schemas.knows(&task.task_type) {
+            issues.push(task_issue(
+                task,
+                Severity::Warning,
+                "task_type",
+                format!("no schema registered for task type {}; parameters are not checked", task.task_type),
+            ));
+        } else if !refs.is_empty() {
+            issues.push(task_issue(
+                task,
+                Severity::Warning,
+                "parameters",
+                "parameters with references are checked against the schema when the task starts",
+            ));
+        } else if let Err(violations) = schemas.validate(&task.task_type, &task.parameters) {
+            for violation in violations {
+                issues.push(task_issue(
+                    task,
+                    Severity::Error,
+                    &format!("parameters{}", violation.path),
+                    violation.message,
+                ));
+            }
+        }
+
+        for dependency in &task.depends_on {
+            if !names.contains(dependency.as_str()) {
+                issues.push(task_issue(
+                    task,
+                    Severity::Error,
+                    "depends_on",
+                    format!("unknown task {}", dependency),
+                ));
+            }
+        }
+    }
+
+    let graph: Vec<(&str, Vec<&str>)> = tasks
+        .iter()
+        .map(|task| (task.name.as_str(), task.depends_on.iter().map(String::as_str).collect()))
+        .collect();
+    if let Err(cycle) = dag::topological_sort(&graph) {
+        issues.push(workflow_issue(
+            Severity::Error,
+            "depends_on",
+            format!("dependency cycle: {}", cycle),
+        ));
+    }
+
+    issues
+}
+
+fn workflow_issue(severity: Severity, field: &str, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue {
+        severity,
+        task_name: None,
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+fn task_issue(task: &TaskDefinition, severity: Severity, field: &str, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue {
+        severity,
+        task_name: Some(task.name.clone()),
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
@@ -0,0 +1,90 @@
+package main
+
+import (
+	"context"
+	"database/sql"
+	"time"
+)
+
+// taskEvent is one row of the durable engine's task_events table
+type taskEvent struct {
+	ID            string
+	TaskID        string
+	WorkflowID    string
+	EventType     string
+	PreviousState string
+	NewState      string
+	Timestamp     time.Time
+	Metadata      string
+}
+
+// exportCursor is the position of the last exported event. Timestamps are not
+// unique, so the event id breaks ties.
+type exportCursor struct {
+	Timestamp time.Time
+	EventID   string
+}
+
+const (
+	exportBatchSize    = 500
+	exportPollInterval = time.Second
+)
+
+// tailTaskEvents sends every event after the cursor in (timestamp, id) order,
+// then keeps polling for new events until ctx is cancelled or send fails.
+// It backs the ExportTaskEvents server stream.
+func tailTaskEvents(ctx context.Context, db *sql.DB, cursor exportCursor, send func(taskEvent) error) error {
+	ticker := time.NewTicker(exportPollInterval)
+	defer ticker.Stop()
+
+	for {
+		sent, err := exportBatch(ctx, db, &cursor, send)
+		if err != nil {
+			return err
+		}
+		if sent == exportBatchSize {
+			// More events are waiting, keep draining without sleeping
+			continue
+		}
+
+		select {
+		case <-ctx.Done():
+			return ctx.Err()
+		case <-ticker.C:
+		}
+	}
+}
+
+func exportBatch(ctx context.Context, db *sql.DB, cursor *exportCursor, send func(taskEvent) error) (int, error) {
+	rows, err := db.QueryContext(ctx, `
+		SELECT id::text, task_id::text, workflow_id::text, event_type,
+		       COALESCE(previous_state::text, ''), new_state::text, timestamp,
+		       COALESCE(metadata::text, '')
+		FROM task_events
+		WHERE (timestamp, id::text) > ($1, $2)
+		ORDER BY timestamp, id::text
+		LIMIT $3`,
+		cursor.Timestamp, cursor.EventID, exportBatchSize)
+	if err != nil {
+		return 0, err
+	}
+	defer rows.Close()
+
+	sent := 0
+	for rows.Next() {
+		var event taskEvent
+		if err := rows.Scan(&event.ID, &event.TaskID, &event.WorkflowID, &event.EventType,
+			&event.PreviousState, &event.NewState, &event.Timestamp, &event.Metadata); err != nil {
+			return sent, err
+		}
+		if err := send(event); err != nil {
+			return sent, err
+		}
+
+		cursor.Timestamp = event.Timestamp
+		cursor.EventID = event.ID
+		sent++
+	}
+
+	return sent, rows.Err()
+}
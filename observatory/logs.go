@@ -0,0 +1,144 @@
+package main
+
+import (
+	"context"
+	"sync"
+	"time"
+)
+
+// logLine is one line of output a worker pushed for a task
+type logLine struct {
+	TaskID string
+	// Numbered from 1 per task, so a follower can tell when it missed lines
+	Sequence  int64
+	Timestamp time.Time
+	Message   string
+}
+
+const (
+	// Oldest lines of a task are dropped beyond this
+	maxLogLinesPerTask = 10000
+	// Tasks whose logs are kept; the one logged to least recently is dropped first
+	maxLoggedTasks = 1000
+	// Lines a follower may fall behind by before it starts missing them
+	followerBuffer = 256
+)
+
+// taskLog is what is kept for one task
+type taskLog struct {
+	lines     []logLine
+	next      int64
+	updatedAt time.Time
+	followers map[chan logLine]struct{}
+}
+
+// taskLogs keeps recent log lines per task in memory and hands new lines to
+// followers as they arrive. It backs the PushTaskLogs and StreamTaskLogs RPCs.
+type taskLogs struct {
+	mu    sync.Mutex
+	tasks map[string]*taskLog
+}
+
+func newTaskLogs() *taskLogs {
+	return &taskLogs{tasks: make(map[string]*taskLog)}
+}
+
+// append numbers and stores a line for taskID and sends it to the task's
+// followers. A follower that isn't keeping up misses the line rather than
+// holding up the worker.
+func (l *taskLogs) append(taskID string, timestamp time.Time, message string) logLine {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+
+	log := l.taskLocked(taskID)
+	log.next++
+	line := logLine{TaskID: taskID, Sequence: log.next, Timestamp: timestamp, Message: message}
+
+	log.lines = append(log.lines, line)
+	if len(log.lines) > maxLogLinesPerTask {
+		log.lines = log.lines[len(log.lines)-maxLogLinesPerTask:]
+	}
+	log.updatedAt = time.Now()
+
+	for follower := range log.followers {
+		select {
+		case follower <- line:
+		default:
+		}
+	}
+	logsReceived.Inc()
+
+	return line
+}
+
+// follow returns the lines kept so far when fromStart is set, and a channel
+// of every line appended from now on. cancel must be called once the caller
+// stops reading.
+func (l *taskLogs) follow(taskID string, fromStart bool) (backlog []logLine, lines <-chan logLine, cancel func()) {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+
+	log := l.taskLocked(taskID)
+	if fromStart {
+		backlog = append([]logLine(nil), log.lines...)
+	}
+	follower := make(chan logLine, followerBuffer)
+	log.followers[follower] = struct{}{}
+
+	cancel = func() {
+		l.mu.Lock()
+		defer l.mu.Unlock()
+		delete(log.followers, follower)
+	}
+	return backlog, follower, cancel
+}
+
+// taskLocked returns the log of taskID, creating it and dropping the least
+// recently updated task without followers if too many are kept
+func (l *taskLogs) taskLocked(taskID string) *taskLog {
+	if log, ok := l.tasks[taskID]; ok {
+		return log
+	}
+
+	if len(l.tasks) >= maxLoggedTasks {
+		var oldestID string
+		var oldest time.Time
+		for id, log := range l.tasks {
+			if len(log.followers) == 0 && (oldestID == "" || log.updatedAt.Before(oldest)) {
+				oldestID, oldest = id, log.updatedAt
+			}
+		}
+		if oldestID != "" {
+			delete(l.tasks, oldestID)
+		}
+	}
+
+	log := &taskLog{updatedAt: time.Now(), followers: make(map[chan logLine]struct{})}
+	l.tasks[taskID] = log
+	return log
+}
+
+// tailTaskLogs sends a task's kept lines when fromStart is set, then every new
+// line as it arrives, until ctx is cancelled or send fails. It backs the
+// StreamTaskLogs server stream.
+func tailTaskLogs(ctx context.Context, logs *taskLogs, taskID string, fromStart bool, send func(logLine) error) error {
+	backlog, lines, cancel := logs.follow(taskID, fromStart)
+	defer cancel()
+
+	for _, line := range backlog {
+		if err := send(line); err != nil {
+			return err
+		}
+	}
+
+	for {
+		select {
+		case <-ctx.Done():
+			return ctx.Err()
+		case line := <-lines:
+			if err := send(line); err != nil {
+				return err
+			}
+		}
+	}
+}
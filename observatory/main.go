@@ -129,7 +129,9 @@ func main() {
 	}
 	
 	grpcServer := grpc.NewServer()
-	// In a real implementation, this would register the observatory service
+	// In a real implementation, this would register the observatory service,
+	// whose ExportTaskEvents stream is served by tailTaskEvents, PushTaskLogs
+	// appends to taskLogs and StreamTaskLogs is served by tailTaskLogs
 	
 	// Start gRPC server in a goroutine
 	go func() {
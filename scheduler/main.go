@@ -99,6 +99,12 @@ func main() {
 	// Create a new cron scheduler
 	c := cron.New(cron.WithSeconds())
 	
+	// Recurring workflow triggers; each occurrence starts a fresh workflow run
+	schedules := newWorkflowSchedules(c, func(workflowID string) {
+		log.Printf("Triggering scheduled run of workflow %s", workflowID)
+		scheduledWorkflows.Inc()
+	})
+	
 	// Start the cron scheduler
 	c.Start()
 	defer c.Stop()
@@ -112,7 +118,8 @@ func main() {
 	
 	grpcServer := grpc.NewServer()
 	// Register the scheduler service (implementation would be in a separate file)
-	// scheduler.RegisterSchedulerServiceServer(grpcServer, &schedulerServer{})
+	// scheduler.RegisterSchedulerServiceServer(grpcServer, &schedulerServer{schedules: schedules})
+	_ = schedules
 	
 	// Start gRPC server in a goroutine
 	go func() {
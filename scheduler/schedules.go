@@ -0,0 +1,78 @@
+package main
+
+import (
+	"fmt"
+	"sync"
+	"time"
+
+	"github.com/robfig/cron/v3"
+)
+
+// workflowSchedules keeps one recurring cron trigger per workflow
+type workflowSchedules struct {
+	mu      sync.Mutex
+	cron    *cron.Cron
+	entries map[string]cron.EntryID
+	trigger func(workflowID string)
+}
+
+func newWorkflowSchedules(c *cron.Cron, trigger func(workflowID string)) *workflowSchedules {
+	return &workflowSchedules{
+		cron:    c,
+		entries: make(map[string]cron.EntryID),
+		trigger: trigger,
+	}
+}
+
+// Schedule registers (or replaces) the recurring trigger for a workflow.
+// The expression uses six fields, starting with seconds, and is evaluated
+// in the given IANA timezone. It returns the next fire time.
+func (s *workflowSchedules) Schedule(workflowID, cronExpr, timezone string) (time.Time, error) {
+	loc, err := time.LoadLocation(timezone)
+	if err != nil {
+		return time.Time{}, fmt.Errorf("invalid timezone %q: %w", timezone, err)
+	}
+
+	parser := cron.NewParser(cron.Second | cron.Minute | cron.Hour | cron.Dom | cron.Month | cron.Dow | cron.Descriptor)
+	schedule, err := parser.Parse(cronExpr)
+	if err != nil {
+		return time.Time{}, fmt.Errorf("invalid cron expression %q: %w", cronExpr, err)
+	}
+
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	if id, ok := s.entries[workflowID]; ok {
+		s.cron.Remove(id)
+	}
+
+	id := s.cron.Schedule(inLocation{schedule, loc}, cron.FuncJob(func() {
+		s.trigger(workflowID)
+	}))
+	s.entries[workflowID] = id
+
+	return schedule.Next(time.Now().In(loc)), nil
+}
+
+// Unschedule removes the workflow's trigger, reporting whether one existed
+func (s *workflowSchedules) Unschedule(workflowID string) bool {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	id, ok := s.entries[workflowID]
+	if ok {
+		s.cron.Remove(id)
+		delete(s.entries, workflowID)
+	}
+	return ok
+}
+
+// inLocation evaluates a schedule in a fixed timezone regardless of the cron's own location
+type inLocation struct {
+	schedule cron.Schedule
+	loc      *time.Location
+}
+
+func (s inLocation) Next(t time.Time) time.Time {
+	return s.schedule.Next(t.In(s.loc))
+}
@@ -16,6 +16,10 @@ echo -e "${YELLOW}Building Project Chronos...${NC}"
 ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
 cd "$ROOT_DIR"
 
+# Compile the durable engine's queries against its committed .sqlx metadata
+# rather than a live database, unless the caller says otherwise
+export SQLX_OFFLINE="${SQLX_OFFLINE:-true}"
+
 # Build Go services
 build_go_service() {
   local service=$1
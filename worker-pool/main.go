@@ -8,6 +8,7 @@ import (
 	"net/http"
 	"os"
 	"os/signal"
+	"strings"
 	"sync"
 	"syscall"
 	"time"
@@ -52,6 +53,9 @@ var (
 type Worker struct {
 	ID          string
 	TaskTypes   []string
+	// Labels advertised to the engine; a task only runs here if all of its
+	// requirements are among them
+	Labels      map[string]string
 	Capacity    int
 	CurrentLoad int
 	ActiveTasks map[string]struct{}
@@ -75,6 +79,7 @@ func init() {
 	viper.SetDefault("PORT", "8082")
 	viper.SetDefault("DURABLE_ENGINE_URL", "localhost:50051")
 	viper.SetDefault("WORKER_COUNT", 5)
+	viper.SetDefault("WORKER_LABELS", "")
 	viper.SetDefault("OTLP_ENDPOINT", "localhost:4317")
 	
 	viper.AutomaticEnv()
@@ -110,8 +115,39 @@ func initTracer() (*sdktrace.TracerProvider, error) {
 	return provider, nil
 }
 
+// parseLabels reads labels written as "gpu=true,pool=batch"
+func parseLabels(entries string) (map[string]string, error) {
+	labels := make(map[string]string)
+	for _, entry := range strings.Split(entries, ",") {
+		entry = strings.TrimSpace(entry)
+		if entry == "" {
+			continue
+		}
+		key, value, ok := strings.Cut(entry, "=")
+		if !ok || strings.TrimSpace(key) == "" {
+			return nil, fmt.Errorf("label %q is not <label>=<value>", entry)
+		}
+		labels[strings.TrimSpace(key)] = strings.TrimSpace(value)
+	}
+	return labels, nil
+}
+
+// CanRun reports whether the worker advertises every label a task requires
+func (w *Worker) CanRun(requirements map[string]string) bool {
+	for key, value := range requirements {
+		if w.Labels[key] != value {
+			return false
+		}
+	}
+	return true
+}
+
 func createWorkerPool() *WorkerPool {
 	workerCount := viper.GetInt("WORKER_COUNT")
+	labels, err := parseLabels(viper.GetString("WORKER_LABELS"))
+	if err != nil {
+		log.Fatalf("Invalid WORKER_LABELS: %v", err)
+	}
 	pool := &WorkerPool{
 		Workers: make(map[string]*Worker),
 	}
@@ -121,6 +157,7 @@ func createWorkerPool() *WorkerPool {
 		worker := &Worker{
 			ID:          workerID,
 			TaskTypes:   []string{"http", "process", "database", "file"},
+			Labels:      labels,
 			Capacity:    10,
 			CurrentLoad: 0,
 			ActiveTasks: make(map[string]struct{}),
@@ -229,9 +266,16 @@ func pollForTasks(ctx context.Context, worker *Worker) {
 	
 	// In a real implementation, this would:
 	// 1. Connect to the Durable Engine via gRPC
-	// 2. Poll for available tasks
-	// 3. Execute tasks and report results
+	// 2. Poll for available tasks, skipping any the worker can't run
+	//    (CanRun is false for the task's requirements)
+	// 3. Execute tasks and report results, calling Heartbeat for each running
+	//    task well within the engine's HEARTBEAT_TIMEOUT_SECS and abandoning
+	//    any task whose heartbeat response is no longer RUNNING. A task still
+	//    running after its timeout_seconds is abandoned and reported with
+	//    FailTask(timed_out=true)
 	// 4. Update metrics
+	// 5. Watch the control topic (KAFKA_CONTROL_TOPIC) and abort any task in
+	//    ActiveTasks that receives a CANCEL_TASK message, reporting it as cancelled
 	
 	ticker := time.NewTicker(5 * time.Second)
 	defer ticker.Stop()